@@ -0,0 +1,63 @@
+-- Migration 007: Index tag names for items
+-- Колонка search_index.tags для айтемов всегда была пустой, теперь в ней имена тегов через пробел
+
+-- 1. Привязка и отвязка тегов пересчитывает теги айтема в индексе
+DROP TRIGGER IF EXISTS item_tags_ai;
+CREATE TRIGGER item_tags_ai AFTER INSERT ON item_tags BEGIN
+    UPDATE search_index
+    SET tags = COALESCE((
+        SELECT group_concat(t.name, ' ')
+        FROM item_tags it
+        JOIN tags t ON t.id = it.tag_id
+        WHERE it.item_id = new.item_id
+    ), '')
+    WHERE rowid = new.item_id;
+END;
+
+DROP TRIGGER IF EXISTS item_tags_ad;
+CREATE TRIGGER item_tags_ad AFTER DELETE ON item_tags BEGIN
+    UPDATE search_index
+    SET tags = COALESCE((
+        SELECT group_concat(t.name, ' ')
+        FROM item_tags it
+        JOIN tags t ON t.id = it.tag_id
+        WHERE it.item_id = old.item_id
+    ), '')
+    WHERE rowid = old.item_id;
+END;
+
+-- 2. Переименование тега обновляет все айтемы с этим тегом
+DROP TRIGGER IF EXISTS tags_au;
+CREATE TRIGGER tags_au AFTER UPDATE OF name ON tags BEGIN
+    UPDATE search_index
+    SET tags = COALESCE((
+        SELECT group_concat(t.name, ' ')
+        FROM item_tags it
+        JOIN tags t ON t.id = it.tag_id
+        WHERE it.item_id = search_index.rowid
+    ), '')
+    WHERE rowid IN (SELECT item_id FROM item_tags WHERE tag_id = new.id);
+END;
+
+-- 3. Удаление тега: связи удаляются каскадно, но пересчитываем явно без удаляемого тега
+DROP TRIGGER IF EXISTS tags_bd;
+CREATE TRIGGER tags_bd BEFORE DELETE ON tags BEGIN
+    UPDATE search_index
+    SET tags = COALESCE((
+        SELECT group_concat(t.name, ' ')
+        FROM item_tags it
+        JOIN tags t ON t.id = it.tag_id
+        WHERE it.item_id = search_index.rowid AND t.id != old.id
+    ), '')
+    WHERE rowid IN (SELECT item_id FROM item_tags WHERE tag_id = old.id);
+END;
+
+-- 4. Заполняем теги для существующих айтемов
+UPDATE search_index
+SET tags = COALESCE((
+    SELECT group_concat(t.name, ' ')
+    FROM item_tags it
+    JOIN tags t ON t.id = it.tag_id
+    WHERE it.item_id = search_index.rowid
+), '')
+WHERE rowid > 0;
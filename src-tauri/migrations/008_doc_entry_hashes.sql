@@ -0,0 +1,13 @@
+-- Migration 008: Incremental documentation updates
+-- Колонка doc_entries.content_hash добавляется в storage.rs, здесь только триггер
+
+-- 1. Индекс обновляется только при изменении индексируемых полей,
+--    запись одного content_hash не трогает search_index
+DROP TRIGGER IF EXISTS doc_entries_au;
+CREATE TRIGGER doc_entries_au AFTER UPDATE OF doc_id, title, content ON doc_entries BEGIN
+    UPDATE search_index
+    SET title = new.title,
+        content = new.content,
+        tags = COALESCE((SELECT display_name FROM documentations WHERE id = new.doc_id), '')
+    WHERE rowid = -new.id;
+END;
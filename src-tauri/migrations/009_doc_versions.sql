@@ -0,0 +1,50 @@
+-- Migration 009: Multiple installed versions of the same documentation
+-- Выполняется из storage.rs один раз, пока у documentations нет колонки is_active,
+-- с выключенными foreign keys (иначе DROP TABLE каскадно удалит doc_entries)
+
+-- 1. Триггеры doc_entries ссылаются на documentations и мешают переименованию таблицы
+DROP TRIGGER IF EXISTS doc_entries_ai;
+DROP TRIGGER IF EXISTS doc_entries_au;
+
+-- 2. Пересоздаём таблицу: уникальна пара (name, version), активна одна версия на name
+CREATE TABLE documentations_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    version TEXT NOT NULL,
+    source_url TEXT NOT NULL,
+    installed_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    metadata TEXT DEFAULT '{}', -- JSON
+    is_active INTEGER NOT NULL DEFAULT 1,
+    UNIQUE(name, version)
+);
+
+INSERT INTO documentations_new (id, name, display_name, version, source_url, installed_at, updated_at, metadata, is_active)
+SELECT id, name, display_name, version, source_url, installed_at, updated_at, metadata, 1
+FROM documentations;
+
+DROP TABLE documentations;
+ALTER TABLE documentations_new RENAME TO documentations;
+
+CREATE INDEX IF NOT EXISTS idx_docs_name ON documentations(name);
+CREATE INDEX IF NOT EXISTS idx_docs_updated ON documentations(updated_at DESC);
+
+-- 3. Возвращаем триггеры в состоянии миграции 008
+CREATE TRIGGER doc_entries_ai AFTER INSERT ON doc_entries BEGIN
+    INSERT INTO search_index(rowid, title, content, tags)
+    VALUES (
+        -new.id,
+        new.title,
+        new.content,
+        COALESCE((SELECT display_name FROM documentations WHERE id = new.doc_id), '')
+    );
+END;
+
+CREATE TRIGGER doc_entries_au AFTER UPDATE OF doc_id, title, content ON doc_entries BEGIN
+    UPDATE search_index
+    SET title = new.title,
+        content = new.content,
+        tags = COALESCE((SELECT display_name FROM documentations WHERE id = new.doc_id), '')
+    WHERE rowid = -new.id;
+END;
@@ -0,0 +1,37 @@
+-- Migration 010: Resumable documentation installs
+-- Прогресс обхода сохраняется по ходу установки, прерванная установка продолжается с места остановки
+
+-- Незавершённые установки: status = 'running' | 'cancelled' | 'failed'
+-- ('running' после падения приложения тоже означает прерванную установку)
+CREATE TABLE IF NOT EXISTS doc_install_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    version TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running',
+    started_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE(name, version)
+);
+
+-- Фронтир обхода: visited = 0 ещё в очереди (порядок по rowid), 1 уже скачан
+CREATE TABLE IF NOT EXISTS doc_install_frontier (
+    job_id INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    depth INTEGER NOT NULL DEFAULT 0,
+    visited INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (job_id) REFERENCES doc_install_jobs(id) ON DELETE CASCADE,
+    PRIMARY KEY (job_id, path)
+);
+
+-- Уже разобранные записи, попадают в doc_entries только после завершения установки
+CREATE TABLE IF NOT EXISTS doc_install_entries (
+    job_id INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    entry_type TEXT,
+    parent_path TEXT,
+    FOREIGN KEY (job_id) REFERENCES doc_install_jobs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_install_entries_job ON doc_install_entries(job_id);
@@ -0,0 +1,3 @@
+-- Migration 011: Search scoped to a documentation and a section
+-- Фильтр docIds + pathPrefix идёт по диапазону path внутри одной документации
+CREATE INDEX IF NOT EXISTS idx_entries_doc_path ON doc_entries(doc_id, path);
@@ -0,0 +1,136 @@
+-- Migration 012: Separate FTS indexes for items and documentation
+-- В общем search_index айтемы (rowid > 0) и записи документации (rowid < 0) ранжировались вместе:
+-- большая документация забивала bm25 личных сниппетов, а поиск по айтемам сканировал строки доков.
+-- Теперь у каждого источника свой индекс, rowid совпадает с id строки в исходной таблице.
+-- Миграция применяется один раз, после 004 и 007: она заменяет их триггеры своими
+-- и удаляет старый search_index. DROP ... IF EXISTS нужны для баз, созданных до
+-- schema_migrations: они проходят все миграции заново поверх уже готовой схемы.
+
+-- 1. Индексы: title, content, tags (имена тегов айтема / название документации)
+CREATE VIRTUAL TABLE IF NOT EXISTS items_fts USING fts5(
+    title,
+    content,
+    tags,
+    tokenize='porter unicode61'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS docs_fts USING fts5(
+    title,
+    content,
+    tags,
+    tokenize='porter unicode61'
+);
+
+-- 2. Триггеры items
+DROP TRIGGER IF EXISTS items_ai;
+CREATE TRIGGER items_ai AFTER INSERT ON items BEGIN
+    INSERT INTO items_fts(rowid, title, content, tags)
+    VALUES (new.id, new.title, new.content, '');
+END;
+
+DROP TRIGGER IF EXISTS items_ad;
+CREATE TRIGGER items_ad AFTER DELETE ON items BEGIN
+    DELETE FROM items_fts WHERE rowid = old.id;
+END;
+
+DROP TRIGGER IF EXISTS items_au;
+CREATE TRIGGER items_au AFTER UPDATE OF title, content ON items BEGIN
+    UPDATE items_fts
+    SET title = new.title, content = new.content
+    WHERE rowid = new.id;
+END;
+
+-- 3. Теги айтемов (как в миграции 007)
+DROP TRIGGER IF EXISTS item_tags_ai;
+CREATE TRIGGER item_tags_ai AFTER INSERT ON item_tags BEGIN
+    UPDATE items_fts
+    SET tags = COALESCE((
+        SELECT group_concat(t.name, ' ')
+        FROM item_tags it
+        JOIN tags t ON t.id = it.tag_id
+        WHERE it.item_id = new.item_id
+    ), '')
+    WHERE rowid = new.item_id;
+END;
+
+DROP TRIGGER IF EXISTS item_tags_ad;
+CREATE TRIGGER item_tags_ad AFTER DELETE ON item_tags BEGIN
+    UPDATE items_fts
+    SET tags = COALESCE((
+        SELECT group_concat(t.name, ' ')
+        FROM item_tags it
+        JOIN tags t ON t.id = it.tag_id
+        WHERE it.item_id = old.item_id
+    ), '')
+    WHERE rowid = old.item_id;
+END;
+
+DROP TRIGGER IF EXISTS tags_au;
+CREATE TRIGGER tags_au AFTER UPDATE OF name ON tags BEGIN
+    UPDATE items_fts
+    SET tags = COALESCE((
+        SELECT group_concat(t.name, ' ')
+        FROM item_tags it
+        JOIN tags t ON t.id = it.tag_id
+        WHERE it.item_id = items_fts.rowid
+    ), '')
+    WHERE rowid IN (SELECT item_id FROM item_tags WHERE tag_id = new.id);
+END;
+
+DROP TRIGGER IF EXISTS tags_bd;
+CREATE TRIGGER tags_bd BEFORE DELETE ON tags BEGIN
+    UPDATE items_fts
+    SET tags = COALESCE((
+        SELECT group_concat(t.name, ' ')
+        FROM item_tags it
+        JOIN tags t ON t.id = it.tag_id
+        WHERE it.item_id = items_fts.rowid AND t.id != old.id
+    ), '')
+    WHERE rowid IN (SELECT item_id FROM item_tags WHERE tag_id = old.id);
+END;
+
+-- 4. Триггеры doc_entries (как в миграции 008)
+DROP TRIGGER IF EXISTS doc_entries_ai;
+CREATE TRIGGER doc_entries_ai AFTER INSERT ON doc_entries BEGIN
+    INSERT INTO docs_fts(rowid, title, content, tags)
+    VALUES (
+        new.id,
+        new.title,
+        new.content,
+        COALESCE((SELECT display_name FROM documentations WHERE id = new.doc_id), '')
+    );
+END;
+
+DROP TRIGGER IF EXISTS doc_entries_ad;
+CREATE TRIGGER doc_entries_ad AFTER DELETE ON doc_entries BEGIN
+    DELETE FROM docs_fts WHERE rowid = old.id;
+END;
+
+DROP TRIGGER IF EXISTS doc_entries_au;
+CREATE TRIGGER doc_entries_au AFTER UPDATE OF doc_id, title, content ON doc_entries BEGIN
+    UPDATE docs_fts
+    SET title = new.title,
+        content = new.content,
+        tags = COALESCE((SELECT display_name FROM documentations WHERE id = new.doc_id), '')
+    WHERE rowid = new.id;
+END;
+
+-- 5. Переносим строки, которых ещё нет в новых индексах
+INSERT INTO items_fts(rowid, title, content, tags)
+SELECT i.id, i.title, i.content, COALESCE((
+    SELECT group_concat(t.name, ' ')
+    FROM item_tags it
+    JOIN tags t ON t.id = it.tag_id
+    WHERE it.item_id = i.id
+), '')
+FROM items i
+WHERE i.id NOT IN (SELECT rowid FROM items_fts);
+
+INSERT INTO docs_fts(rowid, title, content, tags)
+SELECT de.id, de.title, de.content, d.display_name
+FROM doc_entries de
+JOIN documentations d ON de.doc_id = d.id
+WHERE de.id NOT IN (SELECT rowid FROM docs_fts);
+
+-- 6. Старый общий индекс больше не нужен
+DROP TABLE IF EXISTS search_index;
@@ -0,0 +1,37 @@
+-- Migration 013: Substring and typo-tolerant search
+-- items_trigram находит подстроки внутри идентификаторов (State в useState),
+-- словари fts5vocab дают список термов для исправления опечаток (kubctl -> kubectl)
+
+-- 1. Триграммный индекс только для айтемов: документация слишком большая для него
+CREATE VIRTUAL TABLE IF NOT EXISTS items_trigram USING fts5(
+    title,
+    content,
+    tokenize='trigram'
+);
+
+DROP TRIGGER IF EXISTS items_trigram_ai;
+CREATE TRIGGER items_trigram_ai AFTER INSERT ON items BEGIN
+    INSERT INTO items_trigram(rowid, title, content)
+    VALUES (new.id, new.title, new.content);
+END;
+
+DROP TRIGGER IF EXISTS items_trigram_ad;
+CREATE TRIGGER items_trigram_ad AFTER DELETE ON items BEGIN
+    DELETE FROM items_trigram WHERE rowid = old.id;
+END;
+
+DROP TRIGGER IF EXISTS items_trigram_au;
+CREATE TRIGGER items_trigram_au AFTER UPDATE OF title, content ON items BEGIN
+    UPDATE items_trigram
+    SET title = new.title, content = new.content
+    WHERE rowid = new.id;
+END;
+
+INSERT INTO items_trigram(rowid, title, content)
+SELECT id, title, content
+FROM items
+WHERE id NOT IN (SELECT rowid FROM items_trigram);
+
+-- 2. Словари термов основных индексов (term, doc = число строк, cnt = число вхождений)
+CREATE VIRTUAL TABLE IF NOT EXISTS items_vocab USING fts5vocab(items_fts, 'row');
+CREATE VIRTUAL TABLE IF NOT EXISTS docs_vocab USING fts5vocab(docs_fts, 'row');
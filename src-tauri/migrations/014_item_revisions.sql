@@ -0,0 +1,17 @@
+-- Migration 014: Item revision history
+-- Перед каждым изменением title/description/content/metadata прежняя версия айтема
+-- сохраняется сюда, чтобы автосохранение не затирало её безвозвратно
+CREATE TABLE IF NOT EXISTS item_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    description TEXT,
+    content TEXT NOT NULL,
+    metadata TEXT,
+    -- updated_at айтема, когда эта версия была актуальной
+    saved_at INTEGER NOT NULL,
+    -- когда версию вытеснило изменение
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_item_revisions_item ON item_revisions(item_id, id);
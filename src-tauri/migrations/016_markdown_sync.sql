@@ -0,0 +1,14 @@
+-- Migration 016: Markdown folder sync
+-- Заметка и её .md-файл в синхронизируемой папке. Хэши заметки (в виде Markdown) и текста
+-- файла на момент последней синхронизации показывают, какая сторона изменилась с тех пор
+CREATE TABLE IF NOT EXISTS markdown_sync_files (
+    directory TEXT NOT NULL,
+    item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    -- путь относительно папки, через /
+    path TEXT NOT NULL,
+    -- SHA-256
+    note_hash TEXT NOT NULL,
+    file_hash TEXT NOT NULL,
+    PRIMARY KEY (directory, item_id),
+    UNIQUE (directory, path)
+);
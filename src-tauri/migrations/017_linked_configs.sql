@@ -0,0 +1,12 @@
+-- Migration 017: Config items linked to a file
+-- Привязка конфига к файлу на диске и хэш текста файла, с которым айтем совпадал в последний
+-- раз. Привязки создаются только командой link_config_file, а не метаданными, которые
+-- можно менять через MCP или импорт
+CREATE TABLE IF NOT EXISTS linked_config_files (
+    item_id INTEGER PRIMARY KEY REFERENCES items(id) ON DELETE CASCADE,
+    -- как ввел пользователь, ~ не раскрыт
+    path TEXT NOT NULL,
+    -- SHA-256
+    source_hash TEXT NOT NULL
+);
+
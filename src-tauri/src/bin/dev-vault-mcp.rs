@@ -1,14 +1,46 @@
-use dev_vault_lib::domain::{DocumentationManager, SearchEngine, Storage};
-use dev_vault_lib::mcp::{default_db_path, MCP_SERVER_NAME};
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use dev_vault_lib::domain::{ConfigManager, DocumentationManager, SearchEngine, Storage};
+use dev_vault_lib::mcp::{default_db_path, ResourceUri, MCP_SERVER_NAME};
+use dev_vault_lib::models::config::AppConfig;
 use dev_vault_lib::models::{
-    DocEntry, DocTreeNode, Documentation, ItemType, ItemWithTags, SearchQuery,
+    CreateItemDto, DocEntry, DocTreeNode, Documentation, ItemType, ItemWithTags, SearchQuery,
+    UpdateItemDto,
 };
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::io::{self};
-use std::path::PathBuf;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+/// Newest first, the first one is offered when the client asks for an unknown version
+const SUPPORTED_PROTOCOL_VERSIONS: [&str; 2] = ["2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const INTERNAL_ERROR: i32 = -32603;
+/// MCP specific: `resources/read` for a URI that does not exist
+const RESOURCE_NOT_FOUND: i32 = -32002;
+
+/// The single endpoint of the Streamable HTTP transport
+const MCP_HTTP_PATH: &str = "/mcp";
+const SESSION_HEADER: &str = "mcp-session-id";
+const TOKEN_ENV: &str = "DEV_VAULT_MCP_TOKEN";
 
 #[derive(Debug, Deserialize)]
 struct RpcRequest {
@@ -18,6 +50,18 @@ struct RpcRequest {
     params: Option<Value>,
 }
 
+#[derive(Deserialize)]
+struct InitializeParams {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CancelledParams {
+    #[serde(rename = "requestId")]
+    request_id: Value,
+}
+
 #[derive(Serialize)]
 struct RpcResponse {
     jsonrpc: &'static str,
@@ -28,12 +72,29 @@ struct RpcResponse {
     error: Option<RpcError>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
 struct RpcError {
     code: i32,
     message: String,
 }
 
+impl RpcError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+
+    fn internal(err: impl fmt::Display) -> Self {
+        Self::new(INTERNAL_ERROR, err.to_string())
+    }
+}
+
 #[derive(Serialize)]
 struct InitializeResult {
     #[serde(rename = "protocolVersion")]
@@ -132,6 +193,8 @@ struct DocumentationSlim {
     installed_at: i64,
     #[serde(rename = "updatedAt")]
     updated_at: i64,
+    #[serde(rename = "isActive")]
+    is_active: bool,
 }
 
 #[derive(Deserialize)]
@@ -150,20 +213,170 @@ struct GetItemArgs {
 }
 
 #[derive(Deserialize)]
-struct DocsTreeArgs {
+struct DocsListArgs {
+    name: Option<String>,
+}
+
+/// A documentation is addressed by `docId` or by `name`, `version` switches to another
+/// installed version (the active one when omitted)
+#[derive(Deserialize)]
+struct DocRefArgs {
     #[serde(rename = "docId")]
-    doc_id: i64,
+    doc_id: Option<i64>,
+    name: Option<String>,
+    version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DocsTreeArgs {
+    #[serde(flatten)]
+    doc: DocRefArgs,
     #[serde(rename = "parentPath")]
     parent_path: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct DocsEntryArgs {
-    #[serde(rename = "docId")]
-    doc_id: i64,
+    #[serde(flatten)]
+    doc: DocRefArgs,
     path: String,
 }
 
+#[derive(Deserialize)]
+struct CreateItemArgs {
+    #[serde(flatten)]
+    item: CreateItemDto,
+    /// Tag names, missing ones are created
+    tags: Option<Vec<String>>,
+    #[serde(rename = "dryRun", default)]
+    dry_run: bool,
+}
+
+#[derive(Deserialize)]
+struct UpdateItemArgs {
+    #[serde(flatten)]
+    item: UpdateItemDto,
+    tags: Option<Vec<String>>,
+    #[serde(rename = "dryRun", default)]
+    dry_run: bool,
+}
+
+#[derive(Deserialize)]
+struct DeleteItemArgs {
+    id: i64,
+    #[serde(rename = "dryRun", default)]
+    dry_run: bool,
+}
+
+#[derive(Deserialize)]
+struct UpsertTagsArgs {
+    names: Vec<String>,
+    #[serde(rename = "itemId")]
+    item_id: Option<i64>,
+    #[serde(rename = "dryRun", default)]
+    dry_run: bool,
+}
+
+/// A tag named in a write call. `id` is missing for tags a dry run would create.
+#[derive(Serialize)]
+struct TagRef {
+    id: Option<i64>,
+    name: String,
+    created: bool,
+}
+
+const WRITE_TOOLS: [&str; 4] = [
+    "devvault.items.create",
+    "devvault.items.update",
+    "devvault.items.delete",
+    "devvault.tags.upsert",
+];
+
+const READ_ONLY_MESSAGE: &str =
+    "Dev Vault MCP server is read-only, restart it with --allow-write to change items";
+
+struct ServerOptions {
+    db_path: PathBuf,
+    allow_write: bool,
+    /// `--http <addr>` serves Streamable HTTP instead of stdio
+    http: Option<SocketAddr>,
+    token: Option<String>,
+}
+
+/// Items and doc entries listed per `resources/list` page
+const RESOURCES_PAGE_SIZE: i64 = 100;
+/// Notes carrying this tag are served as prompts
+const PROMPT_TAG: &str = "prompt";
+const PROMPTS_LIMIT: i64 = 500;
+
+static PROMPT_PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap());
+
+#[derive(Serialize)]
+struct ResourceDefinition {
+    uri: String,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(rename = "mimeType")]
+    mime_type: &'static str,
+}
+
+#[derive(Serialize)]
+struct ResourceTemplate {
+    #[serde(rename = "uriTemplate")]
+    uri_template: &'static str,
+    name: &'static str,
+    description: &'static str,
+    #[serde(rename = "mimeType")]
+    mime_type: &'static str,
+}
+
+#[derive(Serialize)]
+struct ResourceContents {
+    uri: String,
+    #[serde(rename = "mimeType")]
+    mime_type: &'static str,
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct ResourcesListParams {
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ResourceReadParams {
+    uri: String,
+}
+
+#[derive(Serialize)]
+struct PromptDefinition {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    arguments: Vec<PromptArgument>,
+}
+
+#[derive(Serialize)]
+struct PromptArgument {
+    name: String,
+    required: bool,
+}
+
+#[derive(Deserialize)]
+struct PromptGetParams {
+    name: String,
+    #[serde(default)]
+    arguments: HashMap<String, String>,
+}
+
+/// A prompt-tagged note with the name it is listed under
+struct SavedPrompt {
+    name: String,
+    item: ItemWithTags,
+}
+
 fn item_tags(tags: Vec<dev_vault_lib::models::Tag>) -> Vec<String> {
     tags.into_iter().map(|tag| tag.name).collect()
 }
@@ -229,11 +442,24 @@ fn doc_to_slim(doc: Documentation) -> DocumentationSlim {
         source_url: doc.source_url,
         installed_at: doc.installed_at,
         updated_at: doc.updated_at,
+        is_active: doc.is_active,
     }
 }
 
-fn ensure_request_id(id: Option<Value>) -> Value {
-    id.unwrap_or(Value::Null)
+async fn resolve_doc_id(
+    doc_manager: &DocumentationManager,
+    args: &DocRefArgs,
+) -> Result<i64, String> {
+    let version = args.version.as_deref();
+    match (args.doc_id, args.name.as_deref()) {
+        (Some(doc_id), _) => doc_manager.resolve_doc_version(doc_id, version).await,
+        (None, Some(name)) => doc_manager
+            .find_documentation(name, version)
+            .await
+            .map(|doc| doc.id),
+        (None, None) => return Err("docId or name is required".to_string()),
+    }
+    .map_err(|e| e.to_string())
 }
 
 fn ok_response(id: Value, result: Value) -> RpcResponse {
@@ -245,29 +471,12 @@ fn ok_response(id: Value, result: Value) -> RpcResponse {
     }
 }
 
-fn error_response(id: Value, message: impl Into<String>) -> RpcResponse {
+fn error_response(id: Value, error: RpcError) -> RpcResponse {
     RpcResponse {
         jsonrpc: "2.0",
         id,
         result: None,
-        error: Some(RpcError {
-            code: -32000,
-            message: message.into(),
-        }),
-    }
-}
-
-fn tool_error_response(id: Value, message: impl Into<String>) -> RpcResponse {
-    let result = ToolCallResult {
-        content: vec![ToolContent::Text {
-            text: message.into(),
-        }],
-        is_error: true,
-    };
-
-    match serde_json::to_value(result) {
-        Ok(value) => ok_response(id, value),
-        Err(err) => error_response(id, err.to_string()),
+        error: Some(error),
     }
 }
 
@@ -278,20 +487,31 @@ fn args_or_empty(value: Option<Value>) -> Value {
     }
 }
 
-fn tools_list() -> Vec<ToolDefinition> {
-    vec![
+fn parse_params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, RpcError> {
+    serde_json::from_value(args_or_empty(params))
+        .map_err(|e| RpcError::invalid_params(e.to_string()))
+}
+
+fn tools_list(allow_write: bool) -> Vec<ToolDefinition> {
+    let mut tools = vec![
         ToolDefinition {
             name: "devvault.search".to_string(),
-            description: "Поиск по Dev Vault (FTS + семантика), возвращает slim-результаты"
+            description: "Поиск по Dev Vault (FTS + семантика), возвращает slim-результаты. Без точных совпадений ищет подстроки и исправляет опечатки (correctedQuery)"
                 .to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
-                    "query": {"type": "string"},
+                    "query": {
+                        "type": "string",
+                        "description": "Текст и фильтры: type:, tag:, doc: (doc:python@3.12 для версии), lang:, before:/after:YYYY-MM-DD, created:/updated:>YYYY-MM-DD, \"фраза\", -исключение"
+                    },
                     "type": {"type": "string", "enum": ["snippet", "config", "note", "link", "documentation"]},
                     "tagIds": {"type": "array", "items": {"type": "number"}},
                     "limit": {"type": "number"},
-                    "offset": {"type": "number"}
+                    "offset": {"type": "number"},
+                    "cursor": {"type": "string", "description": "nextCursor из предыдущей страницы"},
+                    "docIds": {"type": "array", "items": {"type": "number"}, "description": "Искать только в этих документациях (id из devvault.docs.list_installed)"},
+                    "pathPrefix": {"type": "string", "description": "Только раздел документации и всё под ним, например api/fs"}
                 },
                 "required": ["query"]
             }),
@@ -330,8 +550,13 @@ fn tools_list() -> Vec<ToolDefinition> {
         },
         ToolDefinition {
             name: "devvault.docs.list_installed".to_string(),
-            description: "Список установленных документаций".to_string(),
-            input_schema: serde_json::json!({"type": "object", "properties": {}}),
+            description: "Список установленных документаций и их версий".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "description": "Только версии этой документации"}
+                }
+            }),
         },
         ToolDefinition {
             name: "devvault.docs.tree".to_string(),
@@ -340,9 +565,10 @@ fn tools_list() -> Vec<ToolDefinition> {
                 "type": "object",
                 "properties": {
                     "docId": {"type": "number"},
+                    "name": {"type": "string", "description": "Имя документации вместо docId"},
+                    "version": {"type": "string", "description": "Версия, по умолчанию активная"},
                     "parentPath": {"type": "string"}
-                },
-                "required": ["docId"]
+                }
             }),
         },
         ToolDefinition {
@@ -350,31 +576,448 @@ fn tools_list() -> Vec<ToolDefinition> {
             description: "Контент документации по пути".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
-                "properties": {"docId": {"type": "number"}, "path": {"type": "string"}},
-                "required": ["docId", "path"]
+                "properties": {
+                    "docId": {"type": "number"},
+                    "name": {"type": "string", "description": "Имя документации вместо docId"},
+                    "version": {"type": "string", "description": "Версия, по умолчанию активная"},
+                    "path": {"type": "string"}
+                },
+                "required": ["path"]
+            }),
+        },
+    ];
+
+    if allow_write {
+        tools.extend(write_tools_list());
+    }
+    tools
+}
+
+fn write_tools_list() -> Vec<ToolDefinition> {
+    let dry_run = serde_json::json!({
+        "type": "boolean",
+        "description": "Только показать, что изменится, ничего не записывая"
+    });
+    let tags = serde_json::json!({
+        "type": "array",
+        "items": {"type": "string"},
+        "description": "Имена тегов, отсутствующие будут созданы"
+    });
+
+    vec![
+        ToolDefinition {
+            name: "devvault.items.create".to_string(),
+            description: "Создать айтем (сниппет, конфиг, заметку, ссылку)".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "type": {"type": "string", "enum": ["snippet", "config", "note", "link"]},
+                    "title": {"type": "string"},
+                    "description": {"type": "string"},
+                    "content": {"type": "string"},
+                    "metadata": {"type": "object", "description": "Например {\"language\": \"bash\"}"},
+                    "tags": tags,
+                    "tagIds": {"type": "array", "items": {"type": "number"}},
+                    "dryRun": dry_run
+                },
+                "required": ["type", "title", "content"]
+            }),
+        },
+        ToolDefinition {
+            name: "devvault.items.update".to_string(),
+            description: "Изменить айтем, переданные поля заменяются, tags/tagIds заменяют набор тегов"
+                .to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "id": {"type": "number"},
+                    "type": {"type": "string", "enum": ["snippet", "config", "note", "link"]},
+                    "title": {"type": "string"},
+                    "description": {"type": "string"},
+                    "content": {"type": "string"},
+                    "metadata": {"type": "object"},
+                    "tags": tags,
+                    "tagIds": {"type": "array", "items": {"type": "number"}},
+                    "dryRun": dry_run
+                },
+                "required": ["id"]
+            }),
+        },
+        ToolDefinition {
+            name: "devvault.items.delete".to_string(),
+            description: "Переместить айтем в корзину, из приложения его можно восстановить"
+                .to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "id": {"type": "number"},
+                    "dryRun": dry_run
+                },
+                "required": ["id"]
+            }),
+        },
+        ToolDefinition {
+            name: "devvault.tags.upsert".to_string(),
+            description: "Найти или создать теги по именам и, если передан itemId, добавить их айтему. Теги без айтемов со временем удаляются"
+                .to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "names": {"type": "array", "items": {"type": "string"}},
+                    "itemId": {"type": "number"},
+                    "dryRun": dry_run
+                },
+                "required": ["names"]
             }),
         },
     ]
 }
 
-async fn handle_tool_call(db_path: &PathBuf, params: ToolCallParams) -> Result<Value, String> {
-    let storage = Storage::new(db_path.clone())
+/// The app's config.toml next to the database, defaults when it cannot be read
+async fn load_app_config(db_path: &Path) -> AppConfig {
+    let Some(app_dir) = db_path.parent() else {
+        return AppConfig::default();
+    };
+
+    match ConfigManager::new(app_dir.to_path_buf())
+        .load_config()
         .await
-        .map_err(|e| e.to_string())?;
+    {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Failed to load config, using defaults: {err}");
+            AppConfig::default()
+        }
+    }
+}
+
+/// Resolves tag names to tags, creating the missing ones unless this is a dry run
+async fn upsert_tag_names(
+    storage: &Storage,
+    names: &[String],
+    dry_run: bool,
+) -> Result<Vec<TagRef>, String> {
+    let mut seen = HashSet::new();
+    let mut tags = Vec::new();
+
+    for name in names {
+        let name = name.trim();
+        if name.is_empty() || !seen.insert(name.to_string()) {
+            continue;
+        }
+
+        let existing = storage
+            .get_tag_by_name(name)
+            .await
+            .map_err(|e| e.to_string())?;
+        tags.push(match existing {
+            Some(tag) => TagRef {
+                id: Some(tag.id),
+                name: tag.name,
+                created: false,
+            },
+            None if dry_run => TagRef {
+                id: None,
+                name: name.to_string(),
+                created: true,
+            },
+            None => TagRef {
+                id: Some(
+                    storage
+                        .create_tag(name.to_string())
+                        .await
+                        .map_err(|e| e.to_string())?,
+                ),
+                name: name.to_string(),
+                created: true,
+            },
+        });
+    }
+
+    Ok(tags)
+}
+
+/// Ids and names of `tag_ids` plus `tags`, unknown ids are rejected
+async fn tag_set(
+    storage: &Storage,
+    tag_ids: Option<&[i64]>,
+    tags: &[TagRef],
+) -> Result<(Vec<i64>, Vec<String>), String> {
+    let known: HashMap<i64, String> = storage
+        .list_tags()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|tag| (tag.id, tag.name))
+        .collect();
+
+    let mut ids = Vec::new();
+    let mut names = Vec::new();
+    for id in tag_ids.unwrap_or_default() {
+        let name = known
+            .get(id)
+            .ok_or_else(|| format!("Tag {} not found", id))?;
+        if !ids.contains(id) {
+            ids.push(*id);
+            names.push(name.clone());
+        }
+    }
+    for tag in tags {
+        if names.contains(&tag.name) {
+            continue;
+        }
+        if let Some(id) = tag.id {
+            ids.push(id);
+        }
+        names.push(tag.name.clone());
+    }
+
+    Ok((ids, names))
+}
+
+async fn find_item(storage: &Storage, id: i64) -> Result<ItemWithTags, String> {
+    storage
+        .get_item(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Item {} not found", id))
+}
+
+/// Documentation lives in its own tables, an item of that type would be searched in
+/// neither
+fn check_item_type(item_type: &ItemType) -> Result<(), String> {
+    if *item_type == ItemType::Documentation {
+        return Err("type must be one of snippet, config, note, link".to_string());
+    }
+    Ok(())
+}
+
+async fn handle_write_tool(storage: &Storage, params: ToolCallParams) -> Result<Value, String> {
+    match params.name.as_str() {
+        "devvault.items.create" => {
+            let args: CreateItemArgs = serde_json::from_value(args_or_empty(params.arguments))
+                .map_err(|e| e.to_string())?;
+            if args.item.title.trim().is_empty() {
+                return Err("title must not be empty".to_string());
+            }
+            check_item_type(&args.item.item_type)?;
+
+            let tags = upsert_tag_names(
+                storage,
+                args.tags.as_deref().unwrap_or_default(),
+                args.dry_run,
+            )
+            .await?;
+            let (tag_ids, tag_names) =
+                tag_set(storage, args.item.tag_ids.as_deref(), &tags).await?;
+
+            if args.dry_run {
+                let item = &args.item;
+                return Ok(serde_json::json!({
+                    "dryRun": true,
+                    "action": "create",
+                    "item": {
+                        "type": item.item_type,
+                        "title": item.title,
+                        "description": item.description,
+                        "content": item.content,
+                        "metadata": item.metadata,
+                        "tags": tag_names
+                    },
+                    "tags": tags
+                }));
+            }
+
+            let id = storage
+                .create_item(CreateItemDto {
+                    tag_ids: Some(tag_ids),
+                    ..args.item
+                })
+                .await
+                .map_err(|e| e.to_string())?;
+            let item = item_to_full(find_item(storage, id).await?);
+            Ok(serde_json::json!({
+                "dryRun": false,
+                "action": "create",
+                "item": item,
+                "tags": tags
+            }))
+        }
+        "devvault.items.update" => {
+            let args: UpdateItemArgs = serde_json::from_value(args_or_empty(params.arguments))
+                .map_err(|e| e.to_string())?;
+            if let Some(item_type) = &args.item.item_type {
+                check_item_type(item_type)?;
+            }
+            let before = item_to_full(find_item(storage, args.item.id).await?);
+            let replaces_tags = args.tags.is_some() || args.item.tag_ids.is_some();
+
+            let tags = upsert_tag_names(
+                storage,
+                args.tags.as_deref().unwrap_or_default(),
+                args.dry_run,
+            )
+            .await?;
+            let mut dto = args.item;
+            let mut tag_names = None;
+            if replaces_tags {
+                let (ids, names) = tag_set(storage, dto.tag_ids.as_deref(), &tags).await?;
+                dto.tag_ids = Some(ids);
+                tag_names = Some(names);
+            }
+
+            if args.dry_run {
+                let mut after = serde_json::to_value(&before).map_err(|e| e.to_string())?;
+                if let Some(item_type) = &dto.item_type {
+                    after["type"] = serde_json::json!(item_type);
+                }
+                for (field, value) in [
+                    ("title", &dto.title),
+                    ("description", &dto.description),
+                    ("content", &dto.content),
+                ] {
+                    if let Some(value) = value {
+                        after[field] = serde_json::json!(value);
+                    }
+                }
+                if let Some(metadata) = &dto.metadata {
+                    after["metadata"] = metadata.clone();
+                }
+                if let Some(names) = tag_names {
+                    after["tags"] = serde_json::json!(names);
+                }
+                return Ok(serde_json::json!({
+                    "dryRun": true,
+                    "action": "update",
+                    "before": before,
+                    "after": after,
+                    "tags": tags
+                }));
+            }
+
+            let id = dto.id;
+            storage.update_item(dto).await.map_err(|e| e.to_string())?;
+            let item = item_to_full(find_item(storage, id).await?);
+            Ok(serde_json::json!({
+                "dryRun": false,
+                "action": "update",
+                "item": item,
+                "tags": tags
+            }))
+        }
+        "devvault.items.delete" => {
+            let args: DeleteItemArgs = serde_json::from_value(args_or_empty(params.arguments))
+                .map_err(|e| e.to_string())?;
+            let item = item_to_slim(find_item(storage, args.id).await?);
+            if !args.dry_run {
+                storage
+                    .delete_item(args.id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            Ok(serde_json::json!({
+                "dryRun": args.dry_run,
+                "action": "delete",
+                "item": item
+            }))
+        }
+        "devvault.tags.upsert" => {
+            let args: UpsertTagsArgs = serde_json::from_value(args_or_empty(params.arguments))
+                .map_err(|e| e.to_string())?;
+            let item = match args.item_id {
+                Some(item_id) => Some(find_item(storage, item_id).await?),
+                None => None,
+            };
+
+            let tags = upsert_tag_names(storage, &args.names, args.dry_run).await?;
+            if tags.is_empty() {
+                return Err("names must contain at least one tag name".to_string());
+            }
+
+            let item = match item {
+                Some(item) => {
+                    let mut ids: Vec<i64> = item.tags.iter().map(|tag| tag.id).collect();
+                    let mut names: Vec<String> =
+                        item.tags.iter().map(|tag| tag.name.clone()).collect();
+                    for tag in &tags {
+                        if !names.contains(&tag.name) {
+                            names.push(tag.name.clone());
+                            ids.extend(tag.id);
+                        }
+                    }
+
+                    if args.dry_run {
+                        let mut item = item_to_full(item);
+                        item.tags = names;
+                        Some(item)
+                    } else {
+                        let id = item.item.id;
+                        storage
+                            .update_item(UpdateItemDto {
+                                id,
+                                item_type: None,
+                                title: None,
+                                description: None,
+                                content: None,
+                                metadata: None,
+                                tag_ids: Some(ids),
+                            })
+                            .await
+                            .map_err(|e| e.to_string())?;
+                        Some(item_to_full(find_item(storage, id).await?))
+                    }
+                }
+                None => None,
+            };
+
+            Ok(serde_json::json!({
+                "dryRun": args.dry_run,
+                "action": "upsert_tags",
+                "tags": tags,
+                "item": item
+            }))
+        }
+        _ => Err("Unknown tool".to_string()),
+    }
+}
+
+async fn handle_tool_call(
+    options: &ServerOptions,
+    storage: &Storage,
+    params: ToolCallParams,
+) -> Result<Value, String> {
+    let is_write = WRITE_TOOLS.contains(&params.name.as_str());
+    if is_write && !options.allow_write {
+        return Err(READ_ONLY_MESSAGE.to_string());
+    }
+
+    let db_path = &options.db_path;
+    if is_write {
+        return handle_write_tool(storage, params).await;
+    }
     let doc_manager = DocumentationManager::new(storage.pool.clone());
 
     match params.name.as_str() {
         "devvault.search" => {
             let args: SearchQuery = serde_json::from_value(args_or_empty(params.arguments))
                 .map_err(|e| e.to_string())?;
-            let search_engine = SearchEngine::new(storage.pool.clone());
+            let search_config = load_app_config(db_path).await.search;
+            let search_engine = SearchEngine::new(storage.pool.clone())
+                .with_embedder(storage.embedder())
+                .with_config(&search_config);
             let result = search_engine
                 .search(args)
                 .await
                 .map_err(|e| e.to_string())?;
             let items: Vec<SearchItemSlim> =
                 result.items.into_iter().map(item_to_search_slim).collect();
-            Ok(serde_json::json!({"total": result.total, "items": items}))
+            Ok(serde_json::json!({
+                "total": result.total,
+                "hasMore": result.has_more,
+                "nextCursor": result.next_cursor,
+                "correctedQuery": result.corrected_query,
+                "items": items
+            }))
         }
         "devvault.items.list" => {
             let args: ListItemsArgs = serde_json::from_value(args_or_empty(params.arguments))
@@ -405,18 +1048,22 @@ async fn handle_tool_call(db_path: &PathBuf, params: ToolCallParams) -> Result<V
             Ok(serde_json::to_value(counts).map_err(|e| e.to_string())?)
         }
         "devvault.docs.list_installed" => {
-            let docs = doc_manager
-                .list_installed_documentations()
-                .await
+            let args: DocsListArgs = serde_json::from_value(args_or_empty(params.arguments))
                 .map_err(|e| e.to_string())?;
+            let docs = match args.name {
+                Some(name) => doc_manager.list_doc_versions(&name).await,
+                None => doc_manager.list_installed_documentations().await,
+            }
+            .map_err(|e| e.to_string())?;
             let docs: Vec<DocumentationSlim> = docs.into_iter().map(doc_to_slim).collect();
             Ok(serde_json::to_value(docs).map_err(|e| e.to_string())?)
         }
         "devvault.docs.tree" => {
             let args: DocsTreeArgs = serde_json::from_value(args_or_empty(params.arguments))
                 .map_err(|e| e.to_string())?;
+            let doc_id = resolve_doc_id(&doc_manager, &args.doc).await?;
             let tree: Vec<DocTreeNode> = doc_manager
-                .get_doc_tree_level(args.doc_id, args.parent_path)
+                .get_doc_tree_level(doc_id, args.parent_path)
                 .await
                 .map_err(|e| e.to_string())?;
             Ok(serde_json::to_value(tree).map_err(|e| e.to_string())?)
@@ -424,8 +1071,9 @@ async fn handle_tool_call(db_path: &PathBuf, params: ToolCallParams) -> Result<V
         "devvault.docs.entry" => {
             let args: DocsEntryArgs = serde_json::from_value(args_or_empty(params.arguments))
                 .map_err(|e| e.to_string())?;
+            let doc_id = resolve_doc_id(&doc_manager, &args.doc).await?;
             let entry: DocEntry = doc_manager
-                .get_doc_entry_by_path(args.doc_id, &args.path)
+                .get_doc_entry_by_path(doc_id, &args.path)
                 .await
                 .map_err(|e| e.to_string())?;
             Ok(serde_json::to_value(entry).map_err(|e| e.to_string())?)
@@ -434,22 +1082,325 @@ async fn handle_tool_call(db_path: &PathBuf, params: ToolCallParams) -> Result<V
     }
 }
 
-fn parse_db_path() -> Result<PathBuf, String> {
+fn item_mime_type(item_type: &ItemType) -> &'static str {
+    match item_type {
+        ItemType::Note | ItemType::Documentation => "text/markdown",
+        _ => "text/plain",
+    }
+}
+
+fn resource_templates() -> Vec<ResourceTemplate> {
+    vec![
+        ResourceTemplate {
+            uri_template: "devvault://item/{id}",
+            name: "Dev Vault item",
+            description: "Содержимое сниппета, конфига, заметки или ссылки по id",
+            mime_type: "text/plain",
+        },
+        ResourceTemplate {
+            uri_template: "devvault://doc/{name}/{path}",
+            name: "Documentation entry",
+            description: "Страница активной версии установленной документации по имени и пути",
+            mime_type: "text/markdown",
+        },
+    ]
+}
+
+/// Lists items first, then entries of active documentation, the cursor is an offset into both
+async fn handle_resources_list(
+    storage: &Storage,
+    params: Option<Value>,
+) -> Result<Value, RpcError> {
+    let params: ResourcesListParams = parse_params(params)?;
+    let offset = match params.cursor.as_deref() {
+        Some(cursor) => cursor
+            .parse::<i64>()
+            .ok()
+            .filter(|offset| *offset >= 0)
+            .ok_or_else(|| RpcError::invalid_params(format!("Invalid cursor: {cursor}")))?,
+        None => 0,
+    };
+
+    let items_total: i64 = storage
+        .list_item_type_counts()
+        .await
+        .map_err(RpcError::internal)?
+        .iter()
+        .map(|count| count.count)
+        .sum();
+
+    let mut resources = Vec::new();
+    if offset < items_total {
+        let items = storage
+            .list_items(Some(RESOURCES_PAGE_SIZE), Some(offset), None, None)
+            .await
+            .map_err(RpcError::internal)?;
+        resources.extend(items.into_iter().map(|item| ResourceDefinition {
+            uri: ResourceUri::Item(item.item.id).to_string(),
+            mime_type: item_mime_type(&item.item.item_type),
+            name: item.item.title,
+            description: item.item.description,
+        }));
+    }
+
+    let remaining = RESOURCES_PAGE_SIZE - resources.len() as i64;
+    if remaining > 0 {
+        let doc_offset = (offset - items_total).max(0);
+        let entries = DocumentationManager::new(storage.pool.clone())
+            .list_active_doc_entries(remaining, doc_offset)
+            .await
+            .map_err(RpcError::internal)?;
+        resources.extend(entries.into_iter().map(|entry| {
+            ResourceDefinition {
+                name: format!("{}: {}", entry.doc_name, entry.title),
+                uri: ResourceUri::Doc {
+                    name: entry.doc_name,
+                    path: entry.path,
+                }
+                .to_string(),
+                description: None,
+                mime_type: "text/markdown",
+            }
+        }));
+    }
+
+    let next_cursor = (resources.len() as i64 == RESOURCES_PAGE_SIZE)
+        .then(|| (offset + RESOURCES_PAGE_SIZE).to_string());
+    Ok(serde_json::json!({
+        "resources": resources,
+        "nextCursor": next_cursor
+    }))
+}
+
+async fn handle_resources_read(
+    storage: &Storage,
+    params: Option<Value>,
+) -> Result<Value, RpcError> {
+    let params: ResourceReadParams = parse_params(params)?;
+    let not_found = |message: String| RpcError::new(RESOURCE_NOT_FOUND, message);
+    let contents = match ResourceUri::parse(&params.uri).map_err(RpcError::invalid_params)? {
+        ResourceUri::Item(id) => {
+            let item = storage
+                .get_item(id)
+                .await
+                .map_err(RpcError::internal)?
+                .ok_or_else(|| not_found(format!("Item {id} not found")))?
+                .item;
+            ResourceContents {
+                uri: params.uri,
+                mime_type: item_mime_type(&item.item_type),
+                text: item.content,
+            }
+        }
+        ResourceUri::Doc { name, path } => {
+            let doc_manager = DocumentationManager::new(storage.pool.clone());
+            let doc = doc_manager
+                .find_documentation(&name, None)
+                .await
+                .map_err(|e| not_found(e.to_string()))?;
+            let entry = doc_manager
+                .get_doc_entry_by_path(doc.id, &path)
+                .await
+                .map_err(|_| not_found(format!("Doc entry {path} not found in {name}")))?;
+            ResourceContents {
+                uri: params.uri,
+                mime_type: "text/markdown",
+                text: entry.content,
+            }
+        }
+    };
+
+    Ok(serde_json::json!({ "contents": [contents] }))
+}
+
+/// `{{name}}` placeholders in order of first appearance
+fn prompt_arguments(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for capture in PROMPT_PLACEHOLDER.captures_iter(content) {
+        let name = capture[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+fn render_prompt(content: &str, arguments: &HashMap<String, String>) -> Result<String, RpcError> {
+    if let Some(missing) = prompt_arguments(content)
+        .into_iter()
+        .find(|name| !arguments.contains_key(name))
+    {
+        return Err(RpcError::invalid_params(format!(
+            "Missing prompt argument: {missing}"
+        )));
+    }
+    Ok(PROMPT_PLACEHOLDER
+        .replace_all(content, |capture: &regex::Captures| {
+            arguments[&capture[1]].clone()
+        })
+        .into_owned())
+}
+
+fn prompt_slug(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Notes tagged `prompt`, named by title slug with the id appended on collisions
+async fn saved_prompts(storage: &Storage) -> Result<Vec<SavedPrompt>, RpcError> {
+    let Some(tag) = storage
+        .get_tag_by_name(PROMPT_TAG)
+        .await
+        .map_err(RpcError::internal)?
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut items = storage
+        .list_items(
+            Some(PROMPTS_LIMIT),
+            Some(0),
+            Some(ItemType::Note),
+            Some(vec![tag.id]),
+        )
+        .await
+        .map_err(RpcError::internal)?;
+    items.sort_by_key(|item| item.item.id);
+
+    let mut taken = HashSet::new();
+    let mut prompts = Vec::with_capacity(items.len());
+    for item in items {
+        let slug = prompt_slug(&item.item.title);
+        let name = if slug.is_empty() || taken.contains(&slug) {
+            format!(
+                "{}-{}",
+                if slug.is_empty() { "prompt" } else { &slug },
+                item.item.id
+            )
+        } else {
+            slug
+        };
+        taken.insert(name.clone());
+        prompts.push(SavedPrompt { name, item });
+    }
+    Ok(prompts)
+}
+
+async fn handle_prompts_list(storage: &Storage) -> Result<Value, RpcError> {
+    let prompts: Vec<PromptDefinition> = saved_prompts(storage)
+        .await?
+        .into_iter()
+        .map(|prompt| PromptDefinition {
+            arguments: prompt_arguments(&prompt.item.item.content)
+                .into_iter()
+                .map(|name| PromptArgument {
+                    name,
+                    required: true,
+                })
+                .collect(),
+            description: prompt
+                .item
+                .item
+                .description
+                .or(Some(prompt.item.item.title)),
+            name: prompt.name,
+        })
+        .collect();
+    Ok(serde_json::json!({ "prompts": prompts }))
+}
+
+async fn handle_prompts_get(storage: &Storage, params: Option<Value>) -> Result<Value, RpcError> {
+    let params: PromptGetParams = parse_params(params)?;
+    let prompt = saved_prompts(storage)
+        .await?
+        .into_iter()
+        .find(|prompt| prompt.name == params.name)
+        .ok_or_else(|| RpcError::invalid_params(format!("Prompt {} not found", params.name)))?;
+    let item = prompt.item.item;
+    let text = render_prompt(&item.content, &params.arguments)?;
+
+    Ok(serde_json::json!({
+        "description": item.description.unwrap_or(item.title),
+        "messages": [{
+            "role": "user",
+            "content": { "type": "text", "text": text }
+        }]
+    }))
+}
+
+/// `--read-only` is the default and wins over `--allow-write` when both are given.
+/// `--http` only accepts loopback addresses, the bearer token comes from `--token` or
+/// `DEV_VAULT_MCP_TOKEN` and is generated when neither is set.
+fn parse_args() -> Result<ServerOptions, String> {
+    let mut db_path = None;
+    let mut allow_write = false;
+    let mut read_only = false;
+    let mut http = None;
+    let mut token = std::env::var(TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.is_empty());
+
     let mut args = std::env::args().skip(1);
     while let Some(arg) = args.next() {
-        if arg == "--db-path" {
-            if let Some(value) = args.next() {
-                return Ok(PathBuf::from(value));
+        match arg.as_str() {
+            "--db-path" => {
+                if let Some(value) = args.next() {
+                    db_path = Some(PathBuf::from(value));
+                }
+            }
+            "--allow-write" => allow_write = true,
+            "--read-only" => read_only = true,
+            "--http" => {
+                let value = args.next().ok_or("--http requires an address")?;
+                let addr: SocketAddr = value
+                    .parse()
+                    .map_err(|_| format!("Invalid --http address: {value}"))?;
+                if !addr.ip().is_loopback() {
+                    return Err(format!("--http only listens on localhost, got {addr}"));
+                }
+                http = Some(addr);
+            }
+            "--token" => {
+                token = Some(args.next().ok_or("--token requires a value")?);
             }
+            _ => {}
         }
     }
-    default_db_path()
+
+    Ok(ServerOptions {
+        db_path: match db_path {
+            Some(db_path) => db_path,
+            None => default_db_path()?,
+        },
+        allow_write: allow_write && !read_only,
+        http,
+        token,
+    })
+}
+
+/// Echoes the client's protocol version when supported, otherwise offers the newest one
+fn negotiate_protocol_version(requested: Option<&str>) -> &'static str {
+    SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .find(|version| Some(**version) == requested)
+        .unwrap_or(&SUPPORTED_PROTOCOL_VERSIONS[0])
 }
 
-fn build_initialize_result() -> InitializeResult {
+fn build_initialize_result(protocol_version: &str) -> InitializeResult {
     InitializeResult {
-        protocol_version: "2024-11-05".to_string(),
-        capabilities: HashMap::from([("tools".to_string(), serde_json::json!({}))]),
+        protocol_version: protocol_version.to_string(),
+        capabilities: HashMap::from([
+            ("tools".to_string(), serde_json::json!({})),
+            ("resources".to_string(), serde_json::json!({})),
+            ("prompts".to_string(), serde_json::json!({})),
+        ]),
         server_info: ServerInfo {
             name: MCP_SERVER_NAME.to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
@@ -457,13 +1408,198 @@ fn build_initialize_result() -> InitializeResult {
     }
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let db_path = parse_db_path().map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+/// Session of the stdio transport, which has only the one
+const STDIO_SESSION: &str = "";
 
-    let mut reader = BufReader::new(tokio::io::stdin());
-    let mut stdout = tokio::io::stdout();
+/// The MCP server: a long-lived pool and the requests currently being handled. Over HTTP
+/// every session shares it.
+struct Server {
+    options: ServerOptions,
+    storage: Storage,
+    /// Keyed by session and the JSON text of the request id: sessions pick their ids
+    /// independently, `notifications/cancelled` trips the token of its own session only
+    in_flight: Mutex<HashMap<(String, String), CancellationToken>>,
+}
+
+impl Server {
+    async fn open(options: ServerOptions) -> Result<Self, String> {
+        let storage = Storage::new(options.db_path.clone())
+            .await
+            .map_err(|e| format!("Failed to open {}: {e:#}", options.db_path.display()))?
+            .with_history(load_app_config(&options.db_path).await.history);
+        Ok(Self {
+            options,
+            storage,
+            in_flight: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Handles one line of input, a single message or a batch. `None` when nothing is owed
+    /// back: notifications, cancelled requests and batches made only of those.
+    async fn handle_line(&self, session: &str, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(err) => {
+                let response =
+                    error_response(Value::Null, RpcError::new(PARSE_ERROR, err.to_string()));
+                return serde_json::to_value(response).ok();
+            }
+        };
+
+        let Value::Array(batch) = message else {
+            let response = self.handle_message(session, message).await?;
+            return serde_json::to_value(response).ok();
+        };
+
+        if batch.is_empty() {
+            let response =
+                error_response(Value::Null, RpcError::new(INVALID_REQUEST, "Empty batch"));
+            return serde_json::to_value(response).ok();
+        }
+
+        let mut responses = Vec::new();
+        for message in batch {
+            if let Some(response) = self.handle_message(session, message).await {
+                responses.push(response);
+            }
+        }
+        if responses.is_empty() {
+            return None;
+        }
+        serde_json::to_value(responses).ok()
+    }
+
+    async fn handle_message(&self, session: &str, message: Value) -> Option<RpcResponse> {
+        let id = message.get("id").cloned();
+        let request = match serde_json::from_value::<RpcRequest>(message) {
+            Ok(request) if request.jsonrpc == "2.0" => request,
+            Ok(_) => {
+                return Some(error_response(
+                    id.unwrap_or(Value::Null),
+                    RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""),
+                ))
+            }
+            Err(err) => {
+                return Some(error_response(
+                    id.unwrap_or(Value::Null),
+                    RpcError::new(INVALID_REQUEST, err.to_string()),
+                ))
+            }
+        };
+
+        let Some(id) = request.id else {
+            self.handle_notification(session, &request.method, request.params);
+            return None;
+        };
+
+        let key = (session.to_string(), id.to_string());
+        let token = CancellationToken::new();
+        self.in_flight
+            .lock()
+            .unwrap()
+            .insert(key.clone(), token.clone());
+
+        let outcome = tokio::select! {
+            _ = token.cancelled() => None,
+            result = self.dispatch(&request.method, request.params) => Some(result),
+        };
+        self.in_flight.lock().unwrap().remove(&key);
+
+        // A cancelled request gets no response at all
+        Some(match outcome? {
+            Ok(result) => ok_response(id, result),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    fn handle_notification(&self, session: &str, method: &str, params: Option<Value>) {
+        match method {
+            // The server never sends requests of its own, so there is nothing to hold back
+            "notifications/initialized" => {}
+            "notifications/cancelled" => {
+                if let Ok(params) = parse_params::<CancelledParams>(params) {
+                    let key = (session.to_string(), params.request_id.to_string());
+                    if let Some(token) = self.in_flight.lock().unwrap().get(&key) {
+                        token.cancel();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    async fn dispatch(&self, method: &str, params: Option<Value>) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => {
+                let params: InitializeParams = parse_params(params)?;
+                let version = negotiate_protocol_version(params.protocol_version.as_deref());
+                serde_json::to_value(build_initialize_result(version)).map_err(RpcError::internal)
+            }
+            "ping" => Ok(serde_json::json!({})),
+            "tools/list" => Ok(serde_json::json!({
+                "tools": tools_list(self.options.allow_write)
+            })),
+            "tools/call" => {
+                let params: ToolCallParams = match params {
+                    Some(params) => serde_json::from_value(params)
+                        .map_err(|e| RpcError::invalid_params(e.to_string()))?,
+                    None => return Err(RpcError::invalid_params("Missing params")),
+                };
+                let known = tools_list(true).iter().any(|tool| tool.name == params.name);
+                if !known {
+                    return Err(RpcError::invalid_params(format!(
+                        "Unknown tool: {}",
+                        params.name
+                    )));
+                }
+
+                // Tool failures are results with `isError`, so the model gets to see them
+                let result = match handle_tool_call(&self.options, &self.storage, params).await {
+                    Ok(payload) => ToolCallResult {
+                        content: vec![ToolContent::Text {
+                            text: serde_json::to_string(&payload)
+                                .unwrap_or_else(|_| "{}".to_string()),
+                        }],
+                        is_error: false,
+                    },
+                    Err(err) => ToolCallResult {
+                        content: vec![ToolContent::Text { text: err }],
+                        is_error: true,
+                    },
+                };
+                serde_json::to_value(result).map_err(RpcError::internal)
+            }
+            "resources/list" => handle_resources_list(&self.storage, params).await,
+            "resources/read" => handle_resources_read(&self.storage, params).await,
+            "resources/templates/list" => Ok(serde_json::json!({
+                "resourceTemplates": resource_templates()
+            })),
+            "prompts/list" => handle_prompts_list(&self.storage).await,
+            "prompts/get" => handle_prompts_get(&self.storage, params).await,
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {method}"),
+            )),
+        }
+    }
+}
 
+async fn run_stdio(server: Arc<Server>) -> io::Result<()> {
+    // Lines are handled concurrently so a cancellation can overtake the request it cancels
+    let (output_tx, mut output_rx) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(message) = output_rx.recv().await {
+            let payload = message.to_string();
+            stdout.write_all(payload.as_bytes()).await?;
+            stdout.write_all(b"\n").await?;
+            stdout.flush().await?;
+        }
+        Ok::<_, io::Error>(())
+    });
+
+    let mut reader = BufReader::new(tokio::io::stdin());
+    let mut tasks = JoinSet::new();
     let mut line = String::new();
     loop {
         line.clear();
@@ -471,65 +1607,220 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         if bytes == 0 {
             break;
         }
-        let trimmed = line.trim();
+        let trimmed = line.trim().to_string();
         if trimmed.is_empty() {
             continue;
         }
 
-        let request: RpcRequest = match serde_json::from_str(trimmed) {
-            Ok(req) => req,
-            Err(err) => {
-                eprintln!("Invalid JSON: {err}");
-                continue;
+        let server = server.clone();
+        let output_tx = output_tx.clone();
+        tasks.spawn(async move {
+            if let Some(response) = server.handle_line(STDIO_SESSION, &trimmed).await {
+                let _ = output_tx.send(response);
             }
-        };
+        });
+        while tasks.try_join_next().is_some() {}
+    }
 
-        if request.id.is_none() {
-            continue;
+    while tasks.join_next().await.is_some() {}
+    drop(output_tx);
+    writer.await?
+}
+
+struct HttpState {
+    server: Arc<Server>,
+    token: String,
+    /// Issued on `initialize`, every later request has to carry one of them
+    sessions: Mutex<HashSet<String>>,
+}
+
+async fn run_http(server: Arc<Server>, addr: SocketAddr, token: String) -> io::Result<()> {
+    let state = Arc::new(HttpState {
+        server,
+        token,
+        sessions: Mutex::new(HashSet::new()),
+    });
+    let app = Router::new()
+        .route(
+            MCP_HTTP_PATH,
+            post(http_post).get(http_get).delete(http_delete),
+        )
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!(
+        "dev-vault-mcp listening on http://{}{}",
+        listener.local_addr()?,
+        MCP_HTTP_PATH
+    );
+    axum::serve(listener, app).await
+}
+
+fn plain_response(status: StatusCode, message: &str) -> Response {
+    (status, message.to_string()).into_response()
+}
+
+/// Checks the bearer token and, against DNS rebinding, a localhost `Origin` when the client
+/// sends one. `Some` is the response rejecting the request.
+fn reject_unauthorized(state: &HttpState, headers: &HeaderMap) -> Option<Response> {
+    let origin = headers
+        .get(header::ORIGIN)
+        .and_then(|value| value.to_str().ok());
+    if let Some(origin) = origin {
+        let host = url::Url::parse(origin)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        if !matches!(host.as_deref(), Some("localhost" | "127.0.0.1" | "[::1]")) {
+            return Some(plain_response(StatusCode::FORBIDDEN, "Origin not allowed"));
         }
+    }
 
-        let id = ensure_request_id(request.id);
-        let response = match request.method.as_str() {
-            "initialize" => match serde_json::to_value(build_initialize_result()) {
-                Ok(value) => ok_response(id, value),
-                Err(err) => error_response(id, err.to_string()),
-            },
-            "tools/list" => ok_response(id, serde_json::json!({ "tools": tools_list() })),
-            "tools/call" => {
-                let params = match request.params {
-                    Some(value) => {
-                        serde_json::from_value::<ToolCallParams>(value).map_err(|e| e.to_string())
-                    }
-                    None => Err("Missing params".to_string()),
-                };
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if bearer != Some(state.token.as_str()) {
+        let mut response = plain_response(StatusCode::UNAUTHORIZED, "Missing or invalid token");
+        response
+            .headers_mut()
+            .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        return Some(response);
+    }
+    None
+}
 
-                match params {
-                    Ok(params) => match handle_tool_call(&db_path, params).await {
-                        Ok(payload) => {
-                            let text = serde_json::to_string(&payload)
-                                .unwrap_or_else(|_| "{}".to_string());
-                            let result = ToolCallResult {
-                                content: vec![ToolContent::Text { text }],
-                                is_error: false,
-                            };
-                            match serde_json::to_value(result) {
-                                Ok(value) => ok_response(id, value),
-                                Err(err) => error_response(id, err.to_string()),
-                            }
-                        }
-                        Err(err) => tool_error_response(id, err),
-                    },
-                    Err(err) => tool_error_response(id, err),
-                }
-            }
-            _ => error_response(id, "Method not supported"),
-        };
+/// Requests other than `initialize` have to name a live session
+fn reject_unknown_session(state: &HttpState, headers: &HeaderMap) -> Option<Response> {
+    let session = headers
+        .get(SESSION_HEADER)
+        .and_then(|value| value.to_str().ok());
+    match session {
+        None => Some(plain_response(
+            StatusCode::BAD_REQUEST,
+            "Missing Mcp-Session-Id header",
+        )),
+        Some(session) if !state.sessions.lock().unwrap().contains(session) => {
+            Some(plain_response(StatusCode::NOT_FOUND, "Unknown session"))
+        }
+        Some(_) => None,
+    }
+}
+
+fn is_initialize(message: &Value) -> bool {
+    match message {
+        Value::Array(batch) => batch.iter().any(is_initialize),
+        message => message.get("method").and_then(Value::as_str) == Some("initialize"),
+    }
+}
+
+/// Answers with one SSE event when the client accepts a stream, plain JSON otherwise
+async fn http_post(
+    State(state): State<Arc<HttpState>>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    if let Some(response) = reject_unauthorized(&state, &headers) {
+        return response;
+    }
 
-        let payload = serde_json::to_string(&response)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
-        stdout.write_all(payload.as_bytes()).await?;
-        stdout.write_all(b"\n").await?;
-        stdout.flush().await?;
+    let initialize = serde_json::from_str::<Value>(&body)
+        .map(|message| is_initialize(&message))
+        .unwrap_or(false);
+    let session = if initialize {
+        uuid::Uuid::new_v4().to_string()
+    } else {
+        if let Some(response) = reject_unknown_session(&state, &headers) {
+            return response;
+        }
+        headers
+            .get(SESSION_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let Some(reply) = state.server.handle_line(&session, body.trim()).await else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+
+    let accepts_stream = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"));
+    let mut response = if accepts_stream {
+        (
+            [(header::CONTENT_TYPE, "text/event-stream")],
+            format!("event: message\ndata: {reply}\n\n"),
+        )
+            .into_response()
+    } else {
+        (
+            [(header::CONTENT_TYPE, "application/json")],
+            reply.to_string(),
+        )
+            .into_response()
+    };
+
+    if initialize {
+        if let Ok(value) = HeaderValue::from_str(&session) {
+            response.headers_mut().insert(SESSION_HEADER, value);
+        }
+        state.sessions.lock().unwrap().insert(session);
+    }
+    response
+}
+
+/// The server never sends requests or notifications of its own, so there is no stream to open
+async fn http_get(State(state): State<Arc<HttpState>>, headers: HeaderMap) -> Response {
+    if let Some(response) = reject_unauthorized(&state, &headers) {
+        return response;
+    }
+    let mut response = plain_response(StatusCode::METHOD_NOT_ALLOWED, "Use POST");
+    response
+        .headers_mut()
+        .insert(header::ALLOW, HeaderValue::from_static("POST, DELETE"));
+    response
+}
+
+async fn http_delete(State(state): State<Arc<HttpState>>, headers: HeaderMap) -> Response {
+    if let Some(response) = reject_unauthorized(&state, &headers) {
+        return response;
+    }
+    if let Some(response) = reject_unknown_session(&state, &headers) {
+        return response;
+    }
+    if let Some(session) = headers
+        .get(SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+    {
+        state.sessions.lock().unwrap().remove(session);
+    }
+    StatusCode::NO_CONTENT.into_response()
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let options = parse_args().map_err(io::Error::other)?;
+    let http = options.http;
+    let token = options.token.clone();
+    let server = match Server::open(options).await {
+        Ok(server) => Arc::new(server),
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    };
+
+    match http {
+        Some(addr) => {
+            let token = token.unwrap_or_else(|| {
+                let token = uuid::Uuid::new_v4().simple().to_string();
+                eprintln!("Bearer token: {token}");
+                token
+            });
+            run_http(server, addr, token).await?;
+        }
+        None => run_stdio(server).await?,
     }
 
     Ok(())
@@ -1,10 +1,15 @@
-use crate::domain::parsers::ScrapeProgress;
+use crate::domain::markdown_sync::MarkdownFolder;
+use crate::domain::parsers::{self, ScrapeCancelled, ScrapeProgress, SourceFormat};
+use crate::domain::snippet_formats;
 use crate::domain::{ConfigManager, DocumentationManager, SearchEngine, Storage};
 use crate::models::config::AppConfig;
 use crate::models::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, State};
 use tokio::sync::{mpsc, Mutex};
+use tokio_util::sync::CancellationToken;
 
 mod mcp;
 pub use mcp::*;
@@ -13,6 +18,89 @@ pub struct AppState {
     pub storage: Arc<Mutex<Storage>>,
     pub config_manager: Arc<ConfigManager>,
     pub doc_manager: Arc<Mutex<DocumentationManager>>,
+    pub app_dir: PathBuf,
+    /// Cancellation tokens of the installs in progress, keyed by documentation name
+    pub doc_installs: Arc<Mutex<HashMap<String, CancellationToken>>>,
+}
+
+/// Shared state the background purge of expired trash entries works on
+pub struct TrashPurge {
+    pub storage: Arc<Mutex<Storage>>,
+    pub doc_manager: Arc<Mutex<DocumentationManager>>,
+    pub config_manager: Arc<ConfigManager>,
+}
+
+impl TrashPurge {
+    /// Purges what has been in the trash longer than `trash.retention_days`, returns
+    /// (items, documentations). The config is re-read on every pass so a changed
+    /// retention applies without a restart.
+    pub async fn purge_expired(&self) -> anyhow::Result<(u64, u64)> {
+        let retention_days = self
+            .config_manager
+            .load_config()
+            .await?
+            .trash
+            .retention_days;
+        if retention_days == 0 {
+            return Ok((0, 0));
+        }
+
+        let deleted_before = chrono::Utc::now().timestamp() - retention_days as i64 * 86_400;
+        let items = self
+            .storage
+            .lock()
+            .await
+            .purge_deleted_items(Some(deleted_before))
+            .await?;
+        let docs = self
+            .doc_manager
+            .lock()
+            .await
+            .purge_deleted_documentations(Some(deleted_before))
+            .await?;
+        Ok((items, docs))
+    }
+}
+
+/// Shared state the background Markdown folder sync works on
+pub struct MarkdownSync {
+    pub storage: Arc<Mutex<Storage>>,
+    pub config_manager: Arc<ConfigManager>,
+}
+
+impl MarkdownSync {
+    /// One pass when the sync is enabled, the config is re-read every time so a new
+    /// folder applies without a restart. Returns the pass report and the delay until
+    /// the next one.
+    pub async fn sync_if_enabled(
+        &self,
+    ) -> (
+        anyhow::Result<Option<MarkdownSyncReport>>,
+        std::time::Duration,
+    ) {
+        let config = match self.config_manager.load_config().await {
+            Ok(config) => config.markdown_sync,
+            Err(e) => return (Err(e), std::time::Duration::from_secs(60)),
+        };
+        let delay = std::time::Duration::from_secs(config.interval_secs.max(1) as u64);
+        if !config.enabled || config.directory.trim().is_empty() {
+            return (Ok(None), delay);
+        }
+
+        let report = match read_markdown_folder(PathBuf::from(config.directory.trim())).await {
+            Ok(folder) => {
+                let storage = self.storage.lock().await;
+                storage.apply_markdown_folder(folder).await.map(Some)
+            }
+            Err(e) => Err(e),
+        };
+        (report, delay)
+    }
+}
+
+/// Scans the synced folder on the blocking pool, the storage lock is taken only after it
+async fn read_markdown_folder(directory: PathBuf) -> anyhow::Result<MarkdownFolder> {
+    tokio::task::spawn_blocking(move || MarkdownFolder::read(&directory)).await?
 }
 
 #[tauri::command]
@@ -30,7 +118,9 @@ pub async fn save_config(state: State<'_, AppState>, config: AppConfig) -> Resul
         .config_manager
         .save_config(&config)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    state.storage.lock().await.set_history(config.history);
+    Ok(())
 }
 
 #[tauri::command]
@@ -57,6 +147,283 @@ pub async fn delete_item(state: State<'_, AppState>, id: i64) -> Result<bool, St
     storage.delete_item(id).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn restore_item(state: State<'_, AppState>, id: i64) -> Result<bool, String> {
+    let storage = state.storage.lock().await;
+    storage.restore_item(id).await.map_err(|e| e.to_string())
+}
+
+/// Deleted items and documentation versions, most recently deleted first
+#[tauri::command]
+pub async fn list_trash(state: State<'_, AppState>) -> Result<Vec<TrashEntry>, String> {
+    let mut entries = state
+        .storage
+        .lock()
+        .await
+        .list_deleted_items()
+        .await
+        .map_err(|e| e.to_string())?;
+    entries.extend(
+        state
+            .doc_manager
+            .lock()
+            .await
+            .list_deleted_documentations()
+            .await
+            .map_err(|e| e.to_string())?,
+    );
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn purge_from_trash(
+    state: State<'_, AppState>,
+    kind: TrashKind,
+    id: i64,
+) -> Result<bool, String> {
+    match kind {
+        TrashKind::Item => state.storage.lock().await.purge_item(id).await,
+        TrashKind::Documentation => state.doc_manager.lock().await.purge_documentation(id).await,
+    }
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn empty_trash(state: State<'_, AppState>) -> Result<(), String> {
+    state
+        .storage
+        .lock()
+        .await
+        .purge_deleted_items(None)
+        .await
+        .map_err(|e| e.to_string())?;
+    state
+        .doc_manager
+        .lock()
+        .await
+        .purge_deleted_documentations(None)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Returns the number of exported items
+#[tauri::command]
+pub async fn export_vault(
+    state: State<'_, AppState>,
+    path: String,
+    format: VaultFormat,
+) -> Result<usize, String> {
+    let storage = state.storage.lock().await;
+    storage
+        .export_vault(&PathBuf::from(path), format)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_vault(
+    state: State<'_, AppState>,
+    path: String,
+    format: VaultFormat,
+    conflict: ImportConflict,
+) -> Result<VaultImportSummary, String> {
+    let storage = state.storage.lock().await;
+    storage
+        .import_vault(&PathBuf::from(path), format, conflict)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_snippets(
+    state: State<'_, AppState>,
+    path: String,
+    format: SnippetFormat,
+    conflict: ImportConflict,
+) -> Result<VaultImportSummary, String> {
+    let storage = state.storage.lock().await;
+    storage
+        .import_snippets(&PathBuf::from(path), format, conflict)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Exports the items of a tag or of a search result, everything when neither is given.
+/// Returns the number of exported snippets.
+#[tauri::command]
+pub async fn export_vscode_snippets(
+    state: State<'_, AppState>,
+    path: String,
+    tag_id: Option<i64>,
+    query: Option<SearchQuery>,
+) -> Result<usize, String> {
+    let items = match query {
+        Some(query) => {
+            let storage = state.storage.lock().await;
+            let pool = storage.pool.clone();
+            let embedder = storage.embedder();
+            drop(storage);
+            let config = state.config_manager.load_config().await.unwrap_or_default();
+            SearchEngine::new(pool)
+                .with_embedder(embedder)
+                .with_config(&config.search)
+                .search(query)
+                .await
+                .map_err(|e| e.to_string())?
+                .items
+        }
+        None => state
+            .storage
+            .lock()
+            .await
+            .list_items(Some(i64::MAX), None, None, tag_id.map(|tag| vec![tag]))
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+
+    snippet_formats::write_vscode_snippets(&PathBuf::from(path), &items).map_err(|e| e.to_string())
+}
+
+/// Runs a Markdown folder sync pass right away with the configured folder
+#[tauri::command]
+pub async fn sync_markdown_now(state: State<'_, AppState>) -> Result<MarkdownSyncReport, String> {
+    let directory = markdown_sync_directory(&state).await?;
+    let folder = read_markdown_folder(directory)
+        .await
+        .map_err(|e| e.to_string())?;
+    let storage = state.storage.lock().await;
+    storage
+        .apply_markdown_folder(folder)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn resolve_markdown_conflict(
+    state: State<'_, AppState>,
+    item_id: i64,
+    keep: SyncSide,
+) -> Result<(), String> {
+    let directory = markdown_sync_directory(&state).await?;
+    let storage = state.storage.lock().await;
+    storage
+        .resolve_markdown_conflict(&directory, item_id, keep)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn markdown_sync_directory(state: &State<'_, AppState>) -> Result<PathBuf, String> {
+    let config = state
+        .config_manager
+        .load_config()
+        .await
+        .map_err(|e| e.to_string())?;
+    match config.markdown_sync.directory.trim() {
+        "" => Err("Папка для синхронизации Markdown не задана".to_string()),
+        directory => Ok(PathBuf::from(directory)),
+    }
+}
+
+#[tauri::command]
+pub async fn link_config_file(
+    state: State<'_, AppState>,
+    item_id: i64,
+    path: String,
+) -> Result<(), String> {
+    let storage = state.storage.lock().await;
+    storage
+        .link_config_file(item_id, &path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn unlink_config_file(state: State<'_, AppState>, item_id: i64) -> Result<(), String> {
+    let storage = state.storage.lock().await;
+    storage
+        .unlink_config_file(item_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_config_links(state: State<'_, AppState>) -> Result<Vec<ConfigLink>, String> {
+    let storage = state.storage.lock().await;
+    storage.list_config_links().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn config_drift_report(state: State<'_, AppState>) -> Result<Vec<ConfigDrift>, String> {
+    let storage = state.storage.lock().await;
+    storage
+        .config_drift_report()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn preview_config_write_back(
+    state: State<'_, AppState>,
+    item_id: i64,
+) -> Result<ConfigWriteBackPreview, String> {
+    let storage = state.storage.lock().await;
+    storage
+        .preview_config_write_back(item_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn write_config_back(state: State<'_, AppState>, item_id: i64) -> Result<(), String> {
+    let storage = state.storage.lock().await;
+    storage
+        .write_config_back(item_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_item_revisions(
+    state: State<'_, AppState>,
+    item_id: i64,
+) -> Result<Vec<ItemRevision>, String> {
+    let storage = state.storage.lock().await;
+    storage
+        .list_item_revisions(item_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `None` on either side is the current version of the item
+#[tauri::command]
+pub async fn diff_item_revisions(
+    state: State<'_, AppState>,
+    item_id: i64,
+    from_revision_id: Option<i64>,
+    to_revision_id: Option<i64>,
+) -> Result<String, String> {
+    let storage = state.storage.lock().await;
+    storage
+        .diff_item_revisions(item_id, from_revision_id, to_revision_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_item_revision(
+    state: State<'_, AppState>,
+    item_id: i64,
+    revision_id: i64,
+) -> Result<bool, String> {
+    let storage = state.storage.lock().await;
+    storage
+        .restore_item_revision(item_id, revision_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn list_items(
     state: State<'_, AppState>,
@@ -137,9 +504,24 @@ pub async fn search(
 
     let storage = state.storage.lock().await;
     let pool = storage.pool.clone();
+    let embedder = storage.embedder();
     drop(storage);
 
-    let search_engine = SearchEngine::new(pool);
+    let config = state
+        .config_manager
+        .load_config()
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(
+                "[Command] Failed to load config, using default search weights: {}",
+                e
+            );
+            AppConfig::default()
+        });
+
+    let search_engine = SearchEngine::new(pool)
+        .with_embedder(embedder)
+        .with_config(&config.search);
     let result = search_engine.search(query).await.map_err(|e| {
         tracing::error!("[Command] Search error: {}", e);
         e.to_string()
@@ -176,6 +558,37 @@ pub async fn list_available_docs(
     }
 }
 
+#[tauri::command]
+pub async fn list_custom_doc_sources(
+    state: State<'_, AppState>,
+) -> Result<CustomDocSourcesReport, String> {
+    tracing::info!("[Command] list_custom_doc_sources called");
+    Ok(parsers::load_custom_sources(&state.app_dir))
+}
+
+#[tauri::command]
+pub async fn add_custom_doc_source(
+    state: State<'_, AppState>,
+    content: String,
+    format: String,
+) -> Result<CustomDocSourcesReport, String> {
+    tracing::info!("[Command] add_custom_doc_source called ({})", format);
+    let format = SourceFormat::parse(&format).map_err(|e| e.to_string())?;
+    parsers::add_custom_source(&state.app_dir, &content, format).map_err(|e| {
+        tracing::error!("[Command] Failed to add custom doc source: {:#}", e);
+        format!("{:#}", e)
+    })
+}
+
+#[tauri::command]
+pub async fn remove_custom_doc_source(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<CustomDocSourcesReport, String> {
+    tracing::info!("[Command] remove_custom_doc_source called for: {}", name);
+    parsers::remove_custom_source(&state.app_dir, &name).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn list_installed_docs(state: State<'_, AppState>) -> Result<Vec<Documentation>, String> {
     tracing::info!("[Command] list_installed_docs called");
@@ -198,8 +611,25 @@ pub async fn install_documentation(
     app: AppHandle,
     state: State<'_, AppState>,
     name: String,
+    version: Option<String>,
 ) -> Result<Documentation, String> {
-    tracing::info!("[Command] install_documentation called for: {}", name);
+    tracing::info!(
+        "[Command] install_documentation called for: {} ({:?})",
+        name,
+        version
+    );
+
+    let cancel = CancellationToken::new();
+    {
+        let mut installs = state.doc_installs.lock().await;
+        if installs.contains_key(&name) {
+            return Err(format!(
+                "Documentation '{}' is already being installed",
+                name
+            ));
+        }
+        installs.insert(name.clone(), cancel.clone());
+    }
 
     let (progress_tx, mut progress_rx) = mpsc::channel::<ScrapeProgress>(100);
 
@@ -212,10 +642,12 @@ pub async fn install_documentation(
 
     let doc_manager = state.doc_manager.lock().await;
 
-    match doc_manager
-        .install_documentation_with_progress(&name, progress_tx)
-        .await
-    {
+    let result = doc_manager
+        .install_documentation_with_progress(&name, version.as_deref(), progress_tx, cancel)
+        .await;
+    state.doc_installs.lock().await.remove(&name);
+
+    match result {
         Ok(doc) => {
             tracing::info!(
                 "[Command] Documentation installed successfully: {}",
@@ -230,18 +662,149 @@ pub async fn install_documentation(
                 name,
                 e
             );
+            if e.is::<ScrapeCancelled>() {
+                let _ = app.emit("doc-install-cancelled", &name);
+                return Err(ScrapeCancelled.to_string());
+            }
             let _ = app.emit("doc-install-error", &e.to_string());
             Err(e.to_string())
         }
     }
 }
 
+/// Stops a running install between pages, its checkpoint is kept for resuming
 #[tauri::command]
-pub async fn update_documentation(
+pub async fn cancel_doc_install(state: State<'_, AppState>, name: String) -> Result<bool, String> {
+    tracing::info!("[Command] cancel_doc_install called for: {}", name);
+    let installs = state.doc_installs.lock().await;
+
+    match installs.get(&name) {
+        Some(cancel) => {
+            cancel.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+pub async fn list_doc_install_checkpoints(
+    state: State<'_, AppState>,
+) -> Result<Vec<DocInstallCheckpoint>, String> {
+    let doc_manager = state.doc_manager.lock().await;
+    doc_manager
+        .list_install_checkpoints()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn discard_doc_install_checkpoint(
+    state: State<'_, AppState>,
+    name: String,
+    version: String,
+) -> Result<bool, String> {
+    tracing::info!(
+        "[Command] discard_doc_install_checkpoint called for: {} {}",
+        name,
+        version
+    );
+    let doc_manager = state.doc_manager.lock().await;
+    doc_manager
+        .discard_install_checkpoint(&name, &version)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rebuild_documentation_from_cache(
     app: AppHandle,
     state: State<'_, AppState>,
     doc_id: i64,
+) -> Result<DocumentationUpdate, String> {
+    tracing::info!(
+        "[Command] rebuild_documentation_from_cache called for doc_id: {}",
+        doc_id
+    );
+
+    let (progress_tx, mut progress_rx) = mpsc::channel::<ScrapeProgress>(100);
+
+    let app_clone = app.clone();
+    tokio::spawn(async move {
+        while let Some(progress) = progress_rx.recv().await {
+            let _ = app_clone.emit("doc-update-progress", &progress);
+        }
+    });
+
+    let doc_manager = state.doc_manager.lock().await;
+
+    match doc_manager
+        .rebuild_documentation_from_cache(doc_id, progress_tx)
+        .await
+    {
+        Ok(update) => {
+            let _ = app.emit("doc-update-complete", &update);
+            Ok(update)
+        }
+        Err(e) => {
+            tracing::error!(
+                "[Command] Failed to rebuild documentation (id={}): {:?}",
+                doc_id,
+                e
+            );
+            let _ = app.emit("doc-update-error", &e.to_string());
+            Err(e.to_string())
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn clear_doc_http_cache(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<usize, String> {
+    tracing::info!("[Command] clear_doc_http_cache called for: {}", name);
+    let doc_manager = state.doc_manager.lock().await;
+    doc_manager
+        .clear_http_cache(&name)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_doc_versions(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Vec<Documentation>, String> {
+    tracing::info!("[Command] list_doc_versions called for: {}", name);
+    let doc_manager = state.doc_manager.lock().await;
+    doc_manager
+        .list_doc_versions(&name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_active_doc_version(
+    state: State<'_, AppState>,
+    doc_id: i64,
 ) -> Result<Documentation, String> {
+    tracing::info!(
+        "[Command] set_active_doc_version called for doc_id: {}",
+        doc_id
+    );
+    let doc_manager = state.doc_manager.lock().await;
+    doc_manager
+        .set_active_version(doc_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_documentation(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    doc_id: i64,
+) -> Result<DocumentationUpdate, String> {
     tracing::info!(
         "🔄 [Command] update_documentation called for doc_id: {}",
         doc_id
@@ -262,13 +825,14 @@ pub async fn update_documentation(
         .update_documentation_with_progress(doc_id, progress_tx)
         .await
     {
-        Ok(doc) => {
+        Ok(update) => {
             tracing::info!(
-                "✓ [Command] Documentation updated successfully: {}",
-                doc.display_name
+                "✓ [Command] Documentation updated successfully: {} ({:?})",
+                update.documentation.display_name,
+                update.summary
             );
-            let _ = app.emit("doc-update-complete", &doc);
-            Ok(doc)
+            let _ = app.emit("doc-update-complete", &update);
+            Ok(update)
         }
         Err(e) => {
             tracing::error!(
@@ -309,6 +873,18 @@ pub async fn delete_documentation(state: State<'_, AppState>, doc_id: i64) -> Re
     }
 }
 
+#[tauri::command]
+pub async fn restore_documentation(
+    state: State<'_, AppState>,
+    doc_id: i64,
+) -> Result<bool, String> {
+    let doc_manager = state.doc_manager.lock().await;
+    doc_manager
+        .restore_documentation(doc_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_doc_entries(
     state: State<'_, AppState>,
@@ -327,8 +903,13 @@ pub async fn get_doc_entry_by_path(
     state: State<'_, AppState>,
     doc_id: i64,
     path: String,
+    version: Option<String>,
 ) -> Result<DocEntry, String> {
     let doc_manager = state.doc_manager.lock().await;
+    let doc_id = doc_manager
+        .resolve_doc_version(doc_id, version.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
     doc_manager
         .get_doc_entry_by_path(doc_id, &path)
         .await
@@ -340,8 +921,13 @@ pub async fn get_doc_tree(
     state: State<'_, AppState>,
     doc_id: i64,
     parent_path: Option<String>,
+    version: Option<String>,
 ) -> Result<Vec<DocTreeNode>, String> {
     let doc_manager = state.doc_manager.lock().await;
+    let doc_id = doc_manager
+        .resolve_doc_version(doc_id, version.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
 
     doc_manager
         .get_doc_tree_level(doc_id, parent_path)
@@ -1,22 +1,53 @@
 use crate::models::{
-    AvailableDocumentation, DocEntry, DocTreeNode, Documentation, DocumentationGraph,
-    DocumentationGraphEdge, DocumentationGraphNode, ParsedDocEntry,
+    AvailableDocumentation, DocEntry, DocEntryRef, DocInstallCheckpoint, DocTreeNode,
+    DocUpdateSummary, Documentation, DocumentationGraph, DocumentationGraphEdge,
+    DocumentationGraphNode, DocumentationUpdate, ParsedDocEntry, TrashEntry, TrashKind,
 };
 use anyhow::{Context, Result};
-use sqlx::{Pool, Row, Sqlite};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 use super::parsers::{
-    get_available_documentations, get_doc_metadata_by_name, scrape_documentation_with_progress,
-    ProgressSender,
+    get_available_documentations, get_versioned_doc_metadata, is_versioned_source,
+    scrape_documentation, CheckpointEvent, CrawlState, HttpCache, ProgressSender, ScrapeCancelled,
+    ScrapeRun,
 };
 
 pub struct DocumentationManager {
     pool: Pool<Sqlite>,
+    /// Root of the per-source page caches, scrapes go straight to the network without it
+    http_cache_root: Option<PathBuf>,
 }
 
 impl DocumentationManager {
     pub fn new(pool: Pool<Sqlite>) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            http_cache_root: None,
+        }
+    }
+
+    pub fn with_http_cache(mut self, root: PathBuf) -> Self {
+        self.http_cache_root = Some(root);
+        self
+    }
+
+    pub fn http_cache(&self, name: &str) -> Option<HttpCache> {
+        self.http_cache_root
+            .as_deref()
+            .map(|root| HttpCache::for_source(root, name))
+    }
+
+    fn cached_run(&self, name: &str, run: ScrapeRun) -> ScrapeRun {
+        match self.http_cache(name) {
+            Some(cache) => run.with_cache(cache),
+            None => run,
+        }
     }
 
     pub async fn list_available_documentations(&self) -> Result<Vec<AvailableDocumentation>> {
@@ -36,9 +67,10 @@ impl DocumentationManager {
 
         let rows = sqlx::query(
             "SELECT id, name, display_name, version, source_url,
-                    installed_at, updated_at, metadata
+                    installed_at, updated_at, metadata, is_active
              FROM documentations
-             ORDER BY display_name",
+             WHERE deleted_at IS NULL
+             ORDER BY display_name, installed_at",
         )
         .fetch_all(&self.pool)
         .await
@@ -48,19 +80,7 @@ impl DocumentationManager {
 
         let mut docs = Vec::new();
         for row in rows {
-            let metadata_str: String = row.get("metadata");
-            let metadata = serde_json::from_str(&metadata_str).ok();
-
-            let doc = Documentation {
-                id: row.get("id"),
-                name: row.get("name"),
-                display_name: row.get("display_name"),
-                version: row.get("version"),
-                source_url: row.get("source_url"),
-                installed_at: row.get("installed_at"),
-                updated_at: row.get("updated_at"),
-                metadata,
-            };
+            let doc = Self::documentation_from_row(&row);
             tracing::debug!(
                 "  → {} (ID: {}, v{})",
                 doc.display_name,
@@ -73,27 +93,289 @@ impl DocumentationManager {
         Ok(docs)
     }
 
+    /// Versions of one documentation installed side by side, the active one first
+    pub async fn list_doc_versions(&self, name: &str) -> Result<Vec<Documentation>> {
+        let rows = sqlx::query(
+            "SELECT id, name, display_name, version, source_url,
+                    installed_at, updated_at, metadata, is_active
+             FROM documentations
+             WHERE name = ?1 AND deleted_at IS NULL
+             ORDER BY is_active DESC, installed_at DESC",
+        )
+        .bind(name)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list documentation versions")?;
+
+        Ok(rows.iter().map(Self::documentation_from_row).collect())
+    }
+
+    /// Installing a version of a documentation makes it the active one. `None` installs the
+    /// source's default version.
+    ///
+    /// The crawl is checkpointed as it goes: a cancelled, failed or crashed install of the
+    /// same version picks up from its checkpoint on the next call.
     pub async fn install_documentation_with_progress(
         &self,
         name: &str,
+        version: Option<&str>,
         progress_tx: ProgressSender,
+        cancel: CancellationToken,
     ) -> Result<Documentation> {
-        tracing::info!("=== Starting documentation installation for: {} ===", name);
+        tracing::info!(
+            "=== Starting documentation installation for: {} ({}) ===",
+            name,
+            version.unwrap_or("default version")
+        );
+
+        let metadata = get_versioned_doc_metadata(name, version)?;
+        self.ensure_not_installed(&metadata.name, &metadata.version)
+            .await?;
+
+        let (job_id, resume) = self
+            .open_install_job(&metadata.name, &metadata.version)
+            .await?;
+        if !resume.is_empty() {
+            tracing::info!(
+                "Resuming interrupted install: {} visited, {} queued, {} entries",
+                resume.visited.len(),
+                resume.frontier.len(),
+                resume.entries.len()
+            );
+        }
+
+        let (checkpoint_tx, checkpoint_rx) = mpsc::channel::<CheckpointEvent>(32);
+        let writer = tokio::spawn(Self::write_checkpoints(
+            self.pool.clone(),
+            job_id,
+            checkpoint_rx,
+        ));
+
+        let run = ScrapeRun::new(progress_tx)
+            .with_cancel(cancel)
+            .with_checkpoint(resume, checkpoint_tx);
+        let run = self.cached_run(name, run);
+        let scraped = scrape_documentation(name, Some(&metadata.version), run).await;
+
+        // The run (and its sender) is gone, wait for the writer to flush what is left
+        match writer.await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::warn!("Failed to save install checkpoint: {:?}", e),
+            Err(e) => tracing::warn!("Checkpoint writer panicked: {:?}", e),
+        }
 
-        let entries = scrape_documentation_with_progress(name, progress_tx)
+        let entries = match scraped {
+            Ok(entries) => entries,
+            Err(e) => {
+                let status = if e.is::<ScrapeCancelled>() {
+                    "cancelled"
+                } else {
+                    "failed"
+                };
+                self.set_install_job_status(job_id, status).await?;
+                return Err(e.context("Failed to scrape documentation"));
+            }
+        };
+
+        let doc = self
+            .install_documentation_with_entries(name, version, entries)
+            .await?;
+
+        sqlx::query("DELETE FROM doc_install_jobs WHERE id = ?1")
+            .bind(job_id)
+            .execute(&self.pool)
             .await
-            .context("Failed to scrape documentation")?;
+            .context("Failed to clear install checkpoint")?;
+
+        Ok(doc)
+    }
+
+    /// Installs that were cancelled, failed or cut short and can be resumed
+    pub async fn list_install_checkpoints(&self) -> Result<Vec<DocInstallCheckpoint>> {
+        let rows = sqlx::query(
+            "SELECT j.name, j.version, j.status, j.started_at, j.updated_at,
+                    (SELECT COUNT(*) FROM doc_install_frontier f
+                     WHERE f.job_id = j.id AND f.visited = 1) AS visited_count,
+                    (SELECT COUNT(*) FROM doc_install_frontier f
+                     WHERE f.job_id = j.id AND f.visited = 0) AS queued_count,
+                    (SELECT COUNT(*) FROM doc_install_entries e
+                     WHERE e.job_id = j.id) AS entries_count
+             FROM doc_install_jobs j
+             ORDER BY j.updated_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list install checkpoints")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DocInstallCheckpoint {
+                name: row.get("name"),
+                version: row.get("version"),
+                status: row.get("status"),
+                visited_count: row.get("visited_count"),
+                queued_count: row.get("queued_count"),
+                entries_count: row.get("entries_count"),
+                started_at: row.get("started_at"),
+                updated_at: row.get("updated_at"),
+            })
+            .collect())
+    }
+
+    /// Drops a saved crawl so the next install starts from scratch
+    pub async fn discard_install_checkpoint(&self, name: &str, version: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM doc_install_jobs WHERE name = ?1 AND version = ?2")
+            .bind(name)
+            .bind(version)
+            .execute(&self.pool)
+            .await
+            .context("Failed to discard install checkpoint")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Creates the job for `(name, version)` or reopens an interrupted one with its state
+    async fn open_install_job(&self, name: &str, version: &str) -> Result<(i64, CrawlState)> {
+        let now = chrono::Utc::now().timestamp();
+        let job_id: i64 = sqlx::query_scalar(
+            "INSERT INTO doc_install_jobs (name, version, status, started_at, updated_at)
+             VALUES (?1, ?2, 'running', ?3, ?3)
+             ON CONFLICT(name, version) DO UPDATE SET status = 'running', updated_at = ?3
+             RETURNING id",
+        )
+        .bind(name)
+        .bind(version)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to open install checkpoint")?;
+
+        let frontier = sqlx::query(
+            "SELECT path, depth, visited FROM doc_install_frontier
+             WHERE job_id = ?1 ORDER BY rowid",
+        )
+        .bind(job_id)
+        .fetch_all(&self.pool)
+        .await?;
 
-        self.install_documentation_with_entries(name, entries).await
+        let mut state = CrawlState::default();
+        for row in frontier {
+            let path: String = row.get("path");
+            if row.get::<bool, _>("visited") {
+                state.visited.push(path);
+            } else {
+                state
+                    .frontier
+                    .push((path, row.get::<i64, _>("depth") as usize));
+            }
+        }
+
+        let entries = sqlx::query(
+            "SELECT path, title, content, entry_type, parent_path FROM doc_install_entries
+             WHERE job_id = ?1 ORDER BY rowid",
+        )
+        .bind(job_id)
+        .fetch_all(&self.pool)
+        .await?;
+        state.entries = entries
+            .iter()
+            .map(|row| ParsedDocEntry {
+                path: row.get("path"),
+                title: row.get("title"),
+                content: row.get("content"),
+                entry_type: row.get("entry_type"),
+                parent_path: row.get("parent_path"),
+            })
+            .collect();
+
+        Ok((job_id, state))
+    }
+
+    async fn set_install_job_status(&self, job_id: i64, status: &str) -> Result<()> {
+        sqlx::query("UPDATE doc_install_jobs SET status = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(status)
+            .bind(chrono::Utc::now().timestamp())
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to update install checkpoint")?;
+        Ok(())
+    }
+
+    async fn write_checkpoints(
+        pool: Pool<Sqlite>,
+        job_id: i64,
+        mut checkpoint_rx: mpsc::Receiver<CheckpointEvent>,
+    ) -> Result<()> {
+        while let Some(event) = checkpoint_rx.recv().await {
+            let mut tx = pool.begin().await?;
+
+            let queued = match event {
+                CheckpointEvent::Seeded { queued } => queued,
+                CheckpointEvent::Visited {
+                    path,
+                    entries,
+                    queued,
+                } => {
+                    sqlx::query(
+                        "INSERT INTO doc_install_frontier (job_id, path, visited) VALUES (?1, ?2, 1)
+                         ON CONFLICT(job_id, path) DO UPDATE SET visited = 1",
+                    )
+                    .bind(job_id)
+                    .bind(&path)
+                    .execute(&mut *tx)
+                    .await?;
+
+                    for entry in &entries {
+                        sqlx::query(
+                            "INSERT INTO doc_install_entries (job_id, path, title, content, entry_type, parent_path)
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        )
+                        .bind(job_id)
+                        .bind(&entry.path)
+                        .bind(&entry.title)
+                        .bind(&entry.content)
+                        .bind(&entry.entry_type)
+                        .bind(&entry.parent_path)
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                    queued
+                }
+            };
+
+            for (path, depth) in &queued {
+                sqlx::query(
+                    "INSERT OR IGNORE INTO doc_install_frontier (job_id, path, depth) VALUES (?1, ?2, ?3)",
+                )
+                .bind(job_id)
+                .bind(path)
+                .bind(*depth as i64)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            sqlx::query("UPDATE doc_install_jobs SET updated_at = ?1 WHERE id = ?2")
+                .bind(chrono::Utc::now().timestamp())
+                .bind(job_id)
+                .execute(&mut *tx)
+                .await?;
+
+            tx.commit().await?;
+        }
+
+        Ok(())
     }
 
-    async fn install_documentation_with_entries(
+    pub async fn install_documentation_with_entries(
         &self,
         name: &str,
+        version: Option<&str>,
         entries: Vec<ParsedDocEntry>,
     ) -> Result<Documentation> {
-        let metadata =
-            get_doc_metadata_by_name(name).context(format!("Documentation not found: {}", name))?;
+        let metadata = get_versioned_doc_metadata(name, version)?;
+        self.ensure_not_installed(&metadata.name, &metadata.version)
+            .await?;
 
         let now = chrono::Utc::now().timestamp();
 
@@ -119,23 +401,17 @@ impl DocumentationManager {
         .await?
         .get::<i64, _>(0);
 
+        sqlx::query("UPDATE documentations SET is_active = (id = ?1) WHERE name = ?2")
+            .bind(doc_id)
+            .bind(&metadata.name)
+            .execute(&mut *tx)
+            .await?;
+
         tracing::info!("Step 2: Inserting {} entries in bulk", entries.len());
 
         for chunk in entries.chunks(100) {
             for entry in chunk {
-                sqlx::query(
-                    "INSERT INTO doc_entries (doc_id, path, title, content, entry_type, parent_path, created_at)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
-                )
-                .bind(doc_id)
-                .bind(&entry.path)
-                .bind(&entry.title)
-                .bind(&entry.content)
-                .bind(&entry.entry_type)
-                .bind(&entry.parent_path)
-                .bind(now)
-                .execute(&mut *tx)
-                .await?;
+                Self::insert_entry(&mut tx, doc_id, entry, now).await?;
             }
         }
 
@@ -149,15 +425,17 @@ impl DocumentationManager {
         &self,
         doc_id: i64,
         progress_tx: ProgressSender,
-    ) -> Result<Documentation> {
+    ) -> Result<DocumentationUpdate> {
         tracing::info!(
             "=== Starting documentation update for doc_id: {} ===",
             doc_id
         );
 
         let doc = self.get_documentation(doc_id).await?;
+        let version = Self::update_version(&doc);
 
-        let entries = scrape_documentation_with_progress(&doc.name, progress_tx)
+        let run = self.cached_run(&doc.name, ScrapeRun::new(progress_tx));
+        let entries = scrape_documentation(&doc.name, version, run)
             .await
             .context("Failed to scrape documentation")?;
 
@@ -165,44 +443,118 @@ impl DocumentationManager {
             .await
     }
 
-    async fn update_documentation_with_entries(
+    /// Re-parses the cached pages of a scraped documentation without any network access and
+    /// applies the result like an update
+    pub async fn rebuild_documentation_from_cache(
+        &self,
+        doc_id: i64,
+        progress_tx: ProgressSender,
+    ) -> Result<DocumentationUpdate> {
+        let doc = self.get_documentation(doc_id).await?;
+        tracing::info!("=== Rebuilding {} from the page cache ===", doc.name);
+
+        let cache = self
+            .http_cache(&doc.name)
+            .filter(|cache| !cache.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("No cached pages for {}", doc.name))?;
+
+        let entries = scrape_documentation(
+            &doc.name,
+            Self::update_version(&doc),
+            ScrapeRun::new(progress_tx).offline(cache),
+        )
+        .await
+        .context("Failed to rebuild documentation from cache")?;
+
+        self.update_documentation_with_entries(doc_id, entries)
+            .await
+    }
+
+    /// Drops the cached pages of a source, returns how many were removed
+    pub fn clear_http_cache(&self, name: &str) -> Result<usize> {
+        match self.http_cache(name) {
+            Some(cache) => cache.clear(),
+            None => Ok(0),
+        }
+    }
+
+    /// Applies a fresh scrape as a diff keyed on `(doc_id, path)`: unchanged entries keep
+    /// their id and `created_at`, only changed rows touch the search index
+    pub async fn update_documentation_with_entries(
         &self,
         doc_id: i64,
         entries: Vec<ParsedDocEntry>,
-    ) -> Result<Documentation> {
+    ) -> Result<DocumentationUpdate> {
         let doc = self.get_documentation(doc_id).await?;
-        let metadata = get_doc_metadata_by_name(&doc.name)
-            .context(format!("Documentation not found: {}", doc.name))?;
+        let metadata = get_versioned_doc_metadata(&doc.name, Self::update_version(&doc))?;
+        if metadata.version != doc.version {
+            // The new version may have been installed side by side already
+            self.ensure_not_installed(&doc.name, &metadata.version)
+                .await?;
+        }
 
         let now = chrono::Utc::now().timestamp();
         let mut tx = self.pool.begin().await?;
 
-        tracing::info!("Step 1: Deleting old entries");
-        sqlx::query("DELETE FROM doc_entries WHERE doc_id = ?1")
-            .bind(doc_id)
-            .execute(&mut *tx)
-            .await?;
+        tracing::info!("Step 1: Loading existing entries");
+        let existing = Self::load_entry_hashes(&mut tx, doc_id).await?;
 
-        tracing::info!("Step 2: Inserting {} new entries", entries.len());
-        for chunk in entries.chunks(100) {
-            for entry in chunk {
-                sqlx::query(
-                    "INSERT INTO doc_entries (doc_id, path, title, content, entry_type, parent_path, created_at)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                )
-                .bind(doc_id)
-                .bind(&entry.path)
-                .bind(&entry.title)
-                .bind(&entry.content)
-                .bind(&entry.entry_type)
-                .bind(&entry.parent_path)
-                .bind(now)
+        tracing::info!("Step 2: Diffing {} scraped entries", entries.len());
+        let mut summary = DocUpdateSummary::default();
+        let mut seen = HashSet::with_capacity(entries.len());
+
+        for entry in &entries {
+            if !seen.insert(entry.path.as_str()) {
+                tracing::debug!("Skipping duplicate doc entry path: {}", entry.path);
+                continue;
+            }
+
+            let hash = Self::entry_hash(entry);
+            match existing.get(&entry.path) {
+                None => {
+                    Self::insert_entry(&mut tx, doc_id, entry, now).await?;
+                    summary.added += 1;
+                }
+                Some((_, existing_hash)) if *existing_hash == hash => {
+                    summary.unchanged += 1;
+                }
+                Some((id, _)) => {
+                    sqlx::query(
+                        "UPDATE doc_entries
+                         SET title = ?1, content = ?2, entry_type = ?3, parent_path = ?4, content_hash = ?5
+                         WHERE id = ?6",
+                    )
+                    .bind(&entry.title)
+                    .bind(&entry.content)
+                    .bind(&entry.entry_type)
+                    .bind(&entry.parent_path)
+                    .bind(&hash)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                    summary.changed += 1;
+                }
+            }
+        }
+
+        for (path, (id, _)) in &existing {
+            if seen.contains(path.as_str()) {
+                continue;
+            }
+            sqlx::query("DELETE FROM doc_entries WHERE id = ?1")
+                .bind(id)
                 .execute(&mut *tx)
                 .await?;
-            }
+            summary.removed += 1;
         }
 
-        tracing::info!("Step 3: Updating metadata");
+        tracing::info!(
+            "Step 3: Updating metadata (added: {}, changed: {}, removed: {}, unchanged: {})",
+            summary.added,
+            summary.changed,
+            summary.removed,
+            summary.unchanged
+        );
         sqlx::query(
             "UPDATE documentations
              SET version = ?1, updated_at = ?2
@@ -217,9 +569,199 @@ impl DocumentationManager {
         tx.commit().await?;
         tracing::info!("=== ✓ Documentation update complete ===");
 
+        Ok(DocumentationUpdate {
+            documentation: self.get_documentation(doc_id).await?,
+            summary,
+        })
+    }
+
+    /// GitHub sources stay on their installed branch or tag, scraped sites are re-read at
+    /// whatever version their definition now declares
+    fn update_version(doc: &Documentation) -> Option<&str> {
+        is_versioned_source(&doc.name).then_some(doc.version.as_str())
+    }
+
+    /// A trashed copy of the same version is purged to make room for the new install
+    async fn ensure_not_installed(&self, name: &str, version: &str) -> Result<()> {
+        let installed = sqlx::query(
+            "SELECT id, deleted_at IS NOT NULL AS trashed FROM documentations
+             WHERE name = ?1 AND version = ?2",
+        )
+        .bind(name)
+        .bind(version)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match installed {
+            Some(row) if row.get::<bool, _>("trashed") => {
+                self.purge_documentation(row.get("id")).await?;
+                Ok(())
+            }
+            Some(_) => anyhow::bail!("Documentation {} {} is already installed", name, version),
+            None => Ok(()),
+        }
+    }
+
+    /// Makes `doc_id` the version that search and name lookups use for its documentation
+    pub async fn set_active_version(&self, doc_id: i64) -> Result<Documentation> {
+        let doc = self.get_documentation(doc_id).await?;
+
+        sqlx::query("UPDATE documentations SET is_active = (id = ?1) WHERE name = ?2")
+            .bind(doc_id)
+            .bind(&doc.name)
+            .execute(&self.pool)
+            .await
+            .context("Failed to switch documentation version")?;
+
         self.get_documentation(doc_id).await
     }
 
+    /// Installed documentation by name, `None` picks the active version
+    pub async fn find_documentation(
+        &self,
+        name: &str,
+        version: Option<&str>,
+    ) -> Result<Documentation> {
+        let row = sqlx::query(
+            "SELECT id, name, display_name, version, source_url,
+                    installed_at, updated_at, metadata, is_active
+             FROM documentations
+             WHERE name = ?1 AND (?2 IS NULL OR version = ?2) AND deleted_at IS NULL
+             ORDER BY is_active DESC, updated_at DESC
+             LIMIT 1",
+        )
+        .bind(name)
+        .bind(version)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match (row, version) {
+            (Some(row), _) => Ok(Self::documentation_from_row(&row)),
+            (None, Some(version)) => {
+                anyhow::bail!("Documentation {} {} is not installed", name, version)
+            }
+            (None, None) => anyhow::bail!("Documentation {} is not installed", name),
+        }
+    }
+
+    /// The installed sibling of `doc_id` at `version`, or `doc_id` itself without a version;
+    /// fails for a documentation in the trash
+    pub async fn resolve_doc_version(&self, doc_id: i64, version: Option<&str>) -> Result<i64> {
+        let doc = sqlx::query(
+            "SELECT name, version FROM documentations WHERE id = ?1 AND deleted_at IS NULL",
+        )
+        .bind(doc_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Documentation {} not found", doc_id))?;
+        let Some(version) = version else {
+            return Ok(doc_id);
+        };
+
+        if doc.get::<String, _>("version") == version {
+            return Ok(doc_id);
+        }
+
+        Ok(self
+            .find_documentation(&doc.get::<String, _>("name"), Some(version))
+            .await?
+            .id)
+    }
+
+    /// path -> (entry id, content hash). Entries stored before hashes existed get theirs
+    /// computed here so they are not reported as changed.
+    async fn load_entry_hashes(
+        conn: &mut SqliteConnection,
+        doc_id: i64,
+    ) -> Result<HashMap<String, (i64, String)>> {
+        let rows = sqlx::query(
+            "SELECT id, path, title, content, entry_type, parent_path, content_hash
+             FROM doc_entries WHERE doc_id = ?1",
+        )
+        .bind(doc_id)
+        .fetch_all(&mut *conn)
+        .await
+        .context("Failed to load doc entries")?;
+
+        let mut hashes = HashMap::with_capacity(rows.len());
+        for row in rows {
+            let id: i64 = row.get("id");
+            let path: String = row.get("path");
+            let mut hash: String = row.get("content_hash");
+
+            if hash.is_empty() {
+                hash = Self::entry_hash(&ParsedDocEntry {
+                    path: path.clone(),
+                    title: row.get("title"),
+                    content: row.get("content"),
+                    entry_type: row.get("entry_type"),
+                    parent_path: row.get("parent_path"),
+                });
+                sqlx::query("UPDATE doc_entries SET content_hash = ?1 WHERE id = ?2")
+                    .bind(&hash)
+                    .bind(id)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+
+            hashes.insert(path, (id, hash));
+        }
+
+        Ok(hashes)
+    }
+
+    async fn insert_entry(
+        conn: &mut SqliteConnection,
+        doc_id: i64,
+        entry: &ParsedDocEntry,
+        now: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO doc_entries (doc_id, path, title, content, entry_type, parent_path, created_at, content_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )
+        .bind(doc_id)
+        .bind(&entry.path)
+        .bind(&entry.title)
+        .bind(&entry.content)
+        .bind(&entry.entry_type)
+        .bind(&entry.parent_path)
+        .bind(now)
+        .bind(Self::entry_hash(entry))
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// SHA-256 over every stored field except the path, which is the diff key
+    fn entry_hash(entry: &ParsedDocEntry) -> String {
+        let mut hasher = Sha256::new();
+        for field in [
+            Some(entry.title.as_str()),
+            Some(entry.content.as_str()),
+            entry.entry_type.as_deref(),
+            entry.parent_path.as_deref(),
+        ] {
+            match field {
+                Some(value) => {
+                    hasher.update([1]);
+                    hasher.update((value.len() as u64).to_le_bytes());
+                    hasher.update(value.as_bytes());
+                }
+                None => hasher.update([0]),
+            }
+        }
+
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// Moves a documentation version to the trash. Its entries stay in the database but
+    /// out of search, an active version hands over to the most recently updated sibling.
     pub async fn delete_documentation(&self, doc_id: i64) -> Result<()> {
         tracing::info!(
             "=== Starting documentation deletion for doc_id: {} ===",
@@ -238,14 +780,23 @@ impl DocumentationManager {
             }
         };
 
-        tracing::info!("Step 2: Deleting from database (will cascade to entries)");
-        let result = sqlx::query("DELETE FROM documentations WHERE id = ?1")
-            .bind(doc_id)
-            .execute(&self.pool)
-            .await
-            .context("Failed to delete documentation")?;
+        tracing::info!("Step 2: Moving to the trash");
+        let result = sqlx::query(
+            "UPDATE documentations SET deleted_at = ?1, is_active = 0
+             WHERE id = ?2 AND deleted_at IS NULL",
+        )
+        .bind(chrono::Utc::now().timestamp())
+        .bind(doc_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to delete documentation")?;
 
-        tracing::info!("✓ Deleted {} rows", result.rows_affected());
+        tracing::info!("✓ Trashed {} rows", result.rows_affected());
+
+        if let Some(doc) = doc.as_ref().filter(|doc| doc.is_active) {
+            tracing::info!("Step 3: Activating the most recently updated remaining version");
+            self.activate_latest_version(&doc.name).await?;
+        }
 
         if let Some(doc) = doc {
             tracing::info!(
@@ -259,10 +810,107 @@ impl DocumentationManager {
         Ok(())
     }
 
+    /// Takes a documentation version out of the trash; it becomes active again when no
+    /// other version of the documentation is
+    pub async fn restore_documentation(&self, doc_id: i64) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE documentations SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+        )
+        .bind(doc_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to restore documentation")?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            "UPDATE documentations SET is_active = 1
+             WHERE id = ?1 AND NOT EXISTS (
+                 SELECT 1 FROM documentations other
+                 WHERE other.name = documentations.name AND other.is_active = 1
+             )",
+        )
+        .bind(doc_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to activate restored documentation")?;
+
+        Ok(true)
+    }
+
+    /// Documentation versions in the trash, most recently deleted first
+    pub async fn list_deleted_documentations(&self) -> Result<Vec<TrashEntry>> {
+        let rows = sqlx::query(
+            "SELECT id, display_name, version, deleted_at FROM documentations
+             WHERE deleted_at IS NOT NULL
+             ORDER BY deleted_at DESC, id DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list deleted documentations")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| TrashEntry {
+                kind: TrashKind::Documentation,
+                id: row.get("id"),
+                title: format!(
+                    "{} {}",
+                    row.get::<String, _>("display_name"),
+                    row.get::<String, _>("version")
+                ),
+                item_type: None,
+                deleted_at: row.get("deleted_at"),
+            })
+            .collect())
+    }
+
+    /// Removes a trashed documentation version for good (cascades to its entries)
+    pub async fn purge_documentation(&self, doc_id: i64) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM documentations WHERE id = ?1 AND deleted_at IS NOT NULL")
+                .bind(doc_id)
+                .execute(&self.pool)
+                .await
+                .context("Failed to purge documentation")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Purges versions trashed before `deleted_before`, or every trashed one without it
+    pub async fn purge_deleted_documentations(&self, deleted_before: Option<i64>) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM documentations
+             WHERE deleted_at IS NOT NULL AND (?1 IS NULL OR deleted_at < ?1)",
+        )
+        .bind(deleted_before)
+        .execute(&self.pool)
+        .await
+        .context("Failed to purge deleted documentations")?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn activate_latest_version(&self, name: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE documentations SET is_active = 1
+             WHERE id = (SELECT id FROM documentations WHERE name = ?1 AND deleted_at IS NULL
+                         ORDER BY updated_at DESC LIMIT 1)",
+        )
+        .bind(name)
+        .execute(&self.pool)
+        .await
+        .context("Failed to activate remaining documentation version")?;
+
+        Ok(())
+    }
+
     async fn get_documentation(&self, doc_id: i64) -> Result<Documentation> {
         let row = sqlx::query(
             "SELECT id, name, display_name, version, source_url,
-                    installed_at, updated_at, metadata
+                    installed_at, updated_at, metadata, is_active
              FROM documentations WHERE id = ?1",
         )
         .bind(doc_id)
@@ -270,10 +918,14 @@ impl DocumentationManager {
         .await
         .context("Documentation not found")?;
 
+        Ok(Self::documentation_from_row(&row))
+    }
+
+    fn documentation_from_row(row: &SqliteRow) -> Documentation {
         let metadata_str: String = row.get("metadata");
         let metadata = serde_json::from_str(&metadata_str).ok();
 
-        Ok(Documentation {
+        Documentation {
             id: row.get("id"),
             name: row.get("name"),
             display_name: row.get("display_name"),
@@ -282,7 +934,8 @@ impl DocumentationManager {
             installed_at: row.get("installed_at"),
             updated_at: row.get("updated_at"),
             metadata,
-        })
+            is_active: row.get("is_active"),
+        }
     }
 
     pub async fn get_doc_entries(
@@ -332,8 +985,10 @@ impl DocumentationManager {
 
     pub async fn get_doc_entry_by_path(&self, doc_id: i64, path: &str) -> Result<DocEntry> {
         let row = sqlx::query(
-            "SELECT id, doc_id, path, title, content, entry_type, parent_path, created_at
+            "SELECT doc_entries.id, doc_id, path, title, content, entry_type, parent_path,
+                    created_at
              FROM doc_entries
+             JOIN documentations d ON d.id = doc_entries.doc_id AND d.deleted_at IS NULL
              WHERE doc_id = ?1 AND path = ?2",
         )
         .bind(doc_id)
@@ -354,6 +1009,36 @@ impl DocumentationManager {
         })
     }
 
+    /// Entries with content across active versions, ordered by documentation name and path
+    pub async fn list_active_doc_entries(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<DocEntryRef>> {
+        let rows = sqlx::query(
+            "SELECT d.name, e.path, e.title
+             FROM doc_entries e
+             JOIN documentations d ON d.id = e.doc_id
+             WHERE d.is_active = 1 AND e.content != ''
+             ORDER BY d.name, e.path
+             LIMIT ?1 OFFSET ?2",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list active doc entries")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DocEntryRef {
+                doc_name: row.get("name"),
+                path: row.get("path"),
+                title: row.get("title"),
+            })
+            .collect())
+    }
+
     pub async fn get_doc_tree_level(
         &self,
         doc_id: i64,
@@ -362,8 +1047,9 @@ impl DocumentationManager {
         let rows = if let Some(ref parent) = parent_path {
             sqlx::query(
                 "SELECT path, title, entry_type, parent_path, (content != '') as has_content,
-                        EXISTS(SELECT 1 FROM doc_entries de2 WHERE de2.parent_path = doc_entries.path) as has_children
+                        EXISTS(SELECT 1 FROM doc_entries de2 WHERE de2.doc_id = doc_entries.doc_id AND de2.parent_path = doc_entries.path) as has_children
                  FROM doc_entries
+                 JOIN documentations d ON d.id = doc_entries.doc_id AND d.deleted_at IS NULL
                  WHERE doc_id = ?1 AND parent_path = ?2
                  ORDER BY title"
             )
@@ -374,8 +1060,9 @@ impl DocumentationManager {
         } else {
             sqlx::query(
                 "SELECT path, title, entry_type, parent_path, (content != '') as has_content,
-                        EXISTS(SELECT 1 FROM doc_entries de2 WHERE de2.parent_path = doc_entries.path) as has_children
+                        EXISTS(SELECT 1 FROM doc_entries de2 WHERE de2.doc_id = doc_entries.doc_id AND de2.parent_path = doc_entries.path) as has_children
                  FROM doc_entries
+                 JOIN documentations d ON d.id = doc_entries.doc_id AND d.deleted_at IS NULL
                  WHERE doc_id = ?1 AND parent_path IS NULL
                  ORDER BY title"
             )
@@ -402,7 +1089,7 @@ impl DocumentationManager {
     pub async fn get_doc_graph(&self, doc_id: i64) -> Result<DocumentationGraph> {
         let rows = sqlx::query(
             "SELECT path, title, entry_type, parent_path, (content != '') as has_content,
-                    EXISTS(SELECT 1 FROM doc_entries de2 WHERE de2.parent_path = doc_entries.path) as has_children
+                    EXISTS(SELECT 1 FROM doc_entries de2 WHERE de2.doc_id = doc_entries.doc_id AND de2.parent_path = doc_entries.path) as has_children
              FROM doc_entries
              WHERE doc_id = ?1
              ORDER BY title",
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+pub trait Embedder: Send + Sync {
+    /// Identifier stored next to every vector so stale embeddings can be detected
+    fn model(&self) -> &str;
+    fn dimensions(&self) -> usize;
+    fn embed(&self, text: &str) -> Vec<f32>;
+}
+
+/// Offline embedder: word unigrams and character trigrams hashed into a fixed-size
+/// vector with sublinear term frequency, L2-normalized.
+pub struct HashedNgramEmbedder {
+    dimensions: usize,
+    model: String,
+}
+
+const DEFAULT_DIMENSIONS: usize = 384;
+const WORD_WEIGHT: f32 = 1.0;
+const TRIGRAM_WEIGHT: f32 = 0.5;
+/// Long config files and notes are embedded by their leading part only
+const MAX_EMBEDDED_CHARS: usize = 4096;
+
+impl HashedNgramEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        let dimensions = dimensions.max(1);
+        Self {
+            dimensions,
+            model: format!("hashed-ngram-v1-{}", dimensions),
+        }
+    }
+
+    fn tokenize(text: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            let parts = Self::split_camel_case(word);
+            if parts.len() > 1 {
+                tokens.extend(parts.iter().map(|p| p.to_lowercase()));
+            }
+            tokens.push(word.to_lowercase());
+        }
+        tokens
+    }
+
+    fn split_camel_case(word: &str) -> Vec<&str> {
+        let mut parts = Vec::new();
+        let mut start = 0;
+        let mut prev_lower = false;
+        for (idx, c) in word.char_indices() {
+            if c.is_uppercase() && prev_lower {
+                parts.push(&word[start..idx]);
+                start = idx;
+            }
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        }
+        parts.push(&word[start..]);
+        parts
+    }
+
+    fn hash_chars(kind: u8, chars: impl IntoIterator<Item = char>) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut feed = |byte: u8| {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        };
+        feed(kind);
+        let mut buf = [0u8; 4];
+        for c in chars {
+            for byte in c.encode_utf8(&mut buf).as_bytes() {
+                feed(*byte);
+            }
+        }
+        hash
+    }
+}
+
+impl Default for HashedNgramEmbedder {
+    fn default() -> Self {
+        Self::new(DEFAULT_DIMENSIONS)
+    }
+}
+
+impl Embedder for HashedNgramEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let text = match text.char_indices().nth(MAX_EMBEDDED_CHARS) {
+            Some((cut, _)) => &text[..cut],
+            None => text,
+        };
+
+        // feature hash -> (count, weight)
+        let mut features: HashMap<u64, (f32, f32)> = HashMap::new();
+
+        for token in Self::tokenize(text) {
+            features
+                .entry(Self::hash_chars(b'w', token.chars()))
+                .or_insert((0.0, WORD_WEIGHT))
+                .0 += 1.0;
+
+            let padded: Vec<char> = std::iter::once('#')
+                .chain(token.chars())
+                .chain(std::iter::once('#'))
+                .collect();
+            for window in padded.windows(3) {
+                features
+                    .entry(Self::hash_chars(b't', window.iter().copied()))
+                    .or_insert((0.0, TRIGRAM_WEIGHT))
+                    .0 += 1.0;
+            }
+        }
+
+        let mut vector = vec![0.0f32; self.dimensions];
+        for (hash, (count, weight)) in features {
+            let index = (hash % self.dimensions as u64) as usize;
+            // Signed hashing keeps collisions from only ever adding up
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[index] += sign * weight * (1.0 + count.ln());
+        }
+
+        normalize(&mut vector);
+        vector
+    }
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > f32::EPSILON {
+        for value in vector.iter_mut() {
+            *value /= norm;
+        }
+    }
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let mut dot = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+
+    let denom = norm_a.sqrt() * norm_b.sqrt();
+    if denom <= f32::EPSILON {
+        0.0
+    } else {
+        dot / denom
+    }
+}
+
+pub fn vector_to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub fn blob_to_vector(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+pub fn item_embedding_text(title: &str, description: Option<&str>, content: &str) -> String {
+    let mut text = String::with_capacity(title.len() + content.len() + 2);
+    text.push_str(title);
+    if let Some(description) = description {
+        text.push('\n');
+        text.push_str(description);
+    }
+    text.push('\n');
+    text.push_str(content);
+    text
+}
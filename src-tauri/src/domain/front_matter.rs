@@ -0,0 +1,124 @@
+//! YAML front-matter of Markdown files: the subset that exports write and editors like
+//! Obsidian produce (scalars, flow and block lists, flow maps)
+
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+const FENCE: &str = "---";
+
+/// `---` block with one `key: value` line per field, followed by `body` unchanged.
+/// Values are written in JSON flow style, which is valid YAML.
+pub fn render(fields: &Map<String, Value>, body: &str) -> String {
+    let mut output = format!("{FENCE}\n");
+    for (key, value) in fields {
+        let value = match value {
+            Value::String(text) if is_plain_scalar(text) => text.clone(),
+            value => value.to_string(),
+        };
+        output.push_str(&format!("{key}: {value}\n"));
+    }
+    output.push_str(FENCE);
+    output.push('\n');
+    output.push_str(body);
+    output
+}
+
+/// Fields and body of a Markdown file, a file without front-matter is all body
+pub fn parse(text: &str) -> Result<(Map<String, Value>, &str)> {
+    let mut fields = Map::new();
+    let Some(rest) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return Ok((fields, text));
+    };
+
+    let mut offset = 0;
+    let mut lines = Vec::new();
+    let mut body = None;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim_end() == FENCE {
+            body = Some(&rest[offset..]);
+            break;
+        }
+        lines.push(line.trim_end());
+    }
+    let Some(body) = body else {
+        anyhow::bail!("Front-matter is not closed with ---");
+    };
+
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        index += 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            anyhow::bail!("Expected `key: value` in front-matter, got `{}`", line);
+        };
+        let value = value.trim();
+
+        if value.is_empty() {
+            // A block list on the following lines, or an empty value
+            let mut items = Vec::new();
+            while let Some(item) = lines
+                .get(index)
+                .and_then(|next| next.trim_start().strip_prefix("- "))
+            {
+                items.push(parse_scalar(item.trim()));
+                index += 1;
+            }
+            let value = if items.is_empty() {
+                Value::Null
+            } else {
+                Value::Array(items)
+            };
+            fields.insert(key.trim().to_string(), value);
+        } else {
+            fields.insert(key.trim().to_string(), parse_value(value));
+        }
+    }
+
+    Ok((fields, body))
+}
+
+fn parse_value(value: &str) -> Value {
+    if let Ok(json) = serde_json::from_str::<Value>(value) {
+        return json;
+    }
+    // `[a, b]` with unquoted entries
+    if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return Value::Array(
+            inner
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(parse_scalar)
+                .collect(),
+        );
+    }
+    parse_scalar(value)
+}
+
+fn parse_scalar(value: &str) -> Value {
+    if let Some(text) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return Value::String(text.replace("''", "'"));
+    }
+    match serde_json::from_str::<Value>(value) {
+        Ok(json @ (Value::String(_) | Value::Number(_) | Value::Bool(_) | Value::Null)) => json,
+        _ => Value::String(value.to_string()),
+    }
+}
+
+/// Strings that read back as the same string without quotes
+fn is_plain_scalar(text: &str) -> bool {
+    !text.is_empty()
+        && text.trim() == text
+        && !text.contains(": ")
+        && !text.contains(" #")
+        && !text.chars().any(|c| c.is_control())
+        && !text.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        && matches!(parse_scalar(text), Value::String(_))
+}
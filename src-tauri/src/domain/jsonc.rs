@@ -0,0 +1,501 @@
+//! JSON with comments and trailing commas, as written by VS Code, Zed and other editors.
+//!
+//! [`JsoncDocument`] edits such a file in place: only the changed value is rewritten, the
+//! comments and formatting around it stay as the user left them.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+
+/// Indentation of a nested level when the file gives no example to follow
+const INDENT: &str = "  ";
+
+/// Removes `//` and `/* */` comments and trailing commas outside of strings
+pub fn strip_json_comments(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        output.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            (']' | '}', _) => {
+                let trimmed = output.trim_end().len();
+                if output[..trimmed].ends_with(',') {
+                    output.truncate(trimmed - 1);
+                }
+                output.push(c);
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+/// Step from a value to one of its members or elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSegment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct JsoncDocument {
+    text: String,
+}
+
+/// A value and its byte range in the text
+struct Node {
+    start: usize,
+    end: usize,
+    kind: NodeKind,
+}
+
+enum NodeKind {
+    Object(Vec<Child>),
+    Array(Vec<Child>),
+    Scalar,
+}
+
+/// Object member or array element; `start` is where its key, or the element, begins
+struct Child {
+    key: Option<String>,
+    start: usize,
+    value: Node,
+}
+
+impl JsoncDocument {
+    pub fn parse(text: &str) -> Result<Self> {
+        let document = Self {
+            text: text.to_string(),
+        };
+        document.root()?;
+        Ok(document)
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn value(&self) -> Result<Value> {
+        serde_json::from_str(&strip_json_comments(&self.text)).context("Invalid JSON")
+    }
+
+    /// Replaces the value at `path`, object members missing on the way are added
+    pub fn set(&mut self, path: &[PathSegment], value: &Value) -> Result<()> {
+        let root = self.root()?;
+        let mut node = &root;
+        for (depth, segment) in path.iter().enumerate() {
+            node = match (segment, &node.kind) {
+                (PathSegment::Key(key), NodeKind::Object(children)) => {
+                    match children
+                        .iter()
+                        .find(|child| child.key.as_deref() == Some(key))
+                    {
+                        Some(child) => &child.value,
+                        None => {
+                            let value = nest(&path[depth + 1..], value)?;
+                            return self.insert_child(node, Some(key), &value);
+                        }
+                    }
+                }
+                (PathSegment::Index(index), NodeKind::Array(children)) => {
+                    &children
+                        .get(*index)
+                        .with_context(|| format!("No element {index} in the list"))?
+                        .value
+                }
+                (segment, kind) => bail!("Cannot take {segment:?} from {kind}"),
+            };
+        }
+
+        let replacement = render(value, &line_indent(&self.text, node.start))?;
+        self.text.replace_range(node.start..node.end, &replacement);
+        Ok(())
+    }
+
+    /// Appends `value` to the list at `path`, a missing list is created
+    pub fn push(&mut self, path: &[PathSegment], value: &Value) -> Result<()> {
+        let root = self.root()?;
+        match find(&root, path)? {
+            Some(
+                node @ Node {
+                    kind: NodeKind::Array(_),
+                    ..
+                },
+            ) => self.insert_child(node, None, value),
+            Some(node) => bail!("Expected a list, found {}", node.kind),
+            None => self.set(path, &Value::Array(vec![value.clone()])),
+        }
+    }
+
+    /// Drops the member or element at `path`, `false` when there is none
+    pub fn remove(&mut self, path: &[PathSegment]) -> Result<bool> {
+        let Some((last, parent_path)) = path.split_last() else {
+            bail!("The root value cannot be removed");
+        };
+        let root = self.root()?;
+        let Some(parent) = find(&root, parent_path)? else {
+            return Ok(false);
+        };
+        let (children, index) = match (&parent.kind, last) {
+            (NodeKind::Object(children), PathSegment::Key(key)) => (
+                children,
+                children
+                    .iter()
+                    .position(|child| child.key.as_deref() == Some(key)),
+            ),
+            (NodeKind::Array(children), PathSegment::Index(index)) => {
+                (children, (*index < children.len()).then_some(*index))
+            }
+            _ => return Ok(false),
+        };
+        let Some(index) = index else {
+            return Ok(false);
+        };
+
+        let child = &children[index];
+        let after = skip_trivia(&self.text, child.value.end);
+        if self.text.as_bytes().get(after) == Some(&b',') {
+            let range = whole_lines(&self.text, child.start, after + 1);
+            self.text.replace_range(range, "");
+        } else {
+            let range = whole_lines(&self.text, child.start, child.value.end);
+            self.text.replace_range(range, "");
+            // The last child takes the comma before it along
+            if index > 0 {
+                let comma = skip_trivia(&self.text, children[index - 1].value.end);
+                self.text.remove(comma);
+            }
+        }
+        Ok(true)
+    }
+
+    fn root(&self) -> Result<Node> {
+        let mut parser = Parser {
+            text: &self.text,
+            pos: 0,
+        };
+        let node = parser.node()?;
+        parser.skip_trivia()?;
+        if parser.pos != self.text.len() {
+            bail!("Unexpected text at offset {}", parser.pos);
+        }
+        Ok(node)
+    }
+
+    /// Adds a member (`key` given) or element as the last child of `container`
+    fn insert_child(&mut self, container: &Node, key: Option<&str>, value: &Value) -> Result<()> {
+        let children = match &container.kind {
+            NodeKind::Object(children) | NodeKind::Array(children) => children,
+            NodeKind::Scalar => bail!("Expected an object or a list, found a value"),
+        };
+        let indent = match children.first() {
+            Some(first)
+                if self.text[line_start(&self.text, first.start)..first.start]
+                    .trim()
+                    .is_empty() =>
+            {
+                line_indent(&self.text, first.start)
+            }
+            _ => format!("{}{INDENT}", line_indent(&self.text, container.start)),
+        };
+        let mut entry = match key {
+            Some(key) => format!("{}: ", serde_json::to_string(key)?),
+            None => String::new(),
+        };
+        entry.push_str(&render(value, &indent)?);
+
+        match children.last() {
+            Some(last) => {
+                let after = skip_trivia(&self.text, last.value.end);
+                let has_comma = self.text.as_bytes().get(after) == Some(&b',');
+                let anchor = if has_comma { after + 1 } else { last.value.end };
+                // After a comment that ends the line, not before it
+                let at = blank_line_end(&self.text, anchor);
+                self.text.insert_str(at, &format!("\n{indent}{entry}"));
+                if !has_comma {
+                    self.text.insert(last.value.end, ',');
+                }
+            }
+            None => {
+                let inner = container.start + 1..container.end - 1;
+                if self.text[inner.clone()].contains('\n') {
+                    self.text
+                        .insert_str(inner.start, &format!("\n{indent}{entry}"));
+                } else {
+                    let closing = line_indent(&self.text, container.start);
+                    self.text
+                        .replace_range(inner, &format!("\n{indent}{entry}\n{closing}"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            NodeKind::Object(_) => "an object",
+            NodeKind::Array(_) => "a list",
+            NodeKind::Scalar => "a value",
+        })
+    }
+}
+
+/// The node at `path`, `None` when a member or element on the way is missing
+fn find<'a>(root: &'a Node, path: &[PathSegment]) -> Result<Option<&'a Node>> {
+    let mut node = root;
+    for segment in path {
+        let child = match (segment, &node.kind) {
+            (PathSegment::Key(key), NodeKind::Object(children)) => children
+                .iter()
+                .find(|child| child.key.as_deref() == Some(key)),
+            (PathSegment::Index(index), NodeKind::Array(children)) => children.get(*index),
+            (segment, kind) => bail!("Cannot take {segment:?} from {kind}"),
+        };
+        match child {
+            Some(child) => node = &child.value,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(node))
+}
+
+/// `value` wrapped in one object per remaining key
+fn nest(path: &[PathSegment], value: &Value) -> Result<Value> {
+    let mut value = value.clone();
+    for segment in path.iter().rev() {
+        let PathSegment::Key(key) = segment else {
+            bail!("No list to take element {:?} from", segment);
+        };
+        let mut object = Map::new();
+        object.insert(key.to_string(), value);
+        value = Value::Object(object);
+    }
+    Ok(value)
+}
+
+/// Pretty JSON whose continuation lines start at `indent`
+fn render(value: &Value, indent: &str) -> Result<String> {
+    let pretty = serde_json::to_string_pretty(value)?;
+    Ok(pretty.replace('\n', &format!("\n{indent}")))
+}
+
+fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+fn line_indent(text: &str, pos: usize) -> String {
+    text[line_start(text, pos)..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect()
+}
+
+/// End of the line at `pos` when only blanks or a `//` comment follow, `pos` otherwise
+fn blank_line_end(text: &str, pos: usize) -> usize {
+    let end = text[pos..]
+        .find('\n')
+        .map_or(text.len(), |newline| pos + newline);
+    let rest = text[pos..end].trim();
+    if rest.is_empty() || rest.starts_with("//") {
+        end
+    } else {
+        pos
+    }
+}
+
+/// `start..end` grown to full lines when nothing but blanks and a comment shares them
+fn whole_lines(text: &str, start: usize, end: usize) -> std::ops::Range<usize> {
+    let line_start = line_start(text, start);
+    let line_end = text[end..]
+        .find('\n')
+        .map_or(text.len(), |newline| end + newline);
+    let rest = text[end..line_end].trim();
+    if text[line_start..start].trim().is_empty() && (rest.is_empty() || rest.starts_with("//")) {
+        line_start..(line_end + 1).min(text.len())
+    } else {
+        start..end
+    }
+}
+
+/// First position from `pos` that is not whitespace or a comment
+fn skip_trivia(text: &str, pos: usize) -> usize {
+    let mut parser = Parser { text, pos };
+    // The text was parsed before, every comment in it is closed
+    let _ = parser.skip_trivia();
+    parser.pos
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_trivia(&mut self) -> Result<()> {
+        loop {
+            let rest = &self.text[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                let end = trimmed[2..]
+                    .find("*/")
+                    .with_context(|| format!("Unterminated comment at offset {}", self.pos))?;
+                self.pos += end + 4;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn node(&mut self) -> Result<Node> {
+        self.skip_trivia()?;
+        let start = self.pos;
+        match self.peek() {
+            Some(b'{') => self.container(b'}'),
+            Some(b'[') => self.container(b']'),
+            Some(b'"') => {
+                self.string()?;
+                Ok(Node {
+                    start,
+                    end: self.pos,
+                    kind: NodeKind::Scalar,
+                })
+            }
+            Some(_) => {
+                while self
+                    .peek()
+                    .is_some_and(|byte| !b",]}/".contains(&byte) && !byte.is_ascii_whitespace())
+                {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    bail!("Unexpected character at offset {start}");
+                }
+                Ok(Node {
+                    start,
+                    end: self.pos,
+                    kind: NodeKind::Scalar,
+                })
+            }
+            None => bail!("Unexpected end of JSON"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => self.pos += 1,
+                None => bail!("Unterminated string at offset {start}"),
+            }
+        }
+        serde_json::from_str(&self.text[start..self.pos])
+            .with_context(|| format!("Invalid string at offset {start}"))
+    }
+
+    fn container(&mut self, close: u8) -> Result<Node> {
+        let start = self.pos;
+        let keyed = close == b'}';
+        self.pos += 1;
+        let mut children = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(byte) if byte == close => {
+                    self.pos += 1;
+                    break;
+                }
+                None => bail!("Unterminated {} at offset {start}", close as char),
+                _ => {}
+            }
+
+            let child_start = self.pos;
+            let key = if keyed {
+                if self.peek() != Some(b'"') {
+                    bail!("Expected a key at offset {}", self.pos);
+                }
+                let key = self.string()?;
+                self.skip_trivia()?;
+                if self.peek() != Some(b':') {
+                    bail!("Expected ':' at offset {}", self.pos);
+                }
+                self.pos += 1;
+                Some(key)
+            } else {
+                None
+            };
+            let value = self.node()?;
+            children.push(Child {
+                key,
+                start: child_start,
+                value,
+            });
+
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(byte) if byte == close => {}
+                _ => bail!("Expected ',' or '{}' at offset {}", close as char, self.pos),
+            }
+        }
+        Ok(Node {
+            start,
+            end: self.pos,
+            kind: if keyed {
+                NodeKind::Object(children)
+            } else {
+                NodeKind::Array(children)
+            },
+        })
+    }
+}
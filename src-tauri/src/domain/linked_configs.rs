@@ -0,0 +1,293 @@
+//! Config items backed by a file on disk.
+//!
+//! `linked_config_files` keeps, per linked item, the file path and the hash of the file
+//! text the item last matched. A file that no longer hashes to it was edited on disk; an
+//! item whose content no longer hashes to it was edited in the vault and waits to be
+//! written back. When both were, the file is not pulled in and the drift report shows a
+//! conflict.
+//!
+//! The link lives in its own table rather than in item metadata, which MCP clients and
+//! imports can write: only `link_config_file` may point the watcher at a file.
+
+use super::markdown_sync::content_hash;
+use super::storage::Storage;
+use super::text_diff::unified_diff;
+use crate::models::*;
+use anyhow::{Context, Result};
+use sqlx::Row;
+use std::path::PathBuf;
+
+/// A linked config item as stored
+pub struct LinkedConfig {
+    item_id: i64,
+    title: String,
+    content: String,
+    /// As stored, `~` not expanded
+    path: String,
+    source_hash: String,
+}
+
+impl LinkedConfig {
+    fn file_path(&self) -> PathBuf {
+        expand_home(&self.path)
+    }
+}
+
+/// Linked files edited on disk since their item last matched them, read without the
+/// database so the files can be read outside the storage lock
+pub struct ChangedConfigFiles {
+    files: Vec<(LinkedConfig, String)>,
+}
+
+impl ChangedConfigFiles {
+    /// A missing file is reported by the drift report, its item stays as it is
+    pub fn read(configs: Vec<LinkedConfig>) -> Self {
+        let files = configs
+            .into_iter()
+            .filter_map(|config| {
+                let text = std::fs::read_to_string(config.file_path()).ok()?;
+                (content_hash(&text) != config.source_hash).then_some((config, text))
+            })
+            .collect();
+        Self { files }
+    }
+}
+
+impl Storage {
+    /// Links a config item to a file and takes over the file's content
+    pub async fn link_config_file(&self, item_id: i64, path: &str) -> Result<()> {
+        let path = path.trim();
+        if path.is_empty() {
+            anyhow::bail!("Config file path is empty");
+        }
+        let item = self
+            .get_item(item_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Item {} not found", item_id))?
+            .item;
+        if item.item_type != ItemType::Config {
+            anyhow::bail!("Only config items can be linked to a file");
+        }
+
+        let file_path = expand_home(path);
+        let text = std::fs::read_to_string(&file_path)
+            .with_context(|| format!("Failed to read {}", file_path.display()))?;
+        let hash = content_hash(&text);
+
+        self.update_item(UpdateItemDto {
+            id: item_id,
+            item_type: None,
+            title: None,
+            description: None,
+            content: Some(text),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+        sqlx::query(
+            "INSERT INTO linked_config_files (item_id, path, source_hash) VALUES (?1, ?2, ?3)
+             ON CONFLICT(item_id) DO UPDATE SET
+                 path = excluded.path,
+                 source_hash = excluded.source_hash",
+        )
+        .bind(item_id)
+        .bind(path)
+        .bind(hash)
+        .execute(&self.pool)
+        .await
+        .context("Failed to link the config file")?;
+        Ok(())
+    }
+
+    pub async fn unlink_config_file(&self, item_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM linked_config_files WHERE item_id = ?1")
+            .bind(item_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to unlink the config file")?;
+        Ok(())
+    }
+
+    pub async fn list_config_links(&self) -> Result<Vec<ConfigLink>> {
+        Ok(self
+            .linked_configs()
+            .await?
+            .into_iter()
+            .map(|config| ConfigLink {
+                item_id: config.item_id,
+                path: config.path,
+            })
+            .collect())
+    }
+
+    /// Takes the content of every linked file edited on disk since the item last matched
+    /// it, see `apply_changed_config_files`
+    pub async fn refresh_linked_configs(&self) -> Result<Vec<i64>> {
+        let files = ChangedConfigFiles::read(self.linked_configs().await?);
+        self.apply_changed_config_files(files).await
+    }
+
+    /// Takes the content of files read by `ChangedConfigFiles::read`, the previous content
+    /// stays in the item history. Items edited in the vault as well are left for the drift
+    /// report. Returns the updated item ids.
+    pub async fn apply_changed_config_files(&self, files: ChangedConfigFiles) -> Result<Vec<i64>> {
+        let mut updated = Vec::new();
+        for (read, text) in files.files {
+            // The files were read without the lock, the link or the item may have changed
+            let Some(config) = self.find_linked_config(read.item_id).await? else {
+                continue;
+            };
+            if config.path != read.path
+                || config.source_hash != read.source_hash
+                || content_hash(&config.content) != config.source_hash
+            {
+                continue;
+            }
+
+            let hash = content_hash(&text);
+            self.update_item(UpdateItemDto {
+                id: config.item_id,
+                item_type: None,
+                title: None,
+                description: None,
+                content: Some(text),
+                metadata: None,
+                tag_ids: None,
+            })
+            .await?;
+            self.save_source_hash(config.item_id, &hash).await?;
+            tracing::info!(
+                "🔗 Config item {} updated from {}",
+                config.item_id,
+                config.path
+            );
+            updated.push(config.item_id);
+        }
+        Ok(updated)
+    }
+
+    /// Linked config items whose file differs from their content
+    pub async fn config_drift_report(&self) -> Result<Vec<ConfigDrift>> {
+        let mut report = Vec::new();
+        for config in self.linked_configs().await? {
+            let drift = match std::fs::read_to_string(config.file_path()) {
+                Ok(text) if text == config.content => continue,
+                Ok(text) => ConfigDrift {
+                    item_id: config.item_id,
+                    file_changed: content_hash(&text) != config.source_hash,
+                    item_changed: content_hash(&config.content) != config.source_hash,
+                    title: config.title,
+                    path: config.path,
+                    missing: false,
+                },
+                Err(_) => ConfigDrift {
+                    item_id: config.item_id,
+                    title: config.title,
+                    path: config.path,
+                    missing: true,
+                    file_changed: false,
+                    item_changed: false,
+                },
+            };
+            report.push(drift);
+        }
+        Ok(report)
+    }
+
+    pub async fn preview_config_write_back(&self, item_id: i64) -> Result<ConfigWriteBackPreview> {
+        let config = self.linked_config(item_id).await?;
+        let on_disk = std::fs::read_to_string(config.file_path()).ok();
+        Ok(ConfigWriteBackPreview {
+            item_id,
+            diff: unified_diff(
+                on_disk.as_deref().unwrap_or_default(),
+                &config.content,
+                &config.path,
+                &config.title,
+            ),
+            exists: on_disk.is_some(),
+            path: config.path,
+        })
+    }
+
+    /// Writes the item content to its file
+    pub async fn write_config_back(&self, item_id: i64) -> Result<()> {
+        let config = self.linked_config(item_id).await?;
+        let file_path = config.file_path();
+        if let Some(parent) = file_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&file_path, &config.content)
+            .with_context(|| format!("Failed to write {}", file_path.display()))?;
+
+        // Only the bookkeeping changes, the item keeps its updated_at and history
+        self.save_source_hash(item_id, &content_hash(&config.content))
+            .await?;
+        tracing::info!("🔗 Config item {} written to {}", item_id, config.path);
+        Ok(())
+    }
+
+    /// Every linked config item not in the trash, in one query
+    pub async fn linked_configs(&self) -> Result<Vec<LinkedConfig>> {
+        let rows = sqlx::query(
+            "SELECT i.id, i.title, i.content, l.path, l.source_hash
+             FROM linked_config_files l
+             JOIN items i ON i.id = l.item_id
+             WHERE i.type = 'config' AND i.deleted_at IS NULL
+             ORDER BY i.id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load linked configs")?;
+        Ok(rows.iter().map(linked_config_from_row).collect())
+    }
+
+    async fn find_linked_config(&self, item_id: i64) -> Result<Option<LinkedConfig>> {
+        let row = sqlx::query(
+            "SELECT i.id, i.title, i.content, l.path, l.source_hash
+             FROM linked_config_files l
+             JOIN items i ON i.id = l.item_id
+             WHERE l.item_id = ?1 AND i.type = 'config' AND i.deleted_at IS NULL",
+        )
+        .bind(item_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to load the linked config")?;
+        Ok(row.as_ref().map(linked_config_from_row))
+    }
+
+    async fn linked_config(&self, item_id: i64) -> Result<LinkedConfig> {
+        self.find_linked_config(item_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Item {} is not linked to a file", item_id))
+    }
+
+    async fn save_source_hash(&self, item_id: i64, hash: &str) -> Result<()> {
+        sqlx::query("UPDATE linked_config_files SET source_hash = ?1 WHERE item_id = ?2")
+            .bind(hash)
+            .bind(item_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to record the config file hash")?;
+        Ok(())
+    }
+}
+
+fn linked_config_from_row(row: &sqlx::sqlite::SqliteRow) -> LinkedConfig {
+    LinkedConfig {
+        item_id: row.get("id"),
+        title: row.get("title"),
+        content: row.get("content"),
+        path: row.get("path"),
+        source_hash: row.get("source_hash"),
+    }
+}
+
+/// `~/.config/nvim/init.lua` -> `/home/me/.config/nvim/init.lua`
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
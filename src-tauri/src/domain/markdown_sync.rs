@@ -0,0 +1,633 @@
+//! Two-way sync of notes with a folder of Markdown files, e.g. an Obsidian vault.
+//!
+//! `markdown_sync_files` remembers, per note, its file and the hashes of the note (as
+//! rendered to Markdown) and of the file text as of the last sync. Whichever side no
+//! longer matches its hash has changed; when both have, the note is reported as a
+//! conflict with the note's `updated_at` and the file's modification time, and left
+//! alone until it is resolved.
+//!
+//! Files of deleted notes are moved to `.devvault-trash` inside the folder rather than
+//! removed, and a folder whose synced files are all gone at once (unmounted, emptied by
+//! another tool) deletes no notes.
+
+use super::front_matter;
+use super::storage::Storage;
+use super::vault_transfer::{format_timestamp, parse_timestamp, unique_stem};
+use crate::models::*;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// `#tag` in text, not a heading; Obsidian requires a non-digit somewhere in the name
+static INLINE_TAG: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|\s)#([\p{L}\p{N}_/-]*[\p{L}_/-][\p{L}\p{N}_/-]*)").expect("valid regex")
+});
+
+struct SyncRecord {
+    item_id: i64,
+    path: String,
+    note_hash: String,
+    file_hash: String,
+}
+
+/// A `.md` file of the synced folder
+struct SyncFile {
+    text: String,
+    hash: String,
+    modified_at: i64,
+}
+
+/// Folder inside the synced one that receives the files of deleted notes; hidden, so
+/// the sync never reads it back
+const TRASH_DIR: &str = ".devvault-trash";
+
+/// The `.md` files of a synced folder, read without the database so the scan can run
+/// outside the storage lock
+pub struct MarkdownFolder {
+    dir: PathBuf,
+    files: HashMap<String, SyncFile>,
+}
+
+impl MarkdownFolder {
+    /// Fails when `dir` does not exist: an unmounted drive must not look like an empty
+    /// folder
+    pub fn read(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            anyhow::bail!("Markdown sync folder {} does not exist", dir.display());
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            files: read_folder(dir)?,
+        })
+    }
+}
+
+/// A note as read from its file
+struct FileNote {
+    title: String,
+    description: Option<String>,
+    content: String,
+    tags: Vec<String>,
+    created_at: Option<i64>,
+}
+
+impl Storage {
+    /// One sync pass over `dir`, which must exist
+    pub async fn sync_markdown_folder(&self, dir: &Path) -> Result<MarkdownSyncReport> {
+        self.apply_markdown_folder(MarkdownFolder::read(dir)?).await
+    }
+
+    /// One sync pass over a folder read beforehand
+    pub async fn apply_markdown_folder(
+        &self,
+        folder: MarkdownFolder,
+    ) -> Result<MarkdownSyncReport> {
+        let MarkdownFolder { dir, mut files } = folder;
+        let dir = dir.as_path();
+        let mut report = MarkdownSyncReport::default();
+        let mut notes: HashMap<i64, ItemWithTags> = self
+            .list_items(Some(i64::MAX), None, Some(ItemType::Note), None)
+            .await?
+            .into_iter()
+            .map(|note| (note.item.id, note))
+            .collect();
+        let mut records = self.sync_records(dir).await?;
+
+        // A record whose file is gone while an untracked file has its exact text was renamed
+        let tracked: HashSet<String> = records.iter().map(|r| r.path.clone()).collect();
+        for record in records.iter_mut() {
+            if files.contains_key(&record.path) {
+                continue;
+            }
+            let renamed = files
+                .iter()
+                .find(|(path, file)| !tracked.contains(*path) && file.hash == record.file_hash)
+                .map(|(path, _)| path.clone());
+            if let Some(path) = renamed {
+                self.save_sync_record(
+                    dir,
+                    record.item_id,
+                    &path,
+                    &record.note_hash,
+                    &record.file_hash,
+                )
+                .await?;
+                record.path = path;
+            }
+        }
+
+        let folder_emptied =
+            !records.is_empty() && records.iter().all(|r| !files.contains_key(&r.path));
+        if folder_emptied {
+            tracing::warn!(
+                "⚠ None of the {} synced files are in {}, no notes are deleted",
+                records.len(),
+                dir.display()
+            );
+        }
+
+        for record in records {
+            let note = notes.remove(&record.item_id);
+            let file = files.remove(&record.path);
+            let path = dir.join(&record.path);
+
+            match (note, file) {
+                (None, None) => self.delete_sync_record(dir, record.item_id).await?,
+                (None, Some(file)) => {
+                    self.delete_sync_record(dir, record.item_id).await?;
+                    if file.hash == record.file_hash {
+                        move_to_trash(dir, &record.path)?;
+                        report.deleted_files += 1;
+                    } else {
+                        // Edited after the note was deleted: comes back as a new note
+                        files.insert(record.path, file);
+                    }
+                }
+                // Kept with its record, deleted once the folder has files again
+                (Some(_), None) if folder_emptied => {}
+                (Some(note), None) => {
+                    if content_hash(&render_note(&note)) == record.note_hash {
+                        self.delete_item(note.item.id).await?;
+                        self.delete_sync_record(dir, note.item.id).await?;
+                        report.deleted_items += 1;
+                    } else {
+                        self.write_note_file(dir, &record.path, &note).await?;
+                        report.updated_files += 1;
+                    }
+                }
+                (Some(note), Some(file)) => {
+                    let rendered = render_note(&note);
+                    let note_hash = content_hash(&rendered);
+                    let note_changed = note_hash != record.note_hash;
+                    let file_changed = file.hash != record.file_hash;
+                    match (note_changed, file_changed) {
+                        (false, false) => {}
+                        (true, false) => {
+                            self.write_note_file(dir, &record.path, &note).await?;
+                            report.updated_files += 1;
+                        }
+                        (false, true) => {
+                            match self
+                                .update_note_from_file(dir, note.item.id, &record.path, &file)
+                                .await
+                            {
+                                Ok(()) => report.updated_items += 1,
+                                // Picked up again once the file is fixed
+                                Err(e) => tracing::warn!("⚠ Skipped {}: {:#}", path.display(), e),
+                            }
+                        }
+                        (true, true) if rendered == file.text => {
+                            self.save_sync_record(
+                                dir,
+                                note.item.id,
+                                &record.path,
+                                &note_hash,
+                                &file.hash,
+                            )
+                            .await?;
+                        }
+                        (true, true) => report.conflicts.push(conflict(note, record.path, &file)),
+                    }
+                }
+            }
+        }
+
+        // Untracked files and notes with the same title are the same note seen for the first
+        // time, e.g. a folder that was exported before: equal versions are linked, differing
+        // ones become a conflict
+        let mut untracked_files: Vec<(String, SyncFile)> = files.into_iter().collect();
+        untracked_files.sort_by(|a, b| a.0.cmp(&b.0));
+        for (relative, file) in untracked_files {
+            let parsed = match parse_note(&relative, &file.text) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    tracing::warn!("⚠ Skipped {}: {:#}", dir.join(&relative).display(), e);
+                    continue;
+                }
+            };
+            let same_title = notes
+                .values()
+                .find(|note| note.item.title == parsed.title)
+                .map(|note| note.item.id);
+
+            match same_title.and_then(|id| notes.remove(&id)) {
+                Some(note) if render_note(&note) == file.text => {
+                    self.save_sync_record(dir, note.item.id, &relative, &file.hash, &file.hash)
+                        .await?;
+                }
+                Some(note) => {
+                    // A record that matches neither version
+                    self.save_sync_record(dir, note.item.id, &relative, "", "")
+                        .await?;
+                    report.conflicts.push(conflict(note, relative, &file));
+                }
+                None => {
+                    self.import_note_file(dir, &relative, &file, parsed).await?;
+                    report.imported += 1;
+                }
+            }
+        }
+
+        let mut used_stems = existing_stems(dir)?;
+        let mut untracked_notes: Vec<ItemWithTags> = notes.into_values().collect();
+        untracked_notes.sort_by_key(|note| note.item.id);
+        for note in untracked_notes {
+            let relative = format!("{}.md", unique_stem(&note.item.title, &mut used_stems));
+            self.write_note_file(dir, &relative, &note).await?;
+            report.exported += 1;
+        }
+
+        if report.changed() || !report.conflicts.is_empty() {
+            tracing::info!("🔁 Markdown sync of {}: {:?}", dir.display(), report);
+        }
+        Ok(report)
+    }
+
+    /// Notes changed on both sides, as of the last sync
+    pub async fn resolve_markdown_conflict(
+        &self,
+        dir: &Path,
+        item_id: i64,
+        keep: SyncSide,
+    ) -> Result<()> {
+        let record = self
+            .sync_records(dir)
+            .await?
+            .into_iter()
+            .find(|record| record.item_id == item_id)
+            .ok_or_else(|| anyhow::anyhow!("Note {} is not synced with a file", item_id))?;
+        let note = self
+            .get_item(item_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Note {} not found", item_id))?;
+
+        match keep {
+            SyncSide::Vault => self.write_note_file(dir, &record.path, &note).await,
+            SyncSide::File => {
+                let path = dir.join(&record.path);
+                let text = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let file = SyncFile {
+                    hash: content_hash(&text),
+                    text,
+                    modified_at: modified_at(&path),
+                };
+                self.update_note_from_file(dir, item_id, &record.path, &file)
+                    .await
+            }
+        }
+    }
+
+    async fn sync_records(&self, dir: &Path) -> Result<Vec<SyncRecord>> {
+        let rows = sqlx::query(
+            "SELECT item_id, path, note_hash, file_hash FROM markdown_sync_files
+             WHERE directory = ?1",
+        )
+        .bind(dir.to_string_lossy())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load Markdown sync state")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SyncRecord {
+                item_id: row.get("item_id"),
+                path: row.get("path"),
+                note_hash: row.get("note_hash"),
+                file_hash: row.get("file_hash"),
+            })
+            .collect())
+    }
+
+    async fn save_sync_record(
+        &self,
+        dir: &Path,
+        item_id: i64,
+        path: &str,
+        note_hash: &str,
+        file_hash: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO markdown_sync_files (directory, item_id, path, note_hash, file_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(directory, item_id) DO UPDATE SET
+                 path = excluded.path,
+                 note_hash = excluded.note_hash,
+                 file_hash = excluded.file_hash",
+        )
+        .bind(dir.to_string_lossy())
+        .bind(item_id)
+        .bind(path)
+        .bind(note_hash)
+        .bind(file_hash)
+        .execute(&self.pool)
+        .await
+        .context("Failed to save Markdown sync state")?;
+        Ok(())
+    }
+
+    async fn delete_sync_record(&self, dir: &Path, item_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM markdown_sync_files WHERE directory = ?1 AND item_id = ?2")
+            .bind(dir.to_string_lossy())
+            .bind(item_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to delete Markdown sync state")?;
+        Ok(())
+    }
+
+    async fn write_note_file(&self, dir: &Path, relative: &str, note: &ItemWithTags) -> Result<()> {
+        let path = dir.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let text = render_note(note);
+        std::fs::write(&path, &text)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        let hash = content_hash(&text);
+        self.save_sync_record(dir, note.item.id, relative, &hash, &hash)
+            .await
+    }
+
+    async fn update_note_from_file(
+        &self,
+        dir: &Path,
+        item_id: i64,
+        relative: &str,
+        file: &SyncFile,
+    ) -> Result<()> {
+        let parsed =
+            parse_note(relative, &file.text).with_context(|| format!("Invalid {}", relative))?;
+        self.update_item(UpdateItemDto {
+            id: item_id,
+            item_type: None,
+            title: Some(parsed.title),
+            description: Some(parsed.description.unwrap_or_default()),
+            content: Some(parsed.content),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+        self.link_imported_tags(item_id, &tag_pairs(parsed.tags))
+            .await?;
+        self.spawn_cleanup_unused_tags();
+
+        self.record_note_from_file(dir, item_id, relative, file)
+            .await
+    }
+
+    async fn import_note_file(
+        &self,
+        dir: &Path,
+        relative: &str,
+        file: &SyncFile,
+        parsed: FileNote,
+    ) -> Result<()> {
+        let id = self
+            .create_item(CreateItemDto {
+                item_type: ItemType::Note,
+                title: parsed.title,
+                description: parsed.description,
+                content: parsed.content,
+                metadata: None,
+                tag_ids: None,
+            })
+            .await?;
+        if let Some(created_at) = parsed.created_at {
+            sqlx::query("UPDATE items SET created_at = ?1 WHERE id = ?2")
+                .bind(created_at)
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .context("Failed to keep the note's creation time")?;
+        }
+        self.link_imported_tags(id, &tag_pairs(parsed.tags)).await?;
+
+        self.record_note_from_file(dir, id, relative, file).await
+    }
+
+    /// The file may differ from how the note renders (e.g. inline tags, front-matter
+    /// written by another editor), both are recorded as they are now
+    async fn record_note_from_file(
+        &self,
+        dir: &Path,
+        item_id: i64,
+        relative: &str,
+        file: &SyncFile,
+    ) -> Result<()> {
+        let note = self
+            .get_item(item_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Note {} not found", item_id))?;
+        let note_hash = content_hash(&render_note(&note));
+        self.save_sync_record(dir, item_id, relative, &note_hash, &file.hash)
+            .await
+    }
+}
+
+fn conflict(note: ItemWithTags, path: String, file: &SyncFile) -> MarkdownSyncConflict {
+    MarkdownSyncConflict {
+        item_id: note.item.id,
+        title: note.item.title,
+        path,
+        item_updated_at: note.item.updated_at,
+        file_modified_at: file.modified_at,
+    }
+}
+
+/// The file text of a note: title, description, tags and creation date in front-matter
+fn render_note(note: &ItemWithTags) -> String {
+    let mut fields = Map::new();
+    fields.insert("title".into(), note.item.title.clone().into());
+    if let Some(description) = note.item.description.as_deref().filter(|d| !d.is_empty()) {
+        fields.insert("description".into(), description.into());
+    }
+    let mut tags: Vec<&str> = note.tags.iter().map(|tag| tag.name.as_str()).collect();
+    tags.sort();
+    if !tags.is_empty() {
+        fields.insert("tags".into(), tags.into());
+    }
+    fields.insert(
+        "created".into(),
+        format_timestamp(note.item.created_at).into(),
+    );
+    front_matter::render(&fields, &note.item.content)
+}
+
+/// Front-matter tags (a list, or names separated by commas or spaces) and `#tags` of
+/// the body, the title falls back to the file name
+fn parse_note(relative: &str, text: &str) -> Result<FileNote> {
+    let (fields, body) = front_matter::parse(text)?;
+    let string = |key: &str| {
+        fields
+            .get(key)
+            .and_then(Value::as_str)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+
+    let title = string("title").unwrap_or_else(|| {
+        Path::new(relative)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    let mut tags: Vec<String> = match fields.get("tags") {
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        Some(Value::String(names)) => names
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    };
+    tags.extend(inline_tags(body));
+
+    let mut seen = HashSet::new();
+    let tags = tags
+        .into_iter()
+        .map(|tag| tag.trim().trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty() && seen.insert(tag.clone()))
+        .collect();
+
+    Ok(FileNote {
+        title,
+        description: string("description"),
+        content: body.to_string(),
+        tags,
+        created_at: fields
+            .get("created")
+            .and_then(|value| parse_timestamp(value).ok()),
+    })
+}
+
+/// `#tags` outside fenced code blocks
+fn inline_tags(body: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut in_code = false;
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        tags.extend(
+            INLINE_TAG
+                .captures_iter(line)
+                .map(|captures| captures[1].to_string()),
+        );
+    }
+    tags
+}
+
+fn tag_pairs(tags: Vec<String>) -> Vec<(String, Option<i64>)> {
+    tags.into_iter().map(|tag| (tag, None)).collect()
+}
+
+/// SHA-256 of the text as hex
+pub(crate) fn content_hash(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// `.md` files by path relative to `dir` with `/` separators; hidden folders such as
+/// `.obsidian` and `.trash` are skipped
+fn read_folder(dir: &Path) -> Result<HashMap<String, SyncFile>> {
+    let mut files = HashMap::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)
+            .with_context(|| format!("Failed to read {}", current.display()))?
+        {
+            let path = entry?.path();
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "md") {
+                let text = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                files.insert(
+                    relative_path(dir, &path),
+                    SyncFile {
+                        hash: content_hash(&text),
+                        text,
+                        modified_at: modified_at(&path),
+                    },
+                );
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Moves a file of the synced folder to its trash folder under the same relative path,
+/// numbered when an earlier file took the name
+fn move_to_trash(dir: &Path, relative: &str) -> Result<()> {
+    let path = dir.join(relative);
+    let mut target = dir.join(TRASH_DIR).join(relative);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let stem = target
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut n = 1;
+    while target.exists() {
+        n += 1;
+        target.set_file_name(format!("{stem} {n}.md"));
+    }
+    std::fs::rename(&path, &target)
+        .with_context(|| format!("Failed to move {} to the trash", path.display()))
+}
+
+/// Unix seconds, 0 when the file system does not tell
+fn modified_at(path: &Path) -> i64 {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+fn relative_path(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir)
+        .unwrap_or(path)
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Lowercased stems of the files at the top of `dir`, new files must not replace them
+fn existing_stems(dir: &Path) -> Result<HashSet<String>> {
+    let mut stems = HashSet::new();
+    for entry in
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?
+    {
+        let path: PathBuf = entry?.path();
+        if let Some(stem) = path.file_stem() {
+            stems.insert(stem.to_string_lossy().to_lowercase());
+        }
+    }
+    Ok(stems)
+}
@@ -1,11 +1,22 @@
 pub mod config_manager;
 pub mod docs;
 pub mod documentation_manager;
+pub mod embeddings;
+pub mod front_matter;
+pub mod jsonc;
+pub mod linked_configs;
+pub mod markdown_sync;
 pub mod parsers;
 pub mod search_engine;
+pub mod search_query;
+pub mod snippet_formats;
 pub mod storage;
+pub mod text_diff;
+pub mod vault_transfer;
 
 pub use config_manager::ConfigManager;
 pub use documentation_manager::DocumentationManager;
+pub use embeddings::{Embedder, HashedNgramEmbedder};
 pub use search_engine::SearchEngine;
+pub use search_query::{ParsedQuery, QueryParseError};
 pub use storage::Storage;
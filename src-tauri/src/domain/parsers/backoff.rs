@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Per-host pauses set after 429/503 answers, every request to the host waits them out
+#[derive(Debug, Default)]
+pub struct HostBackoff {
+    not_before: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostBackoff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sleeps until `host` may be contacted again
+    pub async fn wait(&self, host: &str) {
+        let until = self.not_before.lock().unwrap().get(host).copied();
+        if let Some(until) = until {
+            tokio::time::sleep_until(until).await;
+        }
+    }
+
+    /// Keeps `host` quiet for `delay`, never shortening a longer pause already set
+    pub fn pause(&self, host: &str, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut not_before = self.not_before.lock().unwrap();
+        let entry = not_before.entry(host.to_string()).or_insert(until);
+        if *entry < until {
+            *entry = until;
+        }
+    }
+}
+
+/// `base_ms * 2^attempt`, for servers that do not say how long to wait
+pub fn exponential_delay(attempt: u32, base_ms: u64) -> Duration {
+    Duration::from_millis(base_ms.saturating_mul(1u64 << attempt.min(16)))
+}
+
+/// Parses `Retry-After` as either delay-seconds or an HTTP-date
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
@@ -0,0 +1,284 @@
+use super::github_parser::GitHubDocConfig;
+use super::url_scraper::DocDefinition;
+use crate::models::{CustomDocSource, CustomDocSourceError, CustomDocSourcesReport};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Directory inside the app data dir with one source per `.toml` / `.json` file
+pub const CUSTOM_SOURCES_DIR: &str = "doc-sources";
+
+/// User-defined documentation source, `kind` picks the parser
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum CustomSourceDefinition {
+    Scraper(Box<DocDefinition>),
+    Github(GitHubDocConfig),
+}
+
+impl CustomSourceDefinition {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Scraper(definition) => &definition.name,
+            Self::Github(config) => &config.name,
+        }
+    }
+
+    fn display_name(&self) -> &str {
+        match self {
+            Self::Scraper(definition) => &definition.display_name,
+            Self::Github(config) => &config.display_name,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Scraper(_) => "scraper",
+            Self::Github(_) => "github",
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let name = self.name();
+        let valid_name = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid_name {
+            anyhow::bail!(
+                "name '{}' must contain only lowercase letters, digits, '-' and '_'",
+                name
+            );
+        }
+
+        if self.display_name().trim().is_empty() {
+            anyhow::bail!("display_name must not be empty");
+        }
+
+        match self {
+            Self::Scraper(definition) => definition.validate(),
+            Self::Github(config) => config.validate(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    Toml,
+    Json,
+}
+
+impl SourceFormat {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "toml" => Ok(Self::Toml),
+            "json" => Ok(Self::Json),
+            _ => anyhow::bail!(
+                "Unsupported source format '{}', expected toml or json",
+                value
+            ),
+        }
+    }
+
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Toml => "toml",
+            Self::Json => "json",
+        }
+    }
+}
+
+struct LoadedSource {
+    definition: CustomSourceDefinition,
+    path: PathBuf,
+}
+
+static CUSTOM_SOURCES: Lazy<RwLock<Vec<LoadedSource>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+pub fn sources_dir(app_dir: &Path) -> PathBuf {
+    app_dir.join(CUSTOM_SOURCES_DIR)
+}
+
+pub fn parse_source(content: &str, format: SourceFormat) -> Result<CustomSourceDefinition> {
+    let definition: CustomSourceDefinition = match format {
+        SourceFormat::Toml => {
+            toml::from_str(content).map_err(|e| anyhow::anyhow!(e.to_string()))?
+        }
+        SourceFormat::Json => serde_json::from_str(content)?,
+    };
+    definition.validate()?;
+    Ok(definition)
+}
+
+/// Re-reads every source file and replaces the registered custom sources. Broken files
+/// are reported and skipped, they never hide the valid ones.
+pub fn load_custom_sources(app_dir: &Path) -> CustomDocSourcesReport {
+    let dir = sources_dir(app_dir);
+    let mut report = CustomDocSourcesReport::default();
+    let mut loaded: Vec<LoadedSource> = Vec::new();
+
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| SourceFormat::from_path(path).is_some())
+            .collect(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => {
+            report.errors.push(CustomDocSourceError {
+                file: dir.display().to_string(),
+                message: format!("Failed to read directory: {}", err),
+            });
+            Vec::new()
+        }
+    };
+    paths.sort();
+
+    for path in paths {
+        let file = file_name(&path);
+        let result = read_source(&path).and_then(|definition| {
+            if is_builtin(definition.name()) {
+                anyhow::bail!(
+                    "name '{}' is already used by a built-in source",
+                    definition.name()
+                );
+            }
+            if let Some(other) = loaded
+                .iter()
+                .find(|source| source.definition.name() == definition.name())
+            {
+                anyhow::bail!(
+                    "name '{}' is already defined in {}",
+                    definition.name(),
+                    file_name(&other.path)
+                );
+            }
+            Ok(definition)
+        });
+
+        match result {
+            Ok(definition) => {
+                report.sources.push(CustomDocSource {
+                    name: definition.name().to_string(),
+                    display_name: definition.display_name().to_string(),
+                    kind: definition.kind().to_string(),
+                    file: file.clone(),
+                });
+                loaded.push(LoadedSource { definition, path });
+            }
+            Err(err) => {
+                tracing::warn!("Skipping custom doc source {}: {:#}", file, err);
+                report.errors.push(CustomDocSourceError {
+                    file,
+                    message: format!("{:#}", err),
+                });
+            }
+        }
+    }
+
+    tracing::info!(
+        "Loaded {} custom doc source(s), {} invalid file(s)",
+        report.sources.len(),
+        report.errors.len()
+    );
+    *CUSTOM_SOURCES.write().unwrap_or_else(|e| e.into_inner()) = loaded;
+
+    report
+}
+
+/// Validates and stores a source as `<name>.<format>`, replacing an earlier file of the
+/// same source
+pub fn add_custom_source(
+    app_dir: &Path,
+    content: &str,
+    format: SourceFormat,
+) -> Result<CustomDocSourcesReport> {
+    let definition = parse_source(content, format)?;
+    if is_builtin(definition.name()) {
+        anyhow::bail!(
+            "name '{}' is already used by a built-in source",
+            definition.name()
+        );
+    }
+
+    let dir = sources_dir(app_dir);
+    std::fs::create_dir_all(&dir).context("Failed to create doc sources directory")?;
+
+    let previous = source_path(definition.name());
+    let path = dir.join(format!("{}.{}", definition.name(), format.extension()));
+    std::fs::write(&path, content).context("Failed to write doc source file")?;
+    if let Some(previous) = previous.filter(|previous| *previous != path) {
+        std::fs::remove_file(&previous).context("Failed to remove previous doc source file")?;
+    }
+
+    Ok(load_custom_sources(app_dir))
+}
+
+pub fn remove_custom_source(app_dir: &Path, name: &str) -> Result<CustomDocSourcesReport> {
+    let path = source_path(name)
+        .ok_or_else(|| anyhow::anyhow!("Custom doc source not found: {}", name))?;
+    std::fs::remove_file(&path).context("Failed to remove doc source file")?;
+
+    Ok(load_custom_sources(app_dir))
+}
+
+pub fn custom_doc_definitions() -> Vec<DocDefinition> {
+    read_registry(|source| match &source.definition {
+        CustomSourceDefinition::Scraper(definition) => Some(definition.as_ref().clone()),
+        CustomSourceDefinition::Github(_) => None,
+    })
+}
+
+pub fn custom_github_configs() -> Vec<GitHubDocConfig> {
+    read_registry(|source| match &source.definition {
+        CustomSourceDefinition::Github(config) => Some(config.clone()),
+        CustomSourceDefinition::Scraper(_) => None,
+    })
+}
+
+fn read_registry<T>(map: impl Fn(&LoadedSource) -> Option<T>) -> Vec<T> {
+    CUSTOM_SOURCES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter_map(map)
+        .collect()
+}
+
+fn source_path(name: &str) -> Option<PathBuf> {
+    CUSTOM_SOURCES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|source| source.definition.name() == name)
+        .map(|source| source.path.clone())
+}
+
+fn read_source(path: &Path) -> Result<CustomSourceDefinition> {
+    let format = SourceFormat::from_path(path)
+        .ok_or_else(|| anyhow::anyhow!("Unsupported file extension"))?;
+    let content = std::fs::read_to_string(path).context("Failed to read file")?;
+    parse_source(&content, format)
+}
+
+fn is_builtin(name: &str) -> bool {
+    super::doc_registry::is_builtin_source(name)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
@@ -1,5 +1,6 @@
+use super::custom_sources::{custom_doc_definitions, custom_github_configs};
 use super::github_parser::{GitHubDocConfig, GitHubParser};
-use super::url_scraper::{DocDefinition, ProgressSender, UrlScraper};
+use super::url_scraper::{DocDefinition, ScrapeRun, UrlScraper};
 use crate::domain::docs::github_docs::get_all_github_configs;
 use crate::domain::docs::nodejs::nodejs_definition;
 use crate::domain::docs::react::react_definition;
@@ -7,16 +8,39 @@ use crate::domain::docs::rust::rust_definition;
 use crate::models::{AvailableDocumentation, ParsedDocEntry};
 use anyhow::Result;
 
-pub fn get_doc_definitions() -> Vec<DocDefinition> {
+fn builtin_doc_definitions() -> Vec<DocDefinition> {
     vec![rust_definition(), react_definition(), nodejs_definition()]
 }
 
+/// Built-in definitions followed by the user's custom scraper sources
+pub fn get_doc_definitions() -> Vec<DocDefinition> {
+    let mut definitions = builtin_doc_definitions();
+    definitions.extend(custom_doc_definitions());
+    definitions
+}
+
+/// Built-in GitHub configs followed by the user's custom GitHub sources
+pub fn get_github_configs() -> Vec<GitHubDocConfig> {
+    let mut configs = get_all_github_configs();
+    configs.extend(custom_github_configs());
+    configs
+}
+
+pub fn is_builtin_source(name: &str) -> bool {
+    builtin_doc_definitions()
+        .iter()
+        .any(|definition| definition.name == name)
+        || get_all_github_configs()
+            .iter()
+            .any(|config| config.name == name)
+}
+
 pub fn get_definition_by_name(name: &str) -> Option<DocDefinition> {
     get_doc_definitions().into_iter().find(|d| d.name == name)
 }
 
 pub fn get_github_config_by_name(name: &str) -> Option<GitHubDocConfig> {
-    get_all_github_configs()
+    get_github_configs()
         .into_iter()
         .find(|config| config.name == name)
 }
@@ -30,8 +54,18 @@ pub struct DocMetadata {
 }
 
 pub fn get_doc_metadata_by_name(name: &str) -> Option<DocMetadata> {
+    get_versioned_doc_metadata(name, None).ok()
+}
+
+/// Metadata for one version of a source. GitHub sources can be installed at any of their
+/// `available_versions`, scraped sites only serve the version they are defined with.
+pub fn get_versioned_doc_metadata(name: &str, version: Option<&str>) -> Result<DocMetadata> {
     if let Some(config) = get_github_config_by_name(name) {
-        return Some(DocMetadata {
+        let config = match version {
+            Some(version) => config.for_version(version)?,
+            None => config,
+        };
+        return Ok(DocMetadata {
             name: config.name,
             display_name: config.display_name,
             version: config.version,
@@ -40,7 +74,15 @@ pub fn get_doc_metadata_by_name(name: &str) -> Option<DocMetadata> {
     }
 
     if let Some(definition) = get_definition_by_name(name) {
-        return Some(DocMetadata {
+        if let Some(version) = version.filter(|version| *version != definition.version) {
+            anyhow::bail!(
+                "Version '{}' is not available for {} (available: {})",
+                version,
+                name,
+                definition.version
+            );
+        }
+        return Ok(DocMetadata {
             name: definition.name,
             display_name: definition.display_name,
             version: definition.version,
@@ -48,7 +90,13 @@ pub fn get_doc_metadata_by_name(name: &str) -> Option<DocMetadata> {
         });
     }
 
-    None
+    anyhow::bail!("Documentation not found: {}", name)
+}
+
+/// Whether installed copies of the source stay pinned to their version on update.
+/// Scraped sites always serve their current docs, so updates relabel them instead.
+pub fn is_versioned_source(name: &str) -> bool {
+    get_github_config_by_name(name).is_some()
 }
 
 pub fn get_available_documentations() -> Vec<AvailableDocumentation> {
@@ -58,13 +106,14 @@ pub fn get_available_documentations() -> Vec<AvailableDocumentation> {
         docs.push(AvailableDocumentation {
             name: d.name,
             display_name: d.display_name,
+            available_versions: vec![d.version.clone()],
             version: d.version,
             description: d.description,
             source_url: d.base_url,
         });
     }
 
-    for config in get_all_github_configs() {
+    for config in get_github_configs() {
         let repo_name = config
             .base_url
             .split('/')
@@ -77,6 +126,7 @@ pub fn get_available_documentations() -> Vec<AvailableDocumentation> {
             name: config.name.clone(),
             display_name: config.display_name.clone(),
             version: config.version.clone(),
+            available_versions: config.versions(),
             description: format!("Оффициальная документация {}", repo_name),
             source_url: config.base_url.clone(),
         });
@@ -85,18 +135,32 @@ pub fn get_available_documentations() -> Vec<AvailableDocumentation> {
     docs
 }
 
-pub async fn scrape_documentation_with_progress(
+pub async fn scrape_documentation(
     name: &str,
-    progress_tx: ProgressSender,
+    version: Option<&str>,
+    run: ScrapeRun,
 ) -> Result<Vec<ParsedDocEntry>> {
     if let Some(config) = get_github_config_by_name(name) {
+        if run.offline {
+            anyhow::bail!(
+                "{} is cloned from GitHub and has no page cache to rebuild from",
+                name
+            );
+        }
+        let config = match version {
+            Some(version) => config.for_version(version)?,
+            None => config,
+        };
         let parser = GitHubParser::new(config)?;
-        return parser.scrape_with_progress(progress_tx).await;
+        return parser.scrape(run).await;
     }
 
     let definition = get_definition_by_name(name)
         .ok_or_else(|| anyhow::anyhow!("Documentation not found: {}", name))?;
+    if let Some(version) = version.filter(|version| *version != definition.version) {
+        anyhow::bail!("Version '{}' is not available for {}", version, name);
+    }
 
     let scraper = UrlScraper::new(definition)?;
-    scraper.scrape_with_progress(progress_tx).await
+    scraper.scrape(run).await
 }
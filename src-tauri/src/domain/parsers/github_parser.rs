@@ -7,7 +7,7 @@ use tokio::fs;
 use tokio::process::Command;
 use url::Url;
 
-use super::url_scraper::{ProgressSender, ScrapeProgress, ScrapeStatus};
+use super::url_scraper::{CheckpointEvent, CrawlState, ScrapeProgress, ScrapeRun, ScrapeStatus};
 
 /// Конфигурация для парсинга документации из GitHub
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,14 +21,61 @@ pub struct GitHubDocConfig {
     /// Базовый URL репозитория GitHub (например: https://github.com/python/cpython/tree/main/Doc)
     pub base_url: String,
     /// Доступные версии (ветки), которые можно выбрать
+    #[serde(default)]
     pub available_versions: Vec<String>,
     /// Файлы .md, которые нужно игнорировать
+    #[serde(default)]
     pub ignore_files: Vec<String>,
     /// Директории, которые нужно игнорировать
+    #[serde(default)]
     pub ignore_dirs: Vec<String>,
 }
 
 impl GitHubDocConfig {
+    /// Проверяет, что base_url указывает на директорию в репозитории GitHub
+    pub fn validate(&self) -> Result<()> {
+        self.parse_repo_info().map(|_| ())
+    }
+
+    /// Версия по умолчанию и остальные доступные версии без повторов
+    pub fn versions(&self) -> Vec<String> {
+        let mut versions = vec![self.version.clone()];
+        for version in &self.available_versions {
+            if !versions.contains(version) {
+                versions.push(version.clone());
+            }
+        }
+        versions
+    }
+
+    /// Конфигурация для другой версии: ветка в base_url заменяется на `version`
+    pub fn for_version(&self, version: &str) -> Result<Self> {
+        if !self.versions().iter().any(|v| v == version) {
+            anyhow::bail!(
+                "Version '{}' is not available for {} (available: {})",
+                version,
+                self.name,
+                self.versions().join(", ")
+            );
+        }
+
+        let repo_info = self.parse_repo_info()?;
+        let mut base_url = format!(
+            "https://github.com/{}/{}/tree/{}",
+            repo_info.owner, repo_info.repo, version
+        );
+        if !repo_info.path.is_empty() {
+            base_url.push('/');
+            base_url.push_str(&repo_info.path);
+        }
+
+        Ok(Self {
+            version: version.to_string(),
+            base_url,
+            ..self.clone()
+        })
+    }
+
     /// Извлекает информацию о репозитории из base_url
     /// Формат: https://github.com/{owner}/{repo}/tree/{branch}/{path}
     fn parse_repo_info(&self) -> Result<RepoInfo> {
@@ -125,6 +172,7 @@ impl GitHubParser {
             .arg(&repo_info.branch)
             .arg(&repo_url)
             .arg(temp_dir)
+            .kill_on_drop(true)
             .output()
             .await
             .context("Failed to execute git clone")?;
@@ -241,22 +289,19 @@ impl GitHubParser {
         }
     }
 
-    /// Парсинг с прогрессом
-    pub async fn scrape_with_progress(
-        &self,
-        progress_tx: ProgressSender,
-    ) -> Result<Vec<ParsedDocEntry>> {
+    /// Парсинг с прогрессом. Отмена проверяется между файлами и прерывает клонирование,
+    /// при возобновлении уже прочитанные файлы из `run.resume` пропускаются
+    pub async fn scrape(&self, run: ScrapeRun) -> Result<Vec<ParsedDocEntry>> {
         let repo_info = self.config.parse_repo_info()?;
 
-        let _ = progress_tx
-            .send(ScrapeProgress {
-                current_page: 0,
-                max_pages: 0,
-                current_path: "Cloning repository...".to_string(),
-                entries_count: 0,
-                status: ScrapeStatus::Starting,
-            })
-            .await;
+        run.progress(ScrapeProgress {
+            current_page: 0,
+            max_pages: 0,
+            current_path: "Cloning repository...".to_string(),
+            entries_count: 0,
+            status: ScrapeStatus::Starting,
+        })
+        .await;
 
         tracing::info!("║ 📚 Starting GitHub Parser: {}", self.config.display_name);
         tracing::info!("║ 🌐 Repository: {}/{}", repo_info.owner, repo_info.repo);
@@ -274,50 +319,83 @@ impl GitHubParser {
             fs::remove_dir_all(&temp_dir).await.ok();
         }
 
-        // Клонируем репозиторий
-        self.clone_repository(&repo_info, &temp_dir).await?;
+        // Клонируем репозиторий, отмена убивает процесс git
+        let cloned = tokio::select! {
+            cloned = self.clone_repository(&repo_info, &temp_dir) => cloned,
+            _ = run.cancel.cancelled() => Err(run.cancelled(0, 0, 0).await),
+        };
 
-        let _ = progress_tx
-            .send(ScrapeProgress {
-                current_page: 0,
-                max_pages: 0,
-                current_path: "Collecting files...".to_string(),
-                entries_count: 0,
-                status: ScrapeStatus::Processing,
-            })
-            .await;
+        let result = match cloned {
+            Ok(()) => self.parse_files(&run, &repo_info, &temp_dir).await,
+            Err(e) => Err(e),
+        };
+
+        // Удаляем временную директорию
+        tracing::info!("Cleaning up temporary directory: {:?}", temp_dir);
+        if temp_dir.exists() {
+            if let Err(e) = fs::remove_dir_all(&temp_dir).await {
+                tracing::warn!("Failed to remove temp directory: {:?}", e);
+            }
+        }
+
+        result
+    }
+
+    async fn parse_files(
+        &self,
+        run: &ScrapeRun,
+        repo_info: &RepoInfo,
+        temp_dir: &Path,
+    ) -> Result<Vec<ParsedDocEntry>> {
+        run.progress(ScrapeProgress {
+            current_page: 0,
+            max_pages: 0,
+            current_path: "Collecting files...".to_string(),
+            entries_count: 0,
+            status: ScrapeStatus::Processing,
+        })
+        .await;
 
         // Определяем путь к документации внутри клонированного репозитория
         let docs_path = if repo_info.path.is_empty() {
-            temp_dir.clone()
+            temp_dir.to_path_buf()
         } else {
             temp_dir.join(&repo_info.path)
         };
 
         // Собираем все .md файлы
         let md_files = self
-            .collect_markdown_files(&temp_dir, &docs_path, &repo_info.path)
+            .collect_markdown_files(temp_dir, &docs_path, &repo_info.path)
             .await?;
 
         let max_files = md_files.len();
 
-        let _ = progress_tx
-            .send(ScrapeProgress {
-                current_page: 0,
-                max_pages: max_files,
-                current_path: "".to_string(),
-                entries_count: 0,
-                status: ScrapeStatus::Scraping,
-            })
-            .await;
+        run.progress(ScrapeProgress {
+            current_page: 0,
+            max_pages: max_files,
+            current_path: "".to_string(),
+            entries_count: 0,
+            status: ScrapeStatus::Scraping,
+        })
+        .await;
 
-        let mut entries = Vec::new();
-        let mut existing_paths = HashSet::new();
+        // Прочитанные в прошлый раз файлы не читаем повторно
+        let CrawlState {
+            visited, entries, ..
+        } = run.resume.clone();
+        let visited: HashSet<String> = visited.into_iter().collect();
+        let mut existing_paths: HashSet<String> =
+            entries.iter().map(|entry| entry.path.clone()).collect();
+        let mut entries = entries;
 
         for (idx, file_path) in md_files.iter().enumerate() {
+            if run.cancel.is_cancelled() {
+                return Err(run.cancelled(idx, max_files, entries.len()).await);
+            }
+
             // Получаем относительный путь от корня репозитория
             let relative_path = file_path
-                .strip_prefix(&temp_dir)
+                .strip_prefix(temp_dir)
                 .context("Failed to get relative path")?
                 .to_string_lossy()
                 .to_string();
@@ -334,20 +412,20 @@ impl GitHubParser {
                     relative_path
                 };
 
-            if relative_path.is_empty() {
+            if relative_path.is_empty() || visited.contains(&relative_path) {
                 continue;
             }
 
-            let _ = progress_tx
-                .send(ScrapeProgress {
-                    current_page: idx + 1,
-                    max_pages: max_files,
-                    current_path: relative_path.clone(),
-                    entries_count: entries.len(),
-                    status: ScrapeStatus::Scraping,
-                })
-                .await;
+            run.progress(ScrapeProgress {
+                current_page: idx + 1,
+                max_pages: max_files,
+                current_path: relative_path.clone(),
+                entries_count: entries.len(),
+                status: ScrapeStatus::Scraping,
+            })
+            .await;
 
+            let first_new_entry = entries.len();
             match self.read_file_content(file_path).await {
                 Ok(content) => {
                     let path_without_ext = relative_path.trim_end_matches(".md");
@@ -401,23 +479,23 @@ impl GitHubParser {
                     tracing::warn!("Failed to read {}: {:?}", file_path.display(), e);
                 }
             }
-        }
-
-        // Удаляем временную директорию
-        tracing::info!("Cleaning up temporary directory: {:?}", temp_dir);
-        if let Err(e) = fs::remove_dir_all(&temp_dir).await {
-            tracing::warn!("Failed to remove temp directory: {:?}", e);
-        }
 
-        let _ = progress_tx
-            .send(ScrapeProgress {
-                current_page: max_files,
-                max_pages: max_files,
-                current_path: "".to_string(),
-                entries_count: entries.len(),
-                status: ScrapeStatus::Completed,
+            run.checkpoint(CheckpointEvent::Visited {
+                path: relative_path,
+                entries: entries[first_new_entry..].to_vec(),
+                queued: Vec::new(),
             })
             .await;
+        }
+
+        run.progress(ScrapeProgress {
+            current_page: max_files,
+            max_pages: max_files,
+            current_path: "".to_string(),
+            entries_count: entries.len(),
+            status: ScrapeStatus::Completed,
+        })
+        .await;
 
         tracing::info!("║ ✅ Parsing completed!");
         tracing::info!("║ 📝 Entries created: {}", entries.len());
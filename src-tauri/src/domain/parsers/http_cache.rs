@@ -0,0 +1,97 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Directory inside the app data dir with one subdirectory of cached pages per source
+pub const HTTP_CACHE_DIR: &str = "http-cache";
+
+/// A fetched page with the validators the server sent for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedPage {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub fetched_at: i64,
+    pub body: String,
+}
+
+/// On-disk cache of the pages of one documentation source, one JSON file per URL
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+}
+
+impl HttpCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Cache of `source` under a root such as `<app_dir>/http-cache`
+    pub fn for_source(root: &Path, source: &str) -> Self {
+        Self::new(root.join(source))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn page_path(&self, url: &str) -> PathBuf {
+        let key: String = Sha256::digest(url.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// A missing or unreadable file is a cache miss
+    pub fn get(&self, url: &str) -> Option<CachedPage> {
+        let content = std::fs::read_to_string(self.page_path(url)).ok()?;
+        match serde_json::from_str::<CachedPage>(&content) {
+            Ok(page) if page.url == url => Some(page),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("Ignoring corrupt cache entry for {}: {}", url, e);
+                None
+            }
+        }
+    }
+
+    pub fn put(&self, page: &CachedPage) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+
+        let path = self.page_path(&page.url);
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(page)?)
+            .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, &path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        std::fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every cached page, returns how many there were
+    pub fn clear(&self) -> Result<usize> {
+        let count = self.len();
+        if self.dir.exists() {
+            std::fs::remove_dir_all(&self.dir)
+                .with_context(|| format!("Failed to remove {}", self.dir.display()))?;
+        }
+        Ok(count)
+    }
+}
@@ -1,8 +1,21 @@
+pub mod backoff;
+pub mod custom_sources;
 pub mod doc_registry;
 pub mod github_parser;
+pub mod http_cache;
+pub mod robots;
+pub mod sitemap;
 pub mod url_scraper;
 
+pub use custom_sources::{
+    add_custom_source, load_custom_sources, remove_custom_source, SourceFormat,
+};
 pub use doc_registry::{
-    get_available_documentations, get_doc_metadata_by_name, scrape_documentation_with_progress,
+    get_available_documentations, get_doc_metadata_by_name, get_versioned_doc_metadata,
+    is_versioned_source, scrape_documentation,
+};
+pub use http_cache::{CachedPage, HttpCache, HTTP_CACHE_DIR};
+pub use url_scraper::{
+    CheckpointEvent, CheckpointSender, CrawlState, ProgressSender, ScrapeCancelled, ScrapeProgress,
+    ScrapeRun, ScrapeStatus,
 };
-pub use url_scraper::{ProgressSender, ScrapeProgress};
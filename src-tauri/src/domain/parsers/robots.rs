@@ -0,0 +1,134 @@
+use regex::Regex;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+struct PathRule {
+    allow: bool,
+    /// Length of the pattern as written, the longest matching rule wins
+    specificity: usize,
+    pattern: Regex,
+}
+
+/// The robots.txt rules that apply to one user agent
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    rules: Vec<PathRule>,
+    pub crawl_delay: Option<Duration>,
+    /// `Sitemap:` lines, they apply to every agent
+    pub sitemaps: Vec<String>,
+}
+
+impl RobotsRules {
+    /// Parses robots.txt for `user_agent` (e.g. `DevVault/1.0 (...)` matches a
+    /// `User-agent: devvault` group). Groups naming the agent win over `*`.
+    pub fn parse(content: &str, user_agent: &str) -> Self {
+        let token = agent_token(user_agent);
+
+        let mut specific = RobotsRules::default();
+        let mut wildcard = RobotsRules::default();
+        let mut sitemaps = Vec::new();
+        let mut found_specific = false;
+
+        // Agents of the group being read, and whether its rules have started
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        agents.clear();
+                        in_rules = false;
+                    }
+                    agents.push(agent_token(value));
+                }
+                "sitemap" if !value.is_empty() => {
+                    sitemaps.push(value.to_string());
+                }
+                "allow" | "disallow" | "crawl-delay" => {
+                    in_rules = true;
+                    let targets_us = agents.contains(&token);
+                    let target = if targets_us {
+                        found_specific = true;
+                        &mut specific
+                    } else if agents.iter().any(|agent| agent == "*") {
+                        &mut wildcard
+                    } else {
+                        continue;
+                    };
+
+                    if key == "crawl-delay" {
+                        if let Ok(seconds) = value.parse::<f64>() {
+                            if seconds.is_finite() && seconds >= 0.0 {
+                                target.crawl_delay = Some(Duration::from_secs_f64(seconds));
+                            }
+                        }
+                    } else if let Some(rule) = PathRule::new(value, key == "allow") {
+                        target.rules.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut rules = if found_specific { specific } else { wildcard };
+        rules.sitemaps = sitemaps;
+        rules
+    }
+
+    /// Whether `path` (URL path plus query) may be fetched. Allow wins ties.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| rule.pattern.is_match(path))
+            .max_by_key(|rule| (rule.specificity, rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+impl PathRule {
+    /// An empty `Disallow:` allows everything and is dropped
+    fn new(pattern: &str, allow: bool) -> Option<Self> {
+        if pattern.is_empty() {
+            return None;
+        }
+
+        let (body, anchored) = match pattern.strip_suffix('$') {
+            Some(body) => (body, true),
+            None => (pattern, false),
+        };
+        let mut regex = String::from("^");
+        regex.push_str(
+            &body
+                .split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(".*"),
+        );
+        if anchored {
+            regex.push('$');
+        }
+
+        Some(Self {
+            allow,
+            specificity: pattern.len(),
+            pattern: Regex::new(&regex).ok()?,
+        })
+    }
+}
+
+/// Product token of a user agent, lowercased: `DevVault/1.0 (...)` -> `devvault`
+fn agent_token(user_agent: &str) -> String {
+    user_agent
+        .split(|c: char| c == '/' || c.is_whitespace())
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
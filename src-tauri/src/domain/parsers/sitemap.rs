@@ -0,0 +1,44 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static LOC: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<loc>\s*(.*?)\s*</loc>").expect("valid regex"));
+
+/// Contents of a sitemap.xml: page URLs for a `<urlset>`, nested sitemaps for a
+/// `<sitemapindex>`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sitemap {
+    pub urls: Vec<String>,
+    pub sitemaps: Vec<String>,
+}
+
+impl Sitemap {
+    pub fn parse(xml: &str) -> Self {
+        let locs: Vec<String> = LOC
+            .captures_iter(xml)
+            .map(|caps| unescape(&caps[1]))
+            .filter(|loc| !loc.is_empty())
+            .collect();
+
+        if xml.contains("<sitemapindex") {
+            Self {
+                urls: Vec::new(),
+                sitemaps: locs,
+            }
+        } else {
+            Self {
+                urls: locs,
+                sitemaps: Vec::new(),
+            }
+        }
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
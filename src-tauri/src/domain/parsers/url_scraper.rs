@@ -1,15 +1,24 @@
+use super::backoff::{exponential_delay, parse_retry_after, HostBackoff};
+use super::http_cache::{CachedPage, HttpCache};
+use super::robots::RobotsRules;
+use super::sitemap::Sitemap;
 use crate::models::ParsedDocEntry;
 use anyhow::{Context, Result};
 use ego_tree::NodeRef;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use reqwest::Client;
+use reqwest::header::{
+    HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+};
+use reqwest::{Client, Response, StatusCode};
 use scraper::{ElementRef, Html, Node, Selector};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashSet, VecDeque};
+use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{mpsc, Semaphore};
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,15 +39,141 @@ pub enum ScrapeStatus {
     Processing,
     Completed,
     Failed,
+    Cancelled,
 }
 
 pub type ProgressSender = mpsc::Sender<ScrapeProgress>;
 
+/// Where an interrupted crawl stopped: paths still to fetch (with their depth), paths
+/// already fetched and the entries parsed from them
+#[derive(Debug, Clone, Default)]
+pub struct CrawlState {
+    pub frontier: Vec<(String, usize)>,
+    pub visited: Vec<String>,
+    pub entries: Vec<ParsedDocEntry>,
+}
+
+impl CrawlState {
+    pub fn is_empty(&self) -> bool {
+        self.frontier.is_empty() && self.visited.is_empty() && self.entries.is_empty()
+    }
+}
+
+/// One crawl step, persisted as it happens so a crash loses at most the page in flight
 #[derive(Debug, Clone)]
+pub enum CheckpointEvent {
+    /// Paths queued before the first fetch
+    Seeded { queued: Vec<(String, usize)> },
+    /// `path` was fetched, produced `entries` and queued `queued`
+    Visited {
+        path: String,
+        entries: Vec<ParsedDocEntry>,
+        queued: Vec<(String, usize)>,
+    },
+}
+
+pub type CheckpointSender = mpsc::Sender<CheckpointEvent>;
+
+/// Returned (wrapped in `anyhow::Error`) when a run stops because its token was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrapeCancelled;
+
+impl fmt::Display for ScrapeCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Documentation scrape was cancelled")
+    }
+}
+
+impl std::error::Error for ScrapeCancelled {}
+
+/// Controls shared by both parsers for a single scrape
+pub struct ScrapeRun {
+    pub progress_tx: ProgressSender,
+    pub cancel: CancellationToken,
+    /// State of an interrupted run to continue from
+    pub resume: CrawlState,
+    pub checkpoint_tx: Option<CheckpointSender>,
+    /// Pages are stored here and revalidated with conditional requests
+    pub cache: Option<HttpCache>,
+    /// Read pages from `cache` only, never touching the network
+    pub offline: bool,
+}
+
+impl ScrapeRun {
+    pub fn new(progress_tx: ProgressSender) -> Self {
+        Self {
+            progress_tx,
+            cancel: CancellationToken::new(),
+            resume: CrawlState::default(),
+            checkpoint_tx: None,
+            cache: None,
+            offline: false,
+        }
+    }
+
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn with_checkpoint(mut self, resume: CrawlState, checkpoint_tx: CheckpointSender) -> Self {
+        self.resume = resume;
+        self.checkpoint_tx = Some(checkpoint_tx);
+        self
+    }
+
+    pub fn with_cache(mut self, cache: HttpCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Rebuilds from the pages in `cache`, the filters and markdown conversion run again
+    /// on them, which is how selector changes are tried out without re-downloading
+    pub fn offline(mut self, cache: HttpCache) -> Self {
+        self.cache = Some(cache);
+        self.offline = true;
+        self
+    }
+
+    pub(crate) async fn progress(&self, progress: ScrapeProgress) {
+        let _ = self.progress_tx.send(progress).await;
+    }
+
+    pub(crate) async fn checkpoint(&self, event: CheckpointEvent) {
+        if let Some(checkpoint_tx) = &self.checkpoint_tx {
+            if checkpoint_tx.send(event).await.is_err() {
+                tracing::warn!("Checkpoint writer is gone, crawl progress is no longer saved");
+            }
+        }
+    }
+
+    /// Reports the cancellation and returns the error callers match on
+    pub(crate) async fn cancelled(
+        &self,
+        current_page: usize,
+        max_pages: usize,
+        entries_count: usize,
+    ) -> anyhow::Error {
+        self.progress(ScrapeProgress {
+            current_page,
+            max_pages,
+            current_path: "".to_string(),
+            entries_count,
+            status: ScrapeStatus::Cancelled,
+        })
+        .await;
+        anyhow::Error::new(ScrapeCancelled)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ScraperOptions {
     pub initial_paths: Vec<String>,
+    #[serde(with = "regex_list")]
     pub skip_patterns: Vec<Regex>,
     pub skip_paths: HashSet<String>,
+    #[serde(with = "optional_regex_list")]
     pub only_patterns: Option<Vec<Regex>>,
     pub max_depth: Option<usize>,
     pub max_pages: Option<usize>,
@@ -47,6 +182,17 @@ pub struct ScraperOptions {
     pub delay_ms: u64,
     pub timeout_secs: u64,
     pub user_agent: String,
+    /// Skip paths robots.txt disallows for `user_agent` and honour its `Crawl-delay`
+    pub respect_robots: bool,
+    /// Seed the crawl with the pages listed in the site's sitemaps (from robots.txt, or
+    /// `/sitemap.xml`). Combine with `follow_links = false` to crawl only those pages.
+    pub use_sitemap: bool,
+    /// Retries of a request answered with 429 or 503
+    pub max_retries: u32,
+    /// First backoff delay when the server sends no `Retry-After`, doubled on each retry
+    pub retry_delay_ms: u64,
+    /// Upper bound for any backoff, including the server's `Retry-After`
+    pub max_retry_delay_ms: u64,
 }
 
 static DATA_TYPE_SELECTOR: Lazy<Selector> =
@@ -66,6 +212,11 @@ impl Default for ScraperOptions {
             delay_ms: 200,
             timeout_secs: 30,
             user_agent: "DevVault/1.0 (Documentation Scraper)".to_string(),
+            respect_robots: true,
+            use_sitemap: false,
+            max_retries: 3,
+            retry_delay_ms: 1000,
+            max_retry_delay_ms: 60_000,
         }
     }
 }
@@ -92,7 +243,8 @@ impl ScraperOptions {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ContentSelectors {
     pub title: String,
     pub content: String,
@@ -124,18 +276,98 @@ impl Default for ContentSelectors {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocDefinition {
     pub name: String,
     pub display_name: String,
     pub version: String,
     pub base_url: String,
+    #[serde(default)]
     pub description: String,
+    #[serde(default)]
     pub options: ScraperOptions,
+    #[serde(default)]
     pub selectors: ContentSelectors,
+    #[serde(default)]
     pub attribution: Option<String>,
 }
 
+impl DocDefinition {
+    /// Checks what serde cannot: the base URL and every CSS selector
+    pub fn validate(&self) -> Result<()> {
+        let base_url = Url::parse(&self.base_url).context("Invalid base_url")?;
+        if !matches!(base_url.scheme(), "http" | "https") {
+            anyhow::bail!("base_url must be an http(s) URL");
+        }
+
+        let selectors = [
+            ("selectors.title", &self.selectors.title),
+            ("selectors.content", &self.selectors.content),
+            ("selectors.links", &self.selectors.links),
+        ];
+        for (field, selector) in selectors.into_iter().chain(
+            self.selectors
+                .remove_selectors
+                .iter()
+                .map(|selector| ("selectors.remove_selectors", selector)),
+        ) {
+            Selector::parse(selector)
+                .map_err(|e| anyhow::anyhow!("Invalid {} '{}': {}", field, selector, e))?;
+        }
+
+        if self.options.concurrent_requests == 0 {
+            anyhow::bail!("options.concurrent_requests must be > 0");
+        }
+
+        Ok(())
+    }
+}
+
+/// Regex lists are stored as plain pattern strings in definition files
+mod regex_list {
+    use regex::Regex;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(patterns: &[Regex], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(patterns.iter().map(Regex::as_str))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Regex>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|pattern| Regex::new(pattern).map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
+
+mod optional_regex_list {
+    use regex::Regex;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        patterns: &Option<Vec<Regex>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match patterns {
+            Some(patterns) => super::regex_list::serialize(patterns, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<Regex>>, D::Error> {
+        Option::<Vec<String>>::deserialize(deserializer)?
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .map(|pattern| Regex::new(pattern).map_err(serde::de::Error::custom))
+                    .collect()
+            })
+            .transpose()
+    }
+}
+
 pub trait HtmlFilter: Send + Sync {
     fn name(&self) -> &str;
     fn process(&self, html: &str, context: &FilterContext) -> String;
@@ -260,6 +492,13 @@ impl Default for FilterPipeline {
     }
 }
 
+/// A page body and whether it came out of the cache (revalidated or offline)
+#[derive(Debug)]
+struct FetchedPage {
+    body: String,
+    from_cache: bool,
+}
+
 #[derive(Debug)]
 struct ScrapedPage {
     path: String,
@@ -269,10 +508,14 @@ struct ScrapedPage {
     links: Vec<String>,
 }
 
+/// Nested sitemap indexes are followed up to this many files
+const MAX_SITEMAPS: usize = 50;
+
 pub struct UrlScraper {
     definition: DocDefinition,
     client: Client,
     pipeline: FilterPipeline,
+    backoff: HostBackoff,
 }
 
 impl UrlScraper {
@@ -289,6 +532,7 @@ impl UrlScraper {
             definition,
             client,
             pipeline,
+            backoff: HostBackoff::new(),
         })
     }
 
@@ -334,22 +578,191 @@ impl UrlScraper {
         }
     }
 
-    async fn fetch_page(&self, url: &str) -> Result<String> {
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .context(format!("Failed to fetch: {}", url))?;
+    /// Fetches `url`, revalidating a cached copy with `If-None-Match` / `If-Modified-Since`.
+    /// A 304 answer reuses the cached body, so its entries hash the same as before and the
+    /// update diff leaves them untouched.
+    async fn fetch_page(&self, url: &str, run: &ScrapeRun) -> Result<FetchedPage> {
+        let cached = run.cache.as_ref().and_then(|cache| cache.get(url));
+
+        if run.offline {
+            let page = cached.ok_or_else(|| anyhow::anyhow!("Not in cache: {}", url))?;
+            return Ok(FetchedPage {
+                body: page.body,
+                from_cache: true,
+            });
+        }
+
+        let mut headers = Vec::new();
+        if let Some(page) = &cached {
+            if let Some(etag) = &page.etag {
+                headers.push((IF_NONE_MATCH, etag.clone()));
+            }
+            if let Some(last_modified) = &page.last_modified {
+                headers.push((IF_MODIFIED_SINCE, last_modified.clone()));
+            }
+        }
+
+        let response = self.send_with_retries(url, &headers).await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            let page =
+                cached.ok_or_else(|| anyhow::anyhow!("HTTP 304 without a cached copy: {}", url))?;
+            return Ok(FetchedPage {
+                body: page.body,
+                from_cache: true,
+            });
+        }
 
         if !response.status().is_success() {
             anyhow::bail!("HTTP {} for {}", response.status(), url);
         }
 
-        response
+        let etag = header_value(&response, ETAG);
+        let last_modified = header_value(&response, LAST_MODIFIED);
+        let body = response
             .text()
             .await
-            .context("Failed to read response body")
+            .context("Failed to read response body")?;
+
+        if let Some(cache) = &run.cache {
+            let page = CachedPage {
+                url: url.to_string(),
+                etag,
+                last_modified,
+                fetched_at: chrono::Utc::now().timestamp(),
+                body,
+            };
+            if let Err(e) = cache.put(&page) {
+                tracing::warn!("Failed to cache {}: {:?}", url, e);
+            }
+            return Ok(FetchedPage {
+                body: page.body,
+                from_cache: false,
+            });
+        }
+
+        Ok(FetchedPage {
+            body,
+            from_cache: false,
+        })
+    }
+
+    /// GET with per-host backoff: 429 and 503 are retried up to `max_retries` times after
+    /// the server's `Retry-After`, or an exponentially growing delay when it sends none
+    async fn send_with_retries(
+        &self,
+        url: &str,
+        headers: &[(HeaderName, String)],
+    ) -> Result<Response> {
+        let opts = &self.definition.options;
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+
+        let mut attempt = 0;
+        loop {
+            self.backoff.wait(&host).await;
+
+            let mut request = self.client.get(url);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            let response = request
+                .send()
+                .await
+                .context(format!("Failed to fetch: {}", url))?;
+
+            let status = response.status();
+            let throttled = matches!(
+                status,
+                StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+            );
+            if !throttled || attempt >= opts.max_retries {
+                return Ok(response);
+            }
+
+            let delay = header_value(&response, RETRY_AFTER)
+                .and_then(|value| parse_retry_after(&value, chrono::Utc::now()))
+                .unwrap_or_else(|| exponential_delay(attempt, opts.retry_delay_ms))
+                .min(Duration::from_millis(opts.max_retry_delay_ms));
+            attempt += 1;
+            tracing::warn!(
+                "HTTP {} for {}, retry {}/{} in {:?}",
+                status,
+                url,
+                attempt,
+                opts.max_retries,
+                delay
+            );
+            self.backoff.pause(&host, delay);
+        }
+    }
+
+    /// `path` resolved against the site root, e.g. `/robots.txt`
+    fn site_url(&self, path: &str) -> Option<String> {
+        Url::parse(&self.definition.base_url)
+            .and_then(|base| base.join(path))
+            .ok()
+            .map(String::from)
+    }
+
+    /// Missing or unreachable robots.txt means everything is allowed
+    async fn load_robots(&self, run: &ScrapeRun) -> RobotsRules {
+        let opts = &self.definition.options;
+        if !opts.respect_robots && !opts.use_sitemap {
+            return RobotsRules::default();
+        }
+
+        let Some(url) = self.site_url("/robots.txt") else {
+            return RobotsRules::default();
+        };
+        match self.fetch_page(&url, run).await {
+            Ok(page) => RobotsRules::parse(&page.body, &opts.user_agent),
+            Err(e) => {
+                tracing::debug!("No robots.txt for {}: {:?}", self.definition.name, e);
+                RobotsRules::default()
+            }
+        }
+    }
+
+    /// Paths of the site's pages listed in its sitemaps, nested indexes included
+    async fn sitemap_paths(&self, run: &ScrapeRun, robots: &RobotsRules) -> Vec<String> {
+        let mut pending: VecDeque<String> = if robots.sitemaps.is_empty() {
+            self.site_url("/sitemap.xml").into_iter().collect()
+        } else {
+            robots.sitemaps.iter().cloned().collect()
+        };
+
+        let mut fetched = HashSet::new();
+        let mut paths = Vec::new();
+        while let Some(url) = pending.pop_front() {
+            if fetched.len() >= MAX_SITEMAPS || !fetched.insert(url.clone()) {
+                continue;
+            }
+
+            match self.fetch_page(&url, run).await {
+                Ok(page) => {
+                    let sitemap = Sitemap::parse(&page.body);
+                    pending.extend(sitemap.sitemaps);
+                    paths.extend(sitemap.urls.iter().filter_map(|loc| self.extract_path(loc)));
+                }
+                Err(e) => tracing::warn!("Failed to read sitemap {}: {:?}", url, e),
+            }
+        }
+
+        paths
+    }
+
+    /// Path and query of `url` as robots.txt rules see them
+    fn robots_path(url: &str) -> String {
+        match Url::parse(url) {
+            Ok(url) => match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_string(),
+            },
+            Err(_) => url.to_string(),
+        }
     }
 
     fn parse_page(&self, html: &str, path: &str, url: &str) -> ScrapedPage {
@@ -709,22 +1122,20 @@ impl UrlScraper {
         }
     }
 
-    pub async fn scrape_with_progress(
-        &self,
-        progress_tx: ProgressSender,
-    ) -> Result<Vec<ParsedDocEntry>> {
+    /// Breadth-first crawl that stops between pages when `run.cancel` fires and continues
+    /// from `run.resume` when given the state of an earlier run
+    pub async fn scrape(&self, run: ScrapeRun) -> Result<Vec<ParsedDocEntry>> {
         let opts = &self.definition.options;
         let max_pages = opts.max_pages.unwrap_or(999);
 
-        let _ = progress_tx
-            .send(ScrapeProgress {
-                current_page: 0,
-                max_pages,
-                current_path: "".to_string(),
-                entries_count: 0,
-                status: ScrapeStatus::Starting,
-            })
-            .await;
+        run.progress(ScrapeProgress {
+            current_page: 0,
+            max_pages,
+            current_path: "".to_string(),
+            entries_count: 0,
+            status: ScrapeStatus::Starting,
+        })
+        .await;
 
         tracing::info!(
             "║ 📚 Starting URL Scraper: {}",
@@ -733,19 +1144,79 @@ impl UrlScraper {
         tracing::info!("║ 🌐 Base URL: {}", self.definition.base_url);
         tracing::info!("║ 📦 Version: {}", self.definition.version);
 
-        let mut visited: HashSet<String> = HashSet::new();
-        let mut existing_paths: HashSet<String> = HashSet::new();
-        let mut queue: VecDeque<(String, usize)> = VecDeque::new();
-        let mut entries: Vec<ParsedDocEntry> = Vec::new();
+        let resumed = !run.resume.is_empty();
+        let CrawlState {
+            frontier,
+            visited,
+            entries,
+        } = run.resume.clone();
+
+        let mut existing_paths: HashSet<String> =
+            entries.iter().map(|entry| entry.path.clone()).collect();
+        let mut page_count = visited.len();
+        let mut visited: HashSet<String> = visited.into_iter().collect();
+        let mut queue: VecDeque<(String, usize)> = frontier.into();
+        let mut entries = entries;
+
+        if resumed {
+            tracing::info!(
+                "║ ⏯ Resuming: {} visited, {} queued, {} entries",
+                visited.len(),
+                queue.len(),
+                entries.len()
+            );
+        }
+
+        let robots = tokio::select! {
+            robots = self.load_robots(&run) => robots,
+            _ = run.cancel.cancelled() => {
+                return Err(run.cancelled(page_count, max_pages, entries.len()).await);
+            }
+        };
+        let delay = match robots.crawl_delay {
+            Some(crawl_delay) if opts.respect_robots => {
+                tracing::info!("║ 🐢 robots.txt Crawl-delay: {:?}", crawl_delay);
+                crawl_delay.max(Duration::from_millis(opts.delay_ms))
+            }
+            _ => Duration::from_millis(opts.delay_ms),
+        };
 
-        for path in &opts.initial_paths {
-            queue.push_back((path.clone(), 0));
+        if !resumed {
+            for path in &opts.initial_paths {
+                queue.push_back((path.clone(), 0));
+            }
+            if opts.use_sitemap {
+                let paths = tokio::select! {
+                    paths = self.sitemap_paths(&run, &robots) => paths,
+                    _ = run.cancel.cancelled() => {
+                        return Err(run.cancelled(page_count, max_pages, entries.len()).await);
+                    }
+                };
+                tracing::info!("║ 🗺 Sitemap pages: {}", paths.len());
+                for path in paths {
+                    if !queue.iter().any(|(queued, _)| *queued == path) {
+                        queue.push_back((path, 0));
+                    }
+                }
+            }
+            run.checkpoint(CheckpointEvent::Seeded {
+                queued: queue.iter().cloned().collect(),
+            })
+            .await;
+        }
+
+        if run.offline {
+            tracing::info!("║ 📴 Offline: rebuilding from cached pages");
         }
 
         let semaphore = Arc::new(Semaphore::new(opts.concurrent_requests));
-        let mut page_count = 0;
+        let mut cached_pages = 0;
 
         while let Some((path, depth)) = queue.pop_front() {
+            if run.cancel.is_cancelled() {
+                return Err(run.cancelled(page_count, max_pages, entries.len()).await);
+            }
+
             if visited.contains(&path) {
                 continue;
             }
@@ -765,25 +1236,41 @@ impl UrlScraper {
                 continue;
             }
 
+            let url = self.resolve_url(&path);
+            if opts.respect_robots && !robots.is_allowed(&Self::robots_path(&url)) {
+                tracing::debug!("Disallowed by robots.txt: {}", url);
+                continue;
+            }
+
             visited.insert(path.clone());
 
-            let _ = progress_tx
-                .send(ScrapeProgress {
-                    current_page: page_count + 1,
-                    max_pages,
-                    current_path: path.clone(),
-                    entries_count: entries.len(),
-                    status: ScrapeStatus::Scraping,
-                })
-                .await;
+            run.progress(ScrapeProgress {
+                current_page: page_count + 1,
+                max_pages,
+                current_path: path.clone(),
+                entries_count: entries.len(),
+                status: ScrapeStatus::Scraping,
+            })
+            .await;
 
-            let url = self.resolve_url(&path);
             let _permit = semaphore.acquire().await.unwrap();
 
-            match self.fetch_page(&url).await {
-                Ok(html) => {
-                    let page = self.parse_page(&html, &path, &url);
+            let fetched = tokio::select! {
+                fetched = self.fetch_page(&url, &run) => fetched,
+                _ = run.cancel.cancelled() => {
+                    return Err(run.cancelled(page_count, max_pages, entries.len()).await);
+                }
+            };
+
+            let first_new_entry = entries.len();
+            let mut queued = Vec::new();
+            match fetched {
+                Ok(fetched) => {
+                    let page = self.parse_page(&fetched.body, &path, &url);
                     page_count += 1;
+                    if fetched.from_cache {
+                        cached_pages += 1;
+                    }
 
                     self.ensure_parent_entries(&page.path, &mut existing_paths, &mut entries);
 
@@ -801,6 +1288,7 @@ impl UrlScraper {
                     if opts.follow_links {
                         for link in page.links {
                             if !visited.contains(&link) && !opts.should_skip(&link) {
+                                queued.push((link.clone(), depth + 1));
                                 queue.push_back((link, depth + 1));
                             }
                         }
@@ -811,23 +1299,40 @@ impl UrlScraper {
                 }
             }
 
-            if opts.delay_ms > 0 {
-                tokio::time::sleep(Duration::from_millis(opts.delay_ms)).await;
+            run.checkpoint(CheckpointEvent::Visited {
+                path,
+                entries: entries[first_new_entry..].to_vec(),
+                queued,
+            })
+            .await;
+
+            if !delay.is_zero() && !run.offline {
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = run.cancel.cancelled() => {}
+                }
             }
         }
 
-        let _ = progress_tx
-            .send(ScrapeProgress {
-                current_page: page_count,
-                max_pages,
-                current_path: "".to_string(),
-                entries_count: entries.len(),
-                status: ScrapeStatus::Completed,
-            })
-            .await;
+        if run.offline && page_count == 0 {
+            anyhow::bail!(
+                "No cached pages for {}, it has to be fetched online first",
+                self.definition.name
+            );
+        }
+
+        run.progress(ScrapeProgress {
+            current_page: page_count,
+            max_pages,
+            current_path: "".to_string(),
+            entries_count: entries.len(),
+            status: ScrapeStatus::Completed,
+        })
+        .await;
 
         tracing::info!("║ ✅ Scraping completed!");
         tracing::info!("║ 📊 Total pages scraped: {}", page_count);
+        tracing::info!("║ ♻ Served from cache: {}", cached_pages);
         tracing::info!("║ 📝 Entries created: {}", entries.len());
 
         Ok(entries)
@@ -837,3 +1342,11 @@ impl UrlScraper {
         &self.definition
     }
 }
+
+fn header_value(response: &Response, name: HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
@@ -1,104 +1,420 @@
+use super::embeddings::{blob_to_vector, cosine_similarity, Embedder, HashedNgramEmbedder};
+use super::search_query::{ParsedQuery, QueryFilter, TextTerm, TimeRange};
+use crate::models::config::SearchConfig;
 use crate::models::*;
 use anyhow::{Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
 use sqlx::{Pool, QueryBuilder, Row, Sqlite};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Semantic hits below this cosine similarity are treated as noise
+const MIN_SEMANTIC_SIMILARITY: f32 = 0.25;
+/// Top FTS hits re-ranked together with semantic matches; results past this window
+/// continue in plain bm25 order
+const BLEND_WINDOW: i64 = 200;
 
 pub struct SearchEngine {
     pool: Pool<Sqlite>,
+    embedder: Arc<dyn Embedder>,
+    fts_weight: f64,
+    semantic_weight: f64,
+}
+
+struct SearchHit {
+    rowid: i64,
+    rank: Option<f64>,
+    /// Blended score, set only for hits inside the blended window
+    score: Option<f64>,
+}
+
+impl SearchHit {
+    fn cursor(&self) -> SearchCursor {
+        match self.score {
+            Some(score) => SearchCursor::Blended {
+                score,
+                rowid: self.rowid,
+            },
+            None => SearchCursor::Fts {
+                rank: self.rank,
+                rowid: self.rowid,
+            },
+        }
+    }
+}
+
+/// Position of the last returned hit, handed to clients as an opaque string
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "phase", rename_all = "lowercase")]
+enum SearchCursor {
+    Blended {
+        #[serde(with = "float_bits")]
+        score: f64,
+        rowid: i64,
+    },
+    Fts {
+        #[serde(with = "optional_float_bits")]
+        rank: Option<f64>,
+        rowid: i64,
+    },
+}
+
+/// Keyset comparisons need the exact score back, so floats travel as raw bits
+mod float_bits {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(value.to_bits())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        u64::deserialize(deserializer).map(f64::from_bits)
+    }
+}
+
+mod optional_float_bits {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&value.to_bits()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<f64>, D::Error> {
+        Option::<u64>::deserialize(deserializer).map(|bits| bits.map(f64::from_bits))
+    }
+}
+
+impl SearchCursor {
+    fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    fn decode(value: &str) -> Result<Self> {
+        let json = URL_SAFE_NO_PAD
+            .decode(value)
+            .context("Invalid search cursor")?;
+        serde_json::from_slice(&json).context("Invalid search cursor")
+    }
+
+    /// Whether a blended hit comes after this cursor (score desc, rowid asc)
+    fn precedes(&self, hit: &SearchHit) -> bool {
+        match (self, hit.score) {
+            (SearchCursor::Blended { score, rowid }, Some(hit_score)) => {
+                hit_score < *score || (hit_score == *score && hit.rowid > *rowid)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// bm25 weights of the title, content and tags columns
+const ITEM_COLUMN_WEIGHTS: &str = "10.0, 1.0, 4.0";
+/// Doc entries carry the documentation name in `tags`, it says little about the entry
+const DOC_COLUMN_WEIGHTS: &str = "10.0, 1.0, 1.0";
+/// The trigram index has no `tags` column
+const TRIGRAM_COLUMN_WEIGHTS: &str = "10.0, 1.0";
+/// Shorter words are not corrected: too many vocabulary terms are one edit away
+const MIN_TYPO_LENGTH: usize = 4;
+
+/// FTS index a hit comes from. Hits keep signed ids across both indexes: item ids are
+/// positive, doc entry ids negative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FtsSource {
+    Items,
+    Docs,
+    /// Substrings of item titles and content, queried only when `Items` finds nothing
+    ItemsTrigram,
+}
+
+impl FtsSource {
+    fn of(rowid: i64) -> Self {
+        if rowid > 0 {
+            FtsSource::Items
+        } else {
+            FtsSource::Docs
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            FtsSource::Items => "items_fts",
+            FtsSource::Docs => "docs_fts",
+            FtsSource::ItemsTrigram => "items_trigram",
+        }
+    }
+
+    /// The signed hit id of the current FTS row
+    fn signed_rowid(self) -> &'static str {
+        match self {
+            FtsSource::Items | FtsSource::ItemsTrigram => "rowid",
+            FtsSource::Docs => "-rowid",
+        }
+    }
+
+    fn column_weights(self) -> &'static str {
+        match self {
+            FtsSource::Items => ITEM_COLUMN_WEIGHTS,
+            FtsSource::Docs => DOC_COLUMN_WEIGHTS,
+            FtsSource::ItemsTrigram => TRIGRAM_COLUMN_WEIGHTS,
+        }
+    }
+
+    fn holds_items(self) -> bool {
+        matches!(self, FtsSource::Items | FtsSource::ItemsTrigram)
+    }
+
+    fn match_expr(self, search_query: &ParsedQuery) -> Option<String> {
+        match self {
+            FtsSource::ItemsTrigram => search_query.trigram_match(),
+            FtsSource::Items | FtsSource::Docs => search_query.fts_match(),
+        }
+    }
+
+    /// fts5vocab table over the source's terms, used to correct typos
+    fn vocabulary(self) -> Option<&'static str> {
+        match self {
+            FtsSource::Items => Some("items_vocab"),
+            FtsSource::Docs => Some("docs_vocab"),
+            FtsSource::ItemsTrigram => None,
+        }
+    }
+
+    /// Word index holding the same rows; excluded terms are matched as words
+    fn word_index(self) -> Self {
+        match self {
+            FtsSource::ItemsTrigram => FtsSource::Items,
+            source => source,
+        }
+    }
+}
+
+struct BlendedWindow {
+    hits: Vec<SearchHit>,
+    /// (rank, rowid) of the last FTS hit in the window when more FTS hits may follow
+    boundary: Option<(Option<f64>, i64)>,
 }
 
 impl SearchEngine {
     pub fn new(pool: Pool<Sqlite>) -> Self {
-        Self { pool }
+        let defaults = SearchConfig::default();
+        Self {
+            pool,
+            embedder: Arc::new(HashedNgramEmbedder::default()),
+            fts_weight: defaults.fts_weight,
+            semantic_weight: defaults.semantic_weight,
+        }
+    }
+
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    pub fn with_weights(mut self, fts_weight: f64, semantic_weight: f64) -> Self {
+        self.fts_weight = fts_weight.max(0.0);
+        self.semantic_weight = semantic_weight.max(0.0);
+        self
+    }
+
+    pub fn with_config(self, config: &SearchConfig) -> Self {
+        self.with_weights(config.fts_weight, config.semantic_weight)
     }
 
     pub async fn search(&self, query: SearchQuery) -> Result<SearchResult> {
-        let limit = query.limit.unwrap_or(50);
-        let offset = query.offset.unwrap_or(0);
-        let search_query = Self::prepare_fts_query(&query.query);
-        let use_rank_and_snippet = limit <= 100;
+        let search_query = ParsedQuery::parse(&query.query)?;
+        let sources = Self::sources(&search_query, &query);
+        let result = self.search_sources(&query, &search_query, &sources).await?;
+        if result.total > 0 || search_query.fts_match().is_none() {
+            return Ok(result);
+        }
 
-        let mut fts_builder = QueryBuilder::<Sqlite>::new("SELECT rowid, ");
-        if use_rank_and_snippet {
-            fts_builder.push("snippet(search_index, 1, '**', '**', '...', 10) AS snippet ");
-        } else {
-            fts_builder.push("NULL AS snippet ");
-        }
-        fts_builder.push("FROM search_index WHERE search_index MATCH ");
-        fts_builder.push_bind(&search_query);
-
-        match query.item_type {
-            Some(ItemType::Documentation) => {
-                fts_builder.push(" AND rowid < 0");
-            }
-            Some(ItemType::Snippet | ItemType::Config | ItemType::Note | ItemType::Link) => {
-                fts_builder.push(" AND rowid IN (SELECT id FROM items WHERE type = ");
-                let type_str = match query.item_type {
-                    Some(ItemType::Snippet) => "snippet",
-                    Some(ItemType::Config) => "config",
-                    Some(ItemType::Note) => "note",
-                    Some(ItemType::Link) => "link",
-                    _ => "",
-                };
-                fts_builder.push_bind(type_str);
-                fts_builder.push(")");
+        // No whole-word match: substrings of item text, e.g. `State` inside `useState`
+        if sources.contains(&FtsSource::Items) && search_query.trigram_match().is_some() {
+            let substrings = self
+                .search_sources(&query, &search_query, &[FtsSource::ItemsTrigram])
+                .await?;
+            if substrings.total > 0 {
+                return Ok(substrings);
             }
-            _ => {}
         }
 
-        if let Some(ref tag_ids) = query.tag_ids {
-            if !tag_ids.is_empty() {
-                fts_builder.push(
-                    " AND (rowid < 0 OR rowid IN (SELECT item_id FROM item_tags WHERE tag_id IN (",
-                );
-                let mut separated = fts_builder.separated(", ");
-                for tag_id in tag_ids {
-                    separated.push_bind(tag_id);
-                }
-                separated.push_unseparated(")))");
+        let corrections = self.correct_typos(&search_query, &sources).await?;
+        if corrections.is_empty() {
+            return Ok(result);
+        }
+        let mut corrected = search_query.clone();
+        for term in &mut corrected.terms {
+            if let Some(replacement) = corrections.get(&term.text.to_lowercase()) {
+                term.text = replacement.clone();
             }
         }
+        let mut retried = self.search_sources(&query, &corrected, &sources).await?;
+        if retried.total == 0 {
+            return Ok(result);
+        }
+        retried.corrected_query = Some(
+            query
+                .query
+                .split_whitespace()
+                .map(|word| {
+                    corrections
+                        .get(&word.to_lowercase())
+                        .map_or(word, String::as_str)
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        Ok(retried)
+    }
+
+    /// One search over the given FTS indexes, `search` picks them and the fallbacks
+    async fn search_sources(
+        &self,
+        query: &SearchQuery,
+        search_query: &ParsedQuery,
+        sources: &[FtsSource],
+    ) -> Result<SearchResult> {
+        let limit = query.limit.unwrap_or(50).max(0);
+        let cursor = query
+            .cursor
+            .as_deref()
+            .map(SearchCursor::decode)
+            .transpose()?;
+        // Offset is kept for older clients, a cursor always wins
+        let offset = if cursor.is_some() {
+            0
+        } else {
+            query.offset.unwrap_or(0).max(0)
+        };
+        let use_rank_and_snippet = limit <= 100;
 
-        if use_rank_and_snippet {
-            fts_builder.push(" ORDER BY bm25(search_index) ");
+        if sources.is_empty() {
+            return Ok(SearchResult {
+                items: Vec::new(),
+                total: 0,
+                has_more: false,
+                next_cursor: None,
+                corrected_query: None,
+            });
         }
-        fts_builder.push(" LIMIT ");
-        fts_builder.push_bind(limit);
-        fts_builder.push(" OFFSET ");
-        fts_builder.push_bind(offset);
+        let allow_items = sources.iter().any(|source| source.holds_items());
+        let allow_docs = sources.contains(&FtsSource::Docs);
 
-        let fts_rows = fts_builder
-            .build()
-            .fetch_all(&self.pool)
-            .await
-            .context("Failed to search in FTS index")?;
+        let semantic_text = search_query.semantic_text();
+        let blend = use_rank_and_snippet
+            && sources.contains(&FtsSource::Items)
+            && self.semantic_weight > 0.0
+            && !semantic_text.is_empty();
 
-        let allow_items = matches!(
-            query.item_type,
-            None | Some(ItemType::Snippet | ItemType::Config | ItemType::Note | ItemType::Link)
-        );
-        let allow_docs = matches!(query.item_type, None | Some(ItemType::Documentation));
+        let mut total = self.count_matches(search_query, query, sources).await?;
+        // One extra hit tells whether another page exists
+        let wanted = (offset + limit + 1) as usize;
+
+        let mut hits = if blend {
+            let semantic_scores = self
+                .semantic_scores(&semantic_text, search_query, query, BLEND_WINDOW)
+                .await?;
+            let semantic_ids: Vec<i64> = semantic_scores.keys().copied().collect();
+            let semantic_fts_matches = self
+                .count_matches_among(search_query, &semantic_ids)
+                .await?;
+            total += semantic_ids.len() as i64 - semantic_fts_matches;
+
+            let window = self
+                .blended_window(search_query, query, sources, &semantic_scores)
+                .await?;
+
+            let (mut hits, tail_after) = match cursor {
+                Some(SearchCursor::Fts { rank, rowid }) => (Vec::new(), Some((rank, rowid))),
+                _ => {
+                    let hits: Vec<SearchHit> = window
+                        .hits
+                        .into_iter()
+                        .filter(|hit| cursor.is_none_or(|c| c.precedes(hit)))
+                        .take(wanted)
+                        .collect();
+                    (hits, window.boundary)
+                }
+            };
+
+            let window_exhausted =
+                matches!(cursor, Some(SearchCursor::Fts { .. })) || window.boundary.is_some();
+            if hits.len() < wanted && window_exhausted {
+                let tail = self
+                    .fetch_fts_hits(
+                        search_query,
+                        query,
+                        sources,
+                        true,
+                        tail_after,
+                        &semantic_ids,
+                        (wanted - hits.len()) as i64,
+                        0,
+                    )
+                    .await?;
+                hits.extend(tail);
+            }
+
+            hits.into_iter().skip(offset as usize).collect()
+        } else {
+            let after = match cursor {
+                Some(SearchCursor::Fts { rank, rowid }) => Some((rank, rowid)),
+                Some(SearchCursor::Blended { .. }) => {
+                    anyhow::bail!("Search cursor does not match the current search settings")
+                }
+                None => None,
+            };
+            self.fetch_fts_hits(
+                search_query,
+                query,
+                sources,
+                use_rank_and_snippet,
+                after,
+                &[],
+                limit + 1,
+                offset,
+            )
+            .await?
+        };
+
+        let has_more = hits.len() > limit as usize;
+        hits.truncate(limit as usize);
+        let next_cursor = if has_more {
+            hits.last().map(|hit| hit.cursor().encode())
+        } else {
+            None
+        };
+
+        let snippets = if use_rank_and_snippet {
+            let rowids: Vec<i64> = hits.iter().map(|hit| hit.rowid).collect();
+            self.get_snippets(search_query, sources, &rowids).await?
+        } else {
+            HashMap::new()
+        };
 
-        let mut ordered_hits: Vec<(i64, Option<String>)> = Vec::with_capacity(fts_rows.len());
         let mut item_ids: Vec<i64> = Vec::new();
         let mut doc_ids: Vec<i64> = Vec::new();
 
-        for fts_row in fts_rows {
-            let rowid: i64 = fts_row.get("rowid");
-            let snippet: Option<String> = fts_row.get("snippet");
-            ordered_hits.push((rowid, snippet.clone()));
-
-            if rowid > 0 {
+        for hit in &hits {
+            if hit.rowid > 0 {
                 if allow_items {
-                    item_ids.push(rowid);
+                    item_ids.push(hit.rowid);
                 }
             } else if allow_docs {
-                doc_ids.push(-rowid);
+                doc_ids.push(-hit.rowid);
             }
         }
 
         let (items_map, tags_map) = if allow_items {
-            let items_map = self.get_items_for_search(&item_ids, &query).await?;
+            let items_map = self.get_items_for_search(&item_ids, query).await?;
             let tag_ids_for_items: Vec<i64> = items_map.keys().copied().collect();
             let tags_map = self.get_item_tags_map(&tag_ids_for_items).await?;
             (items_map, tags_map)
@@ -113,10 +429,8 @@ impl SearchEngine {
         };
 
         let mut result_items = Vec::new();
-        for (rowid, snippet) in ordered_hits {
-            if result_items.len() >= limit as usize {
-                break;
-            }
+        for SearchHit { rowid, .. } in hits {
+            let snippet = snippets.get(&rowid).cloned();
 
             if rowid > 0 {
                 let Some(item) = items_map.get(&rowid).cloned() else {
@@ -149,14 +463,585 @@ impl SearchEngine {
             }
         }
 
-        let total = result_items.len() as i64;
-
         Ok(SearchResult {
             items: result_items,
             total,
+            has_more,
+            next_cursor,
+            corrected_query: None,
         })
     }
 
+    /// FTS indexes the query can match, items first
+    fn sources(search_query: &ParsedQuery, query: &SearchQuery) -> Vec<FtsSource> {
+        let allow_items = search_query.allows_items()
+            && !Self::is_doc_scoped(query)
+            && matches!(
+                query.item_type,
+                None | Some(ItemType::Snippet | ItemType::Config | ItemType::Note | ItemType::Link)
+            );
+        let allow_docs = search_query.allows_docs()
+            && matches!(query.item_type, None | Some(ItemType::Documentation));
+
+        let mut sources = Vec::with_capacity(2);
+        if allow_items {
+            sources.push(FtsSource::Items);
+        }
+        if allow_docs {
+            sources.push(FtsSource::Docs);
+        }
+        sources
+    }
+
+    /// Replacements for misspelled words, keyed by the lowercased word: the closest term
+    /// of the sources' vocabularies within one edit (two for longer words). Words that
+    /// match on their own, phrases and short words are left alone.
+    async fn correct_typos(
+        &self,
+        search_query: &ParsedQuery,
+        sources: &[FtsSource],
+    ) -> Result<HashMap<String, String>> {
+        let mut corrections = HashMap::new();
+        for term in &search_query.terms {
+            let word = term.text.to_lowercase();
+            let length = word.chars().count();
+            if term.phrase
+                || length < MIN_TYPO_LENGTH
+                || !word.chars().all(char::is_alphanumeric)
+                || corrections.contains_key(&word)
+            {
+                continue;
+            }
+            if self.matches_alone(term, sources).await? {
+                continue;
+            }
+
+            let max_distance = if length <= 5 { 1 } else { 2 };
+            let first = word.chars().next().unwrap_or_default();
+            let Some(after_first) = char::from_u32(first as u32 + 1) else {
+                continue;
+            };
+
+            // (distance, rows containing the term, term)
+            let mut best: Option<(usize, i64, String)> = None;
+            for vocabulary in sources.iter().filter_map(|source| source.vocabulary()) {
+                let rows = sqlx::query(&format!(
+                    "SELECT term, doc FROM {vocabulary}
+                     WHERE term >= ?1 AND term < ?2 AND length(term) BETWEEN ?3 AND ?4"
+                ))
+                .bind(first.to_string())
+                .bind(after_first.to_string())
+                .bind((length - max_distance) as i64)
+                .bind((length + max_distance) as i64)
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to read search vocabulary")?;
+
+                for row in rows {
+                    let candidate: String = row.get("term");
+                    let doc: i64 = row.get("doc");
+                    let distance = levenshtein(&word, &candidate);
+                    if distance == 0 || distance > max_distance {
+                        continue;
+                    }
+                    let better =
+                        best.as_ref()
+                            .is_none_or(|(best_distance, best_doc, best_term)| {
+                                (distance, -doc, &candidate)
+                                    < (*best_distance, -*best_doc, best_term)
+                            });
+                    if better {
+                        best = Some((distance, doc, candidate));
+                    }
+                }
+            }
+
+            if let Some((_, _, candidate)) = best {
+                corrections.insert(word, candidate);
+            }
+        }
+        Ok(corrections)
+    }
+
+    /// Whether the word matches anything in the sources without the rest of the query
+    async fn matches_alone(&self, term: &TextTerm, sources: &[FtsSource]) -> Result<bool> {
+        for source in sources {
+            let table = source.table();
+            let found = sqlx::query(&format!(
+                "SELECT 1 FROM {table} WHERE {table} MATCH ?1 LIMIT 1"
+            ))
+            .bind(term.to_fts())
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to check search term")?;
+            if found.is_some() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// `(SELECT id, rank ...)` over every source: `id` is the signed hit id, `rank` is
+    /// bm25 divided by the best bm25 of the same source and negated, so the top match of
+    /// each source ranks -1 and a large doc set cannot push items down. Without ranking,
+    /// or without text terms, `rank` is NULL.
+    fn push_fts_union(
+        builder: &mut QueryBuilder<'_, Sqlite>,
+        search_query: &ParsedQuery,
+        query: &SearchQuery,
+        sources: &[FtsSource],
+        ranked: bool,
+    ) {
+        let ranked = ranked && search_query.fts_match().is_some();
+
+        builder.push("(");
+        for (index, &source) in sources.iter().enumerate() {
+            if index > 0 {
+                builder.push(" UNION ALL ");
+            }
+            let table = source.table();
+            if ranked {
+                builder.push(format!(
+                    "SELECT id, -(raw / MIN(raw) OVER ()) AS rank FROM (SELECT {} AS id, bm25({table}, {}) AS raw ",
+                    source.signed_rowid(),
+                    source.column_weights()
+                ));
+            } else {
+                builder.push(format!(
+                    "SELECT {} AS id, NULL AS rank ",
+                    source.signed_rowid()
+                ));
+            }
+            Self::push_fts_source(builder, search_query, query, source);
+            if ranked {
+                builder.push(")");
+            }
+        }
+        builder.push(")");
+    }
+
+    /// `FROM items_fts WHERE ...` (or another index) with the MATCH clause when the query
+    /// has text terms, followed by every filter
+    fn push_fts_source(
+        builder: &mut QueryBuilder<'_, Sqlite>,
+        search_query: &ParsedQuery,
+        query: &SearchQuery,
+        source: FtsSource,
+    ) {
+        let table = source.table();
+        builder.push(format!("FROM {table} WHERE "));
+        match source.match_expr(search_query) {
+            Some(fts_match) => {
+                builder.push(format!("{table} MATCH "));
+                builder.push_bind(fts_match);
+            }
+            None => {
+                builder.push("1 = 1");
+            }
+        }
+        Self::push_fts_filters(builder, query, source);
+        let pins_doc_version = search_query.pins_doc_version()
+            || query.doc_ids.as_ref().is_some_and(|ids| !ids.is_empty());
+        Self::push_query_filters(
+            builder,
+            search_query,
+            source.signed_rowid(),
+            source,
+            pins_doc_version,
+        );
+    }
+
+    /// `docIds` or `pathPrefix` limit the search to doc entries
+    fn is_doc_scoped(query: &SearchQuery) -> bool {
+        query.doc_ids.as_ref().is_some_and(|ids| !ids.is_empty())
+            || Self::path_prefix(query).is_some()
+    }
+
+    fn path_prefix(query: &SearchQuery) -> Option<&str> {
+        query
+            .path_prefix
+            .as_deref()
+            .map(|prefix| prefix.trim_end_matches('/'))
+            .filter(|prefix| !prefix.is_empty())
+    }
+
+    /// Filters from the request itself. Sources that cannot match them are not queried
+    /// at all, so each one only narrows its own rows.
+    fn push_fts_filters(
+        builder: &mut QueryBuilder<'_, Sqlite>,
+        query: &SearchQuery,
+        source: FtsSource,
+    ) {
+        if source.holds_items() {
+            if let Some(ref item_type) = query.item_type {
+                builder.push(" AND rowid IN (SELECT id FROM items WHERE type = ");
+                builder.push_bind(Self::item_type_to_str(item_type));
+                builder.push(")");
+            }
+
+            if let Some(ref tag_ids) = query.tag_ids {
+                if !tag_ids.is_empty() {
+                    builder.push(" AND rowid IN (SELECT item_id FROM item_tags WHERE tag_id IN (");
+                    let mut separated = builder.separated(", ");
+                    for tag_id in tag_ids {
+                        separated.push_bind(*tag_id);
+                    }
+                    separated.push_unseparated("))");
+                }
+            }
+            return;
+        }
+
+        if Self::is_doc_scoped(query) {
+            builder.push(" AND rowid IN (SELECT id FROM doc_entries WHERE 1 = 1");
+            if let Some(ref doc_ids) = query.doc_ids {
+                if !doc_ids.is_empty() {
+                    builder.push(" AND doc_id IN (");
+                    let mut separated = builder.separated(", ");
+                    for doc_id in doc_ids {
+                        separated.push_bind(*doc_id);
+                    }
+                    separated.push_unseparated(")");
+                }
+            }
+            // The section itself and everything below it, as a range over idx_entries_doc_path:
+            // `a/b/` <= path < `a/b0` (`0` follows `/`), so `a/bc` stays out
+            if let Some(prefix) = Self::path_prefix(query) {
+                builder.push(" AND (path = ");
+                builder.push_bind(prefix.to_string());
+                builder.push(" OR (path >= ");
+                builder.push_bind(format!("{prefix}/"));
+                builder.push(" AND path < ");
+                builder.push_bind(format!("{prefix}0"));
+                builder.push("))");
+            }
+            builder.push(")");
+        }
+    }
+
+    async fn count_matches(
+        &self,
+        search_query: &ParsedQuery,
+        query: &SearchQuery,
+        sources: &[FtsSource],
+    ) -> Result<i64> {
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) AS count FROM ");
+        Self::push_fts_union(&mut builder, search_query, query, sources, false);
+
+        let row = builder
+            .build()
+            .fetch_one(&self.pool)
+            .await
+            .context("Failed to count search results")?;
+        Ok(row.get("count"))
+    }
+
+    /// How many of the item `rowids` also match the text terms; the filters are already applied
+    async fn count_matches_among(&self, search_query: &ParsedQuery, rowids: &[i64]) -> Result<i64> {
+        let Some(fts_match) = search_query.fts_match() else {
+            return Ok(rowids.len() as i64);
+        };
+        if rowids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT COUNT(*) AS count FROM items_fts WHERE items_fts MATCH ",
+        );
+        builder.push_bind(fts_match);
+        builder.push(" AND rowid IN (");
+        let mut separated = builder.separated(", ");
+        for rowid in rowids {
+            separated.push_bind(*rowid);
+        }
+        separated.push_unseparated(")");
+
+        let row = builder
+            .build()
+            .fetch_one(&self.pool)
+            .await
+            .context("Failed to count search results")?;
+        Ok(row.get("count"))
+    }
+
+    /// Keyset page over FTS matches ordered by (normalized bm25, id), or by id alone when
+    /// ranking is disabled for large pages
+    #[allow(clippy::too_many_arguments)]
+    async fn fetch_fts_hits(
+        &self,
+        search_query: &ParsedQuery,
+        query: &SearchQuery,
+        sources: &[FtsSource],
+        ranked: bool,
+        after: Option<(Option<f64>, i64)>,
+        exclude: &[i64],
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SearchHit>> {
+        // bm25 needs a MATCH, filter-only queries are listed by id
+        let ranked = ranked && search_query.fts_match().is_some();
+
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT id, rank FROM ");
+        Self::push_fts_union(&mut builder, search_query, query, sources, ranked);
+        builder.push(" WHERE 1 = 1");
+
+        match after {
+            Some((Some(rank), rowid)) if ranked => {
+                builder.push(" AND (rank > ");
+                builder.push_bind(rank);
+                builder.push(" OR (rank = ");
+                builder.push_bind(rank);
+                builder.push(" AND id > ");
+                builder.push_bind(rowid);
+                builder.push("))");
+            }
+            Some((_, rowid)) => {
+                builder.push(" AND id > ");
+                builder.push_bind(rowid);
+            }
+            None => {}
+        }
+
+        if !exclude.is_empty() {
+            builder.push(" AND id NOT IN (");
+            let mut separated = builder.separated(", ");
+            for rowid in exclude {
+                separated.push_bind(*rowid);
+            }
+            separated.push_unseparated(")");
+        }
+
+        if ranked {
+            builder.push(" ORDER BY rank, id");
+        } else {
+            builder.push(" ORDER BY id");
+        }
+        builder.push(" LIMIT ");
+        builder.push_bind(limit);
+        builder.push(" OFFSET ");
+        builder.push_bind(offset);
+
+        let rows = builder
+            .build()
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to search in FTS index")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SearchHit {
+                rowid: row.get("id"),
+                rank: row.get("rank"),
+                score: None,
+            })
+            .collect())
+    }
+
+    /// Snippets are built only for the returned page, not for every match
+    async fn get_snippets(
+        &self,
+        search_query: &ParsedQuery,
+        sources: &[FtsSource],
+        rowids: &[i64],
+    ) -> Result<HashMap<i64, String>> {
+        let mut snippets = HashMap::with_capacity(rowids.len());
+        for &source in sources {
+            let Some(fts_match) = source.match_expr(search_query) else {
+                continue;
+            };
+            let ids: Vec<i64> = rowids
+                .iter()
+                .filter(|rowid| FtsSource::of(**rowid).holds_items() == source.holds_items())
+                .map(|rowid| rowid.abs())
+                .collect();
+            if ids.is_empty() {
+                continue;
+            }
+
+            let table = source.table();
+            let mut builder = QueryBuilder::<Sqlite>::new(format!(
+                "SELECT {} AS id, snippet({table}, 1, '**', '**', '...', 10) AS snippet
+                 FROM {table} WHERE {table} MATCH ",
+                source.signed_rowid()
+            ));
+            builder.push_bind(fts_match);
+            builder.push(" AND rowid IN (");
+            let mut separated = builder.separated(", ");
+            for id in ids {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(")");
+
+            let rows = builder
+                .build()
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to build search snippets")?;
+            snippets.extend(rows.iter().map(|row| (row.get("id"), row.get("snippet"))));
+        }
+
+        Ok(snippets)
+    }
+
+    async fn blended_window(
+        &self,
+        search_query: &ParsedQuery,
+        query: &SearchQuery,
+        sources: &[FtsSource],
+        semantic_scores: &HashMap<i64, f32>,
+    ) -> Result<BlendedWindow> {
+        let fts_hits = self
+            .fetch_fts_hits(
+                search_query,
+                query,
+                sources,
+                true,
+                None,
+                &[],
+                BLEND_WINDOW,
+                0,
+            )
+            .await?;
+
+        let boundary = if fts_hits.len() as i64 >= BLEND_WINDOW {
+            fts_hits.last().map(|hit| (hit.rank, hit.rowid))
+        } else {
+            None
+        };
+
+        Ok(BlendedWindow {
+            hits: self.blend_hits(fts_hits, semantic_scores),
+            boundary,
+        })
+    }
+
+    /// Cosine similarity between the query and stored item vectors, restricted by the
+    /// same filters as the FTS query. Returns the best `pool_size` matches.
+    async fn semantic_scores(
+        &self,
+        text: &str,
+        search_query: &ParsedQuery,
+        query: &SearchQuery,
+        pool_size: i64,
+    ) -> Result<HashMap<i64, f32>> {
+        let query_vector = self.embedder.embed(text);
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT e.item_id, e.vector FROM embeddings e
+             JOIN items i ON i.id = e.item_id
+             WHERE e.model = ",
+        );
+        builder.push_bind(self.embedder.model().to_string());
+
+        if let Some(ref item_type) = query.item_type {
+            builder.push(" AND i.type = ");
+            builder.push_bind(Self::item_type_to_str(item_type));
+        }
+
+        if let Some(ref tag_ids) = query.tag_ids {
+            if !tag_ids.is_empty() {
+                builder.push(
+                    " AND EXISTS (SELECT 1 FROM item_tags it WHERE it.item_id = i.id AND it.tag_id IN (",
+                );
+                let mut separated = builder.separated(", ");
+                for tag_id in tag_ids {
+                    separated.push_bind(tag_id);
+                }
+                separated.push_unseparated("))");
+            }
+        }
+
+        Self::push_query_filters(
+            &mut builder,
+            search_query,
+            "e.item_id",
+            FtsSource::Items,
+            false,
+        );
+
+        let rows = builder
+            .build()
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to load embeddings")?;
+
+        let mut scored: Vec<(i64, f32)> = rows
+            .iter()
+            .filter_map(|row| {
+                let blob: Vec<u8> = row.get("vector");
+                let similarity = cosine_similarity(&query_vector, &blob_to_vector(&blob));
+                (similarity >= MIN_SEMANTIC_SIMILARITY).then(|| (row.get("item_id"), similarity))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(pool_size.max(0) as usize);
+
+        Ok(scored.into_iter().collect())
+    }
+
+    /// Merges FTS hits with semantic matches. bm25 ranks are min-max normalized to
+    /// [0, 1] over the window and mixed with cosine similarity using the configured
+    /// weights. Doc entries have no vectors and are scored by FTS alone.
+    fn blend_hits(&self, hits: Vec<SearchHit>, semantic: &HashMap<i64, f32>) -> Vec<SearchHit> {
+        let total_weight = self.fts_weight + self.semantic_weight;
+        let (fts_weight, semantic_weight) = if total_weight > 0.0 {
+            (
+                self.fts_weight / total_weight,
+                self.semantic_weight / total_weight,
+            )
+        } else {
+            (1.0, 0.0)
+        };
+
+        // bm25 is negative, lower is better
+        let ranks: Vec<f64> = hits.iter().filter_map(|hit| hit.rank).collect();
+        let best = ranks.iter().copied().fold(f64::INFINITY, f64::min);
+        let worst = ranks.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let fts_score = |rank: Option<f64>| -> f64 {
+            match rank {
+                Some(rank) if worst - best > f64::EPSILON => (worst - rank) / (worst - best),
+                Some(_) => 1.0,
+                None => 0.0,
+            }
+        };
+
+        let mut seen = HashSet::new();
+        let mut blended: Vec<SearchHit> = Vec::with_capacity(hits.len() + semantic.len());
+
+        for mut hit in hits {
+            seen.insert(hit.rowid);
+            let fts = fts_score(hit.rank);
+            let score = if hit.rowid > 0 {
+                let similarity = semantic.get(&hit.rowid).copied().unwrap_or(0.0) as f64;
+                fts_weight * fts + semantic_weight * similarity
+            } else {
+                fts
+            };
+            hit.score = Some(score);
+            blended.push(hit);
+        }
+
+        for (&item_id, &similarity) in semantic {
+            if seen.contains(&item_id) {
+                continue;
+            }
+            blended.push(SearchHit {
+                rowid: item_id,
+                rank: None,
+                score: Some(semantic_weight * similarity as f64),
+            });
+        }
+
+        blended.sort_by(|a, b| {
+            b.score
+                .unwrap_or_default()
+                .total_cmp(&a.score.unwrap_or_default())
+                .then(a.rowid.cmp(&b.rowid))
+        });
+        blended
+    }
+
     async fn get_items_for_search(
         &self,
         ids: &[i64],
@@ -277,22 +1162,156 @@ impl SearchEngine {
         Ok(map)
     }
 
-    fn prepare_fts_query(query: &str) -> String {
-        let terms: Vec<&str> = query.split_whitespace().collect();
+    /// Structured filters from the query string. `rowid` is the expression holding the
+    /// signed hit id of `source`: item ids are positive, doc entries negative. Inactive
+    /// doc versions are skipped unless `pins_doc_version`.
+    fn push_query_filters(
+        builder: &mut QueryBuilder<'_, Sqlite>,
+        search_query: &ParsedQuery,
+        rowid: &str,
+        source: FtsSource,
+        pins_doc_version: bool,
+    ) {
+        if let Some(types) = search_query.positive_types() {
+            let unindexed = format!("+{rowid}");
+            builder.push(" AND (0");
+            for item_type in types {
+                builder.push(" OR ");
+                Self::push_filter_condition(
+                    builder,
+                    &QueryFilter::Type(item_type.clone()),
+                    &unindexed,
+                );
+            }
+            builder.push(")");
+        }
+
+        // Trashed rows stay in the indexes until purged
+        if source.holds_items() {
+            builder.push(format!(
+                " AND +{rowid} NOT IN (SELECT id FROM items WHERE deleted_at IS NOT NULL)"
+            ));
+        } else {
+            let hidden = if pins_doc_version {
+                "d.deleted_at IS NOT NULL"
+            } else {
+                "d.is_active = 0 OR d.deleted_at IS NOT NULL"
+            };
+            builder.push(format!(
+                " AND +{rowid} NOT IN (SELECT -de.id FROM doc_entries de
+                 JOIN documentations d ON d.id = de.doc_id
+                 WHERE {hidden})"
+            ));
+        }
 
-        if terms.is_empty() {
-            return String::from("*");
+        for filter in &search_query.filters {
+            if matches!(filter, QueryFilter::Type(_)) {
+                continue;
+            }
+            builder.push(" AND ");
+            Self::push_filter_condition(builder, filter, rowid);
         }
 
-        if terms.len() == 1 {
-            return format!("\"{}\"*", terms[0]);
+        for filter in &search_query.excluded_filters {
+            builder.push(" AND NOT ");
+            Self::push_filter_condition(builder, filter, rowid);
         }
 
-        terms
-            .iter()
-            .map(|term| format!("\"{}\"*", term))
-            .collect::<Vec<_>>()
-            .join(" AND ")
+        if let Some(excluded_match) = search_query.excluded_match() {
+            let words = source.word_index();
+            let table = words.table();
+            builder.push(format!(
+                " AND {rowid} NOT IN (SELECT {} FROM {table} WHERE {table} MATCH ",
+                words.signed_rowid()
+            ));
+            builder.push_bind(excluded_match);
+            builder.push(")");
+        }
+    }
+
+    fn push_filter_condition(
+        builder: &mut QueryBuilder<'_, Sqlite>,
+        filter: &QueryFilter,
+        rowid: &str,
+    ) {
+        match filter {
+            QueryFilter::Type(ItemType::Documentation) => {
+                builder.push(format!("({rowid} < 0)"));
+            }
+            QueryFilter::Type(item_type) => {
+                builder.push(format!("({rowid} IN (SELECT id FROM items WHERE type = "));
+                builder.push_bind(Self::item_type_to_str(item_type));
+                builder.push("))");
+            }
+            QueryFilter::Tag(name) => {
+                builder.push(format!(
+                    "({rowid} IN (SELECT it.item_id FROM item_tags it
+                     JOIN tags t ON t.id = it.tag_id
+                     WHERE t.name = "
+                ));
+                builder.push_bind(name.clone());
+                builder.push(" COLLATE NOCASE))");
+            }
+            QueryFilter::Doc { name, version } => {
+                builder.push(format!(
+                    "({rowid} IN (SELECT -de.id FROM doc_entries de
+                     JOIN documentations d ON d.id = de.doc_id
+                     WHERE (d.name = "
+                ));
+                builder.push_bind(name.clone());
+                builder.push(" COLLATE NOCASE OR d.display_name = ");
+                builder.push_bind(name.clone());
+                builder.push(" COLLATE NOCASE)");
+                if let Some(version) = version {
+                    builder.push(" AND d.version = ");
+                    builder.push_bind(version.clone());
+                }
+                builder.push("))");
+            }
+            QueryFilter::Lang(language) => {
+                builder.push(format!(
+                    "({rowid} IN (SELECT id FROM items
+                     WHERE json_valid(metadata) AND lower(json_extract(metadata, '$.language')) = "
+                ));
+                builder.push_bind(language.clone());
+                builder.push("))");
+            }
+            QueryFilter::Created(range) => {
+                Self::push_time_condition(builder, "created_at", range, rowid);
+            }
+            QueryFilter::Updated(range) => {
+                Self::push_time_condition(builder, "updated_at", range, rowid);
+            }
+        }
+    }
+
+    /// Doc entries only have `created_at`, it stands in for both columns
+    fn push_time_condition(
+        builder: &mut QueryBuilder<'_, Sqlite>,
+        column: &str,
+        range: &TimeRange,
+        rowid: &str,
+    ) {
+        let push_bounds = |builder: &mut QueryBuilder<'_, Sqlite>, column: &str| {
+            if let Some(from) = range.from {
+                builder.push(format!(" AND {column} >= "));
+                builder.push_bind(from);
+            }
+            if let Some(to) = range.to {
+                builder.push(format!(" AND {column} < "));
+                builder.push_bind(to);
+            }
+        };
+
+        // Unary `+` keeps the planner from splitting the OR into rowid lookups,
+        // FTS5 refuses MATCH in that plan
+        builder.push(format!("(+{rowid} IN (SELECT id FROM items WHERE 1 = 1"));
+        push_bounds(builder, column);
+        builder.push(format!(
+            ") OR +{rowid} IN (SELECT -id FROM doc_entries WHERE 1 = 1"
+        ));
+        push_bounds(builder, "created_at");
+        builder.push("))");
     }
 
     async fn get_item_tags_map(&self, item_ids: &[i64]) -> Result<HashMap<i64, Vec<Tag>>> {
@@ -328,6 +1347,16 @@ impl SearchEngine {
         Ok(map)
     }
 
+    fn item_type_to_str(item_type: &ItemType) -> &'static str {
+        match item_type {
+            ItemType::Snippet => "snippet",
+            ItemType::Config => "config",
+            ItemType::Note => "note",
+            ItemType::Link => "link",
+            ItemType::Documentation => "documentation",
+        }
+    }
+
     fn parse_item_type(s: &str) -> Result<ItemType> {
         match s {
             "snippet" => Ok(ItemType::Snippet),
@@ -339,3 +1368,18 @@ impl SearchEngine {
         }
     }
 }
+
+/// Edit distance between two words, counted in characters
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,414 @@
+use crate::models::ItemType;
+use chrono::{Days, NaiveDate};
+use std::fmt;
+
+/// Search string split into full-text terms and structured filters, e.g.
+/// `tag:docker type:snippet lang:yaml updated:>2026-01-01 "compose file" -swarm`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedQuery {
+    pub terms: Vec<TextTerm>,
+    pub excluded_terms: Vec<TextTerm>,
+    pub filters: Vec<QueryFilter>,
+    pub excluded_filters: Vec<QueryFilter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextTerm {
+    pub text: String,
+    /// Quoted terms match as an exact phrase, bare words also match as a prefix
+    pub phrase: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryFilter {
+    Type(ItemType),
+    Tag(String),
+    /// Installed documentation by `name` or `display_name`, `doc:python@3.12` picks a
+    /// version; without one only the active version is searched
+    Doc {
+        name: String,
+        version: Option<String>,
+    },
+    /// `metadata.language` of an item
+    Lang(String),
+    Created(TimeRange),
+    Updated(TimeRange),
+}
+
+/// Half-open range of unix timestamps, `from <= t < to`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryParseError {
+    /// 1-based character column in the query string
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QueryParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid search query at column {}: {}",
+            self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for QueryParseError {}
+
+const FILTER_KEYS: &[&str] = &[
+    "type", "tag", "doc", "lang", "before", "after", "created", "updated",
+];
+const TYPE_NAMES: &str = "snippet, config, note, link, documentation";
+
+impl ParsedQuery {
+    pub fn parse(input: &str) -> Result<Self, QueryParseError> {
+        let chars: Vec<char> = input.chars().collect();
+        let mut parsed = ParsedQuery::default();
+        let mut pos = 0;
+
+        while pos < chars.len() {
+            if chars[pos].is_whitespace() {
+                pos += 1;
+                continue;
+            }
+
+            let start = pos;
+            let negated = chars[pos] == '-';
+            if negated {
+                pos += 1;
+                if pos >= chars.len() || chars[pos].is_whitespace() {
+                    return Err(error(start, "nothing to exclude after '-'"));
+                }
+            }
+
+            if chars[pos] == '"' {
+                let (text, end) = read_quoted(&chars, pos)?;
+                pos = end;
+                if let Some(term) = text_term(&text, true) {
+                    parsed.push_term(term, negated);
+                }
+                continue;
+            }
+
+            let word_end = chars[pos..]
+                .iter()
+                .position(|c| c.is_whitespace() || *c == '"')
+                .map_or(chars.len(), |offset| pos + offset);
+            let word: String = chars[pos..word_end].iter().collect();
+
+            let filter_key = word.split_once(':').and_then(|(key, _)| {
+                let key = key.to_lowercase();
+                FILTER_KEYS.contains(&key.as_str()).then_some(key)
+            });
+
+            let Some(key) = filter_key else {
+                if word_end < chars.len() && chars[word_end] == '"' {
+                    return Err(error(word_end, "unexpected '\"' inside a word"));
+                }
+                pos = word_end;
+                if let Some(term) = text_term(&word, false) {
+                    parsed.push_term(term, negated);
+                }
+                continue;
+            };
+
+            let value_start = pos + key.chars().count() + 1;
+            let value = if value_start < chars.len() && chars[value_start] == '"' {
+                let (value, end) = read_quoted(&chars, value_start)?;
+                pos = end;
+                value
+            } else {
+                if word_end < chars.len() && chars[word_end] == '"' {
+                    return Err(error(word_end, "unexpected '\"' inside a filter value"));
+                }
+                pos = word_end;
+                chars[value_start.min(word_end)..word_end].iter().collect()
+            };
+
+            let value = value.trim();
+            if value.is_empty() {
+                return Err(error(start, format!("filter '{key}:' needs a value")));
+            }
+
+            let filter =
+                parse_filter(&key, value).map_err(|message| error(value_start, message))?;
+            if negated {
+                parsed.excluded_filters.push(filter);
+            } else {
+                parsed.filters.push(filter);
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn push_term(&mut self, term: TextTerm, negated: bool) {
+        if negated {
+            self.excluded_terms.push(term);
+        } else {
+            self.terms.push(term);
+        }
+    }
+
+    /// FTS5 MATCH expression for the positive terms, `None` when only filters are given
+    pub fn fts_match(&self) -> Option<String> {
+        Self::join_terms(&self.terms, " AND ")
+    }
+
+    /// Substring MATCH for the trigram index: every positive term as a phrase, `None`
+    /// without terms or when one is shorter than a trigram
+    pub fn trigram_match(&self) -> Option<String> {
+        if self.terms.iter().any(|term| term.text.chars().count() < 3) {
+            return None;
+        }
+        let phrases: Vec<String> = self.terms.iter().map(TextTerm::to_phrase).collect();
+        (!phrases.is_empty()).then(|| phrases.join(" AND "))
+    }
+
+    /// FTS5 MATCH expression that any excluded term satisfies; a bare word excludes
+    /// that word, not every word it is a prefix of
+    pub fn excluded_match(&self) -> Option<String> {
+        let phrases: Vec<String> = self
+            .excluded_terms
+            .iter()
+            .map(TextTerm::to_phrase)
+            .collect();
+        (!phrases.is_empty()).then(|| phrases.join(" OR "))
+    }
+
+    /// Free-text part of the query, used for the query embedding
+    pub fn semantic_text(&self) -> String {
+        self.terms
+            .iter()
+            .map(|term| term.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Whether user items can satisfy the filters
+    pub fn allows_items(&self) -> bool {
+        let types_allow = self.positive_types().is_none_or(|types| {
+            types
+                .iter()
+                .any(|item_type| **item_type != ItemType::Documentation)
+        });
+        let docs_only = self
+            .filters
+            .iter()
+            .any(|filter| matches!(filter, QueryFilter::Doc { .. }));
+        types_allow && !docs_only
+    }
+
+    /// Whether documentation entries can satisfy the filters
+    pub fn allows_docs(&self) -> bool {
+        let types_allow = self
+            .positive_types()
+            .is_none_or(|types| types.contains(&&ItemType::Documentation));
+        let items_only = self
+            .filters
+            .iter()
+            .any(|filter| matches!(filter, QueryFilter::Tag(_) | QueryFilter::Lang(_)));
+        let docs_excluded = self
+            .excluded_filters
+            .contains(&QueryFilter::Type(ItemType::Documentation));
+        types_allow && !items_only && !docs_excluded
+    }
+
+    /// Whether a `doc:name@version` filter opts into inactive documentation versions
+    pub fn pins_doc_version(&self) -> bool {
+        self.filters.iter().any(|filter| {
+            matches!(
+                filter,
+                QueryFilter::Doc {
+                    version: Some(_),
+                    ..
+                }
+            )
+        })
+    }
+
+    /// Positive `type:` filters, combined with OR
+    pub fn positive_types(&self) -> Option<Vec<&ItemType>> {
+        let types: Vec<&ItemType> = self
+            .filters
+            .iter()
+            .filter_map(|filter| match filter {
+                QueryFilter::Type(item_type) => Some(item_type),
+                _ => None,
+            })
+            .collect();
+        (!types.is_empty()).then_some(types)
+    }
+
+    fn join_terms(terms: &[TextTerm], separator: &str) -> Option<String> {
+        if terms.is_empty() {
+            return None;
+        }
+
+        Some(
+            terms
+                .iter()
+                .map(TextTerm::to_fts)
+                .collect::<Vec<_>>()
+                .join(separator),
+        )
+    }
+}
+
+impl TextTerm {
+    pub fn to_fts(&self) -> String {
+        if self.phrase {
+            self.to_phrase()
+        } else {
+            format!("{}*", self.to_phrase())
+        }
+    }
+
+    fn to_phrase(&self) -> String {
+        format!("\"{}\"", self.text.replace('"', "\"\""))
+    }
+}
+
+fn text_term(text: &str, phrase: bool) -> Option<TextTerm> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| TextTerm {
+        text: text.to_string(),
+        phrase,
+    })
+}
+
+fn error(position: usize, message: impl Into<String>) -> QueryParseError {
+    QueryParseError {
+        column: position + 1,
+        message: message.into(),
+    }
+}
+
+/// Reads a `"..."` run starting at the opening quote, returns its text and the index
+/// right after the closing quote
+fn read_quoted(chars: &[char], open: usize) -> Result<(String, usize), QueryParseError> {
+    let close = chars[open + 1..]
+        .iter()
+        .position(|c| *c == '"')
+        .map(|offset| open + 1 + offset)
+        .ok_or_else(|| error(open, "unclosed quote"))?;
+
+    let end = close + 1;
+    if end < chars.len() && !chars[end].is_whitespace() {
+        return Err(error(end, "expected a space after the closing quote"));
+    }
+
+    Ok((chars[open + 1..close].iter().collect(), end))
+}
+
+fn parse_doc(value: &str) -> QueryFilter {
+    match value.rsplit_once('@') {
+        Some((name, version)) if !name.is_empty() && !version.is_empty() => QueryFilter::Doc {
+            name: name.to_string(),
+            version: Some(version.to_string()),
+        },
+        _ => QueryFilter::Doc {
+            name: value.to_string(),
+            version: None,
+        },
+    }
+}
+
+fn parse_filter(key: &str, value: &str) -> Result<QueryFilter, String> {
+    match key {
+        "type" => parse_type(value).map(QueryFilter::Type),
+        "tag" => Ok(QueryFilter::Tag(value.to_string())),
+        "doc" => Ok(parse_doc(value)),
+        "lang" => Ok(QueryFilter::Lang(value.to_lowercase())),
+        "before" => parse_date(value).map(|day| QueryFilter::Created(day.before())),
+        "after" => parse_date(value).map(|day| QueryFilter::Created(day.after())),
+        "created" => parse_date_comparison(value).map(QueryFilter::Created),
+        "updated" => parse_date_comparison(value).map(QueryFilter::Updated),
+        _ => Err(format!("unknown filter '{key}:'")),
+    }
+}
+
+fn parse_type(value: &str) -> Result<ItemType, String> {
+    match value.to_lowercase().as_str() {
+        "snippet" => Ok(ItemType::Snippet),
+        "config" => Ok(ItemType::Config),
+        "note" => Ok(ItemType::Note),
+        "link" => Ok(ItemType::Link),
+        "documentation" => Ok(ItemType::Documentation),
+        _ => Err(format!(
+            "unknown type '{value}', expected one of: {TYPE_NAMES}"
+        )),
+    }
+}
+
+/// One UTC calendar day as `[start, next day start)`
+struct Day {
+    start: i64,
+    end: i64,
+}
+
+impl Day {
+    fn before(&self) -> TimeRange {
+        TimeRange {
+            from: None,
+            to: Some(self.start),
+        }
+    }
+
+    fn after(&self) -> TimeRange {
+        TimeRange {
+            from: Some(self.end),
+            to: None,
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Result<Day, String> {
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{value}', expected YYYY-MM-DD"))?;
+    let next = date
+        .checked_add_days(Days::new(1))
+        .ok_or_else(|| format!("date '{value}' is out of range"))?;
+    let timestamp = |date: NaiveDate| {
+        date.and_hms_opt(0, 0, 0)
+            .map(|datetime| datetime.and_utc().timestamp())
+            .unwrap_or_default()
+    };
+
+    Ok(Day {
+        start: timestamp(date),
+        end: timestamp(next),
+    })
+}
+
+/// `>D`, `>=D`, `<D`, `<=D` or a bare `D` for the whole day
+fn parse_date_comparison(value: &str) -> Result<TimeRange, String> {
+    let (operator, date) = [">=", "<=", ">", "<"]
+        .iter()
+        .find_map(|operator| value.strip_prefix(operator).map(|date| (*operator, date)))
+        .unwrap_or(("", value));
+    let day = parse_date(date)?;
+
+    Ok(match operator {
+        ">" => day.after(),
+        ">=" => TimeRange {
+            from: Some(day.start),
+            to: None,
+        },
+        "<" => day.before(),
+        "<=" => TimeRange {
+            from: None,
+            to: Some(day.end),
+        },
+        _ => TimeRange {
+            from: Some(day.start),
+            to: Some(day.end),
+        },
+    })
+}
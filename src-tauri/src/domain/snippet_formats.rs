@@ -0,0 +1,607 @@
+//! Editor snippet files: VS Code `.code-snippets` JSON and JetBrains live template XML.
+//!
+//! Imported snippets keep the editor template in `metadata.snippet` (`prefix`, `body` in
+//! VS Code syntax, `scope`), the item content is the text the template inserts by default.
+
+use super::jsonc::strip_json_comments;
+use super::storage::Storage;
+use super::vault_transfer::ImportedItem;
+use crate::models::*;
+use anyhow::{Context, Result};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// JetBrains context option prefixes and the VS Code language ids they stand for
+const JETBRAINS_CONTEXTS: &[(&str, &str)] = &[
+    ("JAVA_SCRIPT", "javascript"),
+    ("JS_", "javascript"),
+    ("TYPE_SCRIPT", "typescript"),
+    ("TYPESCRIPT", "typescript"),
+    ("TS_", "typescript"),
+    ("JAVA", "java"),
+    ("KOTLIN", "kotlin"),
+    ("PYTHON", "python"),
+    ("GO", "go"),
+    ("SHELL", "shellscript"),
+    ("RUST", "rust"),
+];
+
+impl Storage {
+    pub async fn import_snippets(
+        &self,
+        path: &Path,
+        format: SnippetFormat,
+        conflict: ImportConflict,
+    ) -> Result<VaultImportSummary> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let items = match format {
+            SnippetFormat::VsCode => parse_vscode_snippets(&text),
+            SnippetFormat::JetBrains => parse_jetbrains_templates(&text),
+        }
+        .with_context(|| format!("Invalid snippets file {}", path.display()))?;
+
+        self.import_items(items, conflict).await
+    }
+}
+
+/// Entries of a VS Code snippets file, comments and trailing commas allowed
+pub(crate) fn parse_vscode_snippets(text: &str) -> Result<Vec<ImportedItem>> {
+    let root: Value = serde_json::from_str(&strip_json_comments(text))
+        .context("Snippets file is not valid JSON")?;
+    let Value::Object(entries) = root else {
+        anyhow::bail!("Snippets file must be a JSON object");
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let mut items = Vec::new();
+    for (name, entry) in entries {
+        let body = match entry.get("body") {
+            Some(Value::String(body)) => body.clone(),
+            Some(Value::Array(lines)) => lines
+                .iter()
+                .map(|line| line.as_str().unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => continue,
+        };
+        let prefix: Vec<Value> = match entry.get("prefix") {
+            Some(Value::String(prefix)) => vec![prefix.clone().into()],
+            Some(Value::Array(prefixes)) => prefixes
+                .iter()
+                .filter(|prefix| prefix.is_string())
+                .cloned()
+                .collect(),
+            _ => Vec::new(),
+        };
+        let scope = entry
+            .get("scope")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let description = entry
+            .get("description")
+            .and_then(Value::as_str)
+            .filter(|description| !description.is_empty())
+            .map(str::to_string);
+
+        items.push(snippet_item(
+            name,
+            description,
+            body,
+            prefix,
+            scope.split(',').map(str::trim),
+            now,
+        ));
+    }
+
+    Ok(items)
+}
+
+/// Templates of a live template set (`<templateSet><template .../></templateSet>`)
+pub(crate) fn parse_jetbrains_templates(text: &str) -> Result<Vec<ImportedItem>> {
+    let now = chrono::Utc::now().timestamp();
+    let mut items = Vec::new();
+    let mut current: Option<LiveTemplate> = None;
+    let mut in_context = false;
+
+    for tag in xml_tags(text)? {
+        match (tag.name.as_str(), tag.closing) {
+            ("template", false) => {
+                current = Some(LiveTemplate {
+                    name: tag.attribute("name").unwrap_or_default(),
+                    value: tag.attribute("value").unwrap_or_default(),
+                    description: tag.attribute("description"),
+                    defaults: HashMap::new(),
+                    contexts: Vec::new(),
+                });
+                if tag.self_closing {
+                    items.extend(current.take().map(|t| t.into_item(now)));
+                }
+            }
+            ("template", true) => items.extend(current.take().map(|t| t.into_item(now))),
+            ("variable", false) => {
+                if let (Some(template), Some(name)) = (current.as_mut(), tag.attribute("name")) {
+                    let default = tag.attribute("defaultValue").unwrap_or_default();
+                    template.defaults.insert(name, unquote_expression(&default));
+                }
+            }
+            ("context", closing) => in_context = !closing && !tag.self_closing,
+            ("option", false) if in_context => {
+                if let (Some(template), Some(name)) = (current.as_mut(), tag.attribute("name")) {
+                    if tag.attribute("value").as_deref() == Some("true") {
+                        template.contexts.push(name);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(items)
+}
+
+struct LiveTemplate {
+    name: String,
+    value: String,
+    description: Option<String>,
+    /// Default values by variable name
+    defaults: HashMap<String, String>,
+    contexts: Vec<String>,
+}
+
+impl LiveTemplate {
+    fn into_item(self, now: i64) -> ImportedItem {
+        let title = self
+            .description
+            .filter(|description| !description.is_empty())
+            .unwrap_or_else(|| self.name.clone());
+        let languages: Vec<String> = self
+            .contexts
+            .iter()
+            .filter_map(|context| jetbrains_language(context))
+            .collect();
+
+        snippet_item(
+            title,
+            None,
+            jetbrains_to_vscode_body(&self.value, &self.defaults),
+            vec![self.name.into()],
+            languages.iter().map(String::as_str),
+            now,
+        )
+    }
+}
+
+fn snippet_item<'a>(
+    title: String,
+    description: Option<String>,
+    body: String,
+    prefix: Vec<Value>,
+    languages: impl Iterator<Item = &'a str>,
+    now: i64,
+) -> ImportedItem {
+    let mut seen = HashSet::new();
+    let languages: Vec<&str> = languages
+        .filter(|language| !language.is_empty() && seen.insert(*language))
+        .collect();
+
+    let mut metadata = Map::new();
+    if let Some(language) = languages.first() {
+        metadata.insert("language".into(), (*language).into());
+    }
+    metadata.insert(
+        "snippet".into(),
+        json!({ "prefix": prefix, "body": body, "scope": languages.join(",") }),
+    );
+
+    ImportedItem {
+        item_type: ItemType::Snippet,
+        title,
+        description,
+        content: expand_placeholders(&body),
+        metadata: Some(Value::Object(metadata)),
+        tags: languages
+            .iter()
+            .map(|language| (language.to_string(), None))
+            .collect(),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// Writes the VS Code snippets file of `items`, returns the number of exported snippets
+pub fn write_vscode_snippets(path: &Path, items: &[ItemWithTags]) -> Result<usize> {
+    let file = vscode_snippets_file(items)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, file).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let count = items
+        .iter()
+        .filter(|item| item.item.item_type != ItemType::Documentation)
+        .count();
+    tracing::info!("✂️ Exported {} snippet(s) to {}", count, path.display());
+    Ok(count)
+}
+
+/// VS Code snippets file for the given items, documentation entries are left out.
+/// An item keeps its imported template while its content is what the template inserts,
+/// an edited item is exported as plain text.
+pub fn vscode_snippets_file(items: &[ItemWithTags]) -> Result<String> {
+    let mut snippets = Map::new();
+    let mut used_names = HashSet::new();
+
+    for item in items
+        .iter()
+        .map(|item| &item.item)
+        .filter(|item| item.item_type != ItemType::Documentation)
+    {
+        let metadata = item.metadata.as_ref();
+        let template = metadata.and_then(|metadata| metadata.get("snippet"));
+        let body = template
+            .and_then(|template| template.get("body"))
+            .and_then(Value::as_str)
+            .filter(|body| expand_placeholders(body) == item.content)
+            .map(str::to_string)
+            .unwrap_or_else(|| escape_body(&item.content));
+        let prefix = template
+            .and_then(|template| template.get("prefix"))
+            .filter(|prefix| prefix.as_array().is_some_and(|p| !p.is_empty()))
+            .cloned()
+            .unwrap_or_else(|| Value::String(default_prefix(&item.title)));
+        let scope = template
+            .and_then(|template| template.get("scope"))
+            .and_then(Value::as_str)
+            .filter(|scope| !scope.is_empty())
+            .or_else(|| {
+                metadata
+                    .and_then(|m| m.get("language"))
+                    .and_then(Value::as_str)
+            });
+
+        let mut snippet = Map::new();
+        snippet.insert("prefix".into(), prefix);
+        snippet.insert(
+            "body".into(),
+            Value::Array(body.split('\n').map(|line| line.into()).collect()),
+        );
+        if let Some(description) = item.description.as_deref().filter(|d| !d.is_empty()) {
+            snippet.insert("description".into(), description.into());
+        }
+        if let Some(scope) = scope {
+            snippet.insert("scope".into(), scope.into());
+        }
+
+        let mut name = item.title.clone();
+        let mut counter = 2;
+        while !used_names.insert(name.clone()) {
+            name = format!("{} ({counter})", item.title);
+            counter += 1;
+        }
+        snippets.insert(name, Value::Object(snippet));
+    }
+
+    Ok(serde_json::to_string_pretty(&Value::Object(snippets))?)
+}
+
+/// The text a VS Code snippet inserts with every placeholder left at its default:
+/// tabstops disappear, choices give their first option, mirrors repeat their placeholder.
+/// Variables (`$TM_FILENAME`) stay as written.
+pub fn expand_placeholders(body: &str) -> String {
+    let chars: Vec<char> = body.chars().collect();
+    let mut index = 0;
+    let mut values = HashMap::new();
+    expand_until(&chars, &mut index, false, &mut values)
+}
+
+fn expand_until(
+    chars: &[char],
+    index: &mut usize,
+    nested: bool,
+    values: &mut HashMap<String, String>,
+) -> String {
+    let mut output = String::new();
+    while let Some(&c) = chars.get(*index) {
+        match c {
+            '\\' if matches!(chars.get(*index + 1), Some('$' | '}' | '\\')) => {
+                output.push(chars[*index + 1]);
+                *index += 2;
+            }
+            '}' if nested => {
+                *index += 1;
+                return output;
+            }
+            '$' => {
+                *index += 1;
+                output.push_str(&expand_dollar(chars, index, values));
+            }
+            _ => {
+                output.push(c);
+                *index += 1;
+            }
+        }
+    }
+    output
+}
+
+/// Expands what follows a `$`
+fn expand_dollar(
+    chars: &[char],
+    index: &mut usize,
+    values: &mut HashMap<String, String>,
+) -> String {
+    let braced = chars.get(*index) == Some(&'{');
+    if braced {
+        *index += 1;
+    }
+    let start = *index;
+    while chars
+        .get(*index)
+        .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_')
+    {
+        *index += 1;
+    }
+    let name: String = chars[start..*index].iter().collect();
+    let is_tabstop = !name.is_empty() && name.chars().all(|c| c.is_ascii_digit());
+    let mirror = |values: &HashMap<String, String>| values.get(&name).cloned().unwrap_or_default();
+
+    if name.is_empty() {
+        return if braced { "${".into() } else { "$".into() };
+    }
+    if !braced {
+        return if is_tabstop {
+            mirror(values)
+        } else {
+            format!("${name}")
+        };
+    }
+
+    match chars.get(*index) {
+        Some('}') => {
+            *index += 1;
+            if is_tabstop {
+                mirror(values)
+            } else {
+                format!("${{{name}}}")
+            }
+        }
+        Some(':') => {
+            *index += 1;
+            let value = expand_until(chars, index, true, values);
+            if is_tabstop {
+                values.insert(name, value.clone());
+            }
+            value
+        }
+        Some('|') => {
+            *index += 1;
+            let mut first = String::new();
+            let mut in_first = true;
+            while let Some(&c) = chars.get(*index) {
+                *index += 1;
+                match c {
+                    '\\' => {
+                        if let Some(&escaped) = chars.get(*index) {
+                            *index += 1;
+                            if in_first {
+                                first.push(escaped);
+                            }
+                        }
+                    }
+                    ',' => in_first = false,
+                    '|' if chars.get(*index) == Some(&'}') => {
+                        *index += 1;
+                        break;
+                    }
+                    _ if in_first => first.push(c),
+                    _ => {}
+                }
+            }
+            values.insert(name, first.clone());
+            first
+        }
+        Some('/') => {
+            // Transforms only apply once the snippet is inserted
+            let mut depth = 0;
+            while let Some(&c) = chars.get(*index) {
+                *index += 1;
+                match c {
+                    '\\' => *index += 1,
+                    '{' => depth += 1,
+                    '}' if depth == 0 => break,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+            }
+            String::new()
+        }
+        _ => format!("${{{name}"),
+    }
+}
+
+/// Plain text as a VS Code snippet body that inserts it unchanged
+fn escape_body(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('$', "\\$")
+}
+
+/// `Restart deployment` -> `restart-deployment`
+fn default_prefix(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// `$NAME$` variables become numbered placeholders in order of appearance, repeated ones
+/// mirror the first; `$END$` is the final cursor position and `$$` a literal dollar
+fn jetbrains_to_vscode_body(value: &str, defaults: &HashMap<String, String>) -> String {
+    let mut output = String::new();
+    let mut numbers: HashMap<&str, usize> = HashMap::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find('$') {
+        output.push_str(&escape_body(&rest[..start]).replace('}', "\\}"));
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('$') else {
+            output.push_str("\\$");
+            rest = after;
+            continue;
+        };
+        let name = &after[..end];
+        if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            // Not a variable, a lone dollar sign
+            output.push_str("\\$");
+            rest = after;
+            continue;
+        }
+        rest = &after[end + 1..];
+
+        match name {
+            "" => output.push_str("\\$"),
+            "END" => output.push_str("$0"),
+            "SELECTION" => output.push_str("$TM_SELECTED_TEXT"),
+            name if numbers.contains_key(name) => {
+                output.push_str(&format!("${}", numbers[name]));
+            }
+            name => {
+                let number = numbers.len() + 1;
+                numbers.insert(name, number);
+                let default = defaults
+                    .get(name)
+                    .filter(|default| !default.is_empty())
+                    .map(String::as_str)
+                    .unwrap_or(name);
+                output.push_str(&format!(
+                    "${{{number}:{}}}",
+                    escape_body(default).replace('}', "\\}")
+                ));
+            }
+        }
+    }
+    output.push_str(&escape_body(rest).replace('}', "\\}"));
+    output
+}
+
+/// Default values are expressions, a quoted string is the only kind with a fixed value
+fn unquote_expression(expression: &str) -> String {
+    expression
+        .trim()
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn jetbrains_language(context: &str) -> Option<String> {
+    let context = context.to_uppercase();
+    if context == "OTHER" {
+        return None;
+    }
+    JETBRAINS_CONTEXTS
+        .iter()
+        .find(|(prefix, _)| context.starts_with(prefix))
+        .map(|(_, language)| language.to_string())
+        // SQL, HTML, CSS, PHP, RUBY, XML... are named after the language
+        .or_else(|| context.split('_').next().map(str::to_lowercase))
+}
+
+struct XmlTag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attributes: Vec<(String, String)>,
+}
+
+impl XmlTag {
+    fn attribute(&self, name: &str) -> Option<String> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+/// Element tags of an XML document in order, text content is not needed for templates
+fn xml_tags(text: &str) -> Result<Vec<XmlTag>> {
+    let tag_pattern = regex::Regex::new(
+        r#"<(/?)([\w:.-]+)((?:\s+[\w:.-]+\s*=\s*(?:"[^"]*"|'[^']*'))*)\s*(/?)>"#,
+    )?;
+    let attribute_pattern = regex::Regex::new(r#"([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#)?;
+
+    let mut without_comments = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("<!--") {
+        without_comments.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find("-->")
+            .map(|end| &rest[start + end + 3..])
+            .unwrap_or("");
+    }
+    without_comments.push_str(rest);
+
+    let tags: Vec<XmlTag> = tag_pattern
+        .captures_iter(&without_comments)
+        .map(|captures| XmlTag {
+            name: captures[2].to_string(),
+            closing: &captures[1] == "/",
+            self_closing: &captures[4] == "/",
+            attributes: attribute_pattern
+                .captures_iter(&captures[3])
+                .map(|attribute| {
+                    let value = attribute
+                        .get(2)
+                        .or_else(|| attribute.get(3))
+                        .map_or("", |value| value.as_str());
+                    (attribute[1].to_string(), unescape_xml(value))
+                })
+                .collect(),
+        })
+        .collect();
+
+    if !tags.iter().any(|tag| tag.name == "templateSet") {
+        anyhow::bail!("Not a live template file: no <templateSet> element");
+    }
+    Ok(tags)
+}
+
+fn unescape_xml(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let decoded = after.find(';').and_then(|end| {
+            let entity = &after[..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            }?;
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, length)) => {
+                output.push(c);
+                rest = &after[length..];
+            }
+            None => {
+                output.push('&');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
@@ -1,13 +1,135 @@
+use super::embeddings::{item_embedding_text, vector_to_blob, Embedder, HashedNgramEmbedder};
+use super::text_diff::unified_diff;
+use crate::models::config::HistoryConfig;
 use crate::models::*;
 use anyhow::{Context, Result};
-use sqlx::{sqlite::SqlitePool, Executor, Pool, Row, Sqlite};
+use sha2::{Digest, Sha256};
+use sqlx::{sqlite::SqlitePool, Connection, Executor, Pool, Row, Sqlite, SqliteConnection};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 pub struct Storage {
     pub pool: Pool<Sqlite>,
+    embedder: Arc<dyn Embedder>,
+    history: HistoryConfig,
 }
 
-const TAG_COLOR_CODE_COUNT: i64 = 12;
+pub(crate) const TAG_COLOR_CODE_COUNT: i64 = 12;
+
+/// A schema migration, applied once and recorded in `schema_migrations`
+struct Migration {
+    version: i64,
+    name: &'static str,
+    /// Empty when the migration is done entirely in `run_migration_code`
+    sql: &'static str,
+    /// Runs with foreign keys off
+    rebuilds_tables: bool,
+}
+
+impl Migration {
+    const fn sql(version: i64, name: &'static str, sql: &'static str) -> Self {
+        Self {
+            version,
+            name,
+            sql,
+            rebuilds_tables: false,
+        }
+    }
+
+    /// SHA-256 of the name and SQL, a changed migration file no longer matches the record
+    fn checksum(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.name.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.sql.as_bytes());
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+/// Applied in order, versions never change once released
+const MIGRATIONS: &[Migration] = &[
+    Migration::sql(
+        1,
+        "initial_schema",
+        include_str!("../../migrations/001_initial_schema.sql"),
+    ),
+    Migration::sql(
+        2,
+        "documentation_system",
+        include_str!("../../migrations/002_documentation_system.sql"),
+    ),
+    Migration::sql(
+        3,
+        "fix_doc_triggers",
+        include_str!("../../migrations/003_fix_doc_triggers.sql"),
+    ),
+    Migration::sql(
+        4,
+        "fix_fts_for_docs",
+        include_str!("../../migrations/004_fix_fts_for_docs.sql"),
+    ),
+    Migration::sql(5, "tag_colors", ""),
+    Migration::sql(6, "embedding_model", ""),
+    Migration::sql(
+        7,
+        "index_item_tags",
+        include_str!("../../migrations/007_index_item_tags.sql"),
+    ),
+    Migration::sql(
+        8,
+        "doc_entry_hashes",
+        include_str!("../../migrations/008_doc_entry_hashes.sql"),
+    ),
+    Migration {
+        rebuilds_tables: true,
+        ..Migration::sql(
+            9,
+            "doc_versions",
+            include_str!("../../migrations/009_doc_versions.sql"),
+        )
+    },
+    Migration::sql(
+        10,
+        "doc_install_checkpoints",
+        include_str!("../../migrations/010_doc_install_checkpoints.sql"),
+    ),
+    Migration::sql(
+        11,
+        "doc_entry_scope_index",
+        include_str!("../../migrations/011_doc_entry_scope_index.sql"),
+    ),
+    Migration::sql(
+        12,
+        "split_fts",
+        include_str!("../../migrations/012_split_fts.sql"),
+    ),
+    Migration::sql(
+        13,
+        "fuzzy_search",
+        include_str!("../../migrations/013_fuzzy_search.sql"),
+    ),
+    Migration::sql(
+        14,
+        "item_revisions",
+        include_str!("../../migrations/014_item_revisions.sql"),
+    ),
+    Migration::sql(15, "soft_delete", ""),
+    Migration::sql(
+        16,
+        "markdown_sync",
+        include_str!("../../migrations/016_markdown_sync.sql"),
+    ),
+    Migration::sql(
+        17,
+        "linked_configs",
+        include_str!("../../migrations/017_linked_configs.sql"),
+    ),
+];
 
 impl Storage {
     fn item_type_to_str(item_type: ItemType) -> &'static str {
@@ -39,44 +161,167 @@ impl Storage {
 
         Self::run_migrations(&pool).await?;
 
-        Ok(Self { pool })
+        let storage = Self {
+            pool,
+            embedder: Arc::new(HashedNgramEmbedder::default()),
+            history: HistoryConfig::default(),
+        };
+        storage.backfill_embeddings().await?;
+
+        Ok(storage)
+    }
+
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    pub fn embedder(&self) -> Arc<dyn Embedder> {
+        self.embedder.clone()
+    }
+
+    pub fn with_history(mut self, history: HistoryConfig) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Retention applies from the next update on; existing revisions are pruned lazily
+    pub fn set_history(&mut self, history: HistoryConfig) {
+        self.history = history;
     }
 
     async fn run_migrations(pool: &Pool<Sqlite>) -> Result<()> {
         tracing::info!("🔄 Running database migrations...");
 
-        let migration_001 = include_str!("../../migrations/001_initial_schema.sql");
-        let migration_002 = include_str!("../../migrations/002_documentation_system.sql");
-        let migration_003 = include_str!("../../migrations/003_fix_doc_triggers.sql");
-        let migration_004 = include_str!("../../migrations/004_fix_fts_for_docs.sql");
-
-        pool.execute(migration_001)
+        // One connection for the whole run: the foreign_keys pragma is per connection
+        let mut conn = pool
+            .acquire()
             .await
-            .context("Failed to run migration 001")?;
+            .context("Failed to acquire a connection for migrations")?;
 
-        pool.execute(migration_002)
-            .await
-            .context("Failed to run migration 002")?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+                 version INTEGER PRIMARY KEY,
+                 name TEXT NOT NULL,
+                 checksum TEXT NOT NULL,
+                 applied_at INTEGER NOT NULL
+             )",
+        )
+        .execute(&mut *conn)
+        .await
+        .context("Failed to create schema_migrations table")?;
 
-        pool.execute(migration_003)
-            .await
-            .context("Failed to run migration 003")?;
+        let applied: HashMap<i64, String> =
+            sqlx::query("SELECT version, checksum FROM schema_migrations")
+                .fetch_all(&mut *conn)
+                .await
+                .context("Failed to read applied migrations")?
+                .iter()
+                .map(|row| (row.get("version"), row.get("checksum")))
+                .collect();
+
+        let latest = MIGRATIONS.last().map_or(0, |migration| migration.version);
+        if let Some(newest) = applied.keys().copied().filter(|v| *v > latest).max() {
+            anyhow::bail!(
+                "Database schema version {} is newer than this Dev Vault build supports ({}), \
+                 update the app to open it",
+                newest,
+                latest
+            );
+        }
 
-        pool.execute(migration_004)
-            .await
-            .context("Failed to run migration 004")?;
+        let mut applied_now = 0;
+        for migration in MIGRATIONS {
+            let checksum = migration.checksum();
+            match applied.get(&migration.version) {
+                Some(recorded) if *recorded == checksum => continue,
+                Some(_) => anyhow::bail!(
+                    "Migration {:03} ({}) was changed after it had been applied",
+                    migration.version,
+                    migration.name
+                ),
+                None => {}
+            }
 
-        Self::run_migration_005_tag_colors(pool).await?;
+            Self::apply_migration(&mut conn, migration, &checksum)
+                .await
+                .with_context(|| format!("Failed to run migration {:03}", migration.version))?;
+            applied_now += 1;
+        }
 
-        tracing::info!("✅ All migrations completed successfully");
+        tracing::info!(
+            "✅ Migrations completed, {} applied, schema version {}",
+            applied_now,
+            latest
+        );
         Ok(())
     }
 
-    async fn run_migration_005_tag_colors(pool: &Pool<Sqlite>) -> Result<()> {
+    /// Applies a migration and records it in one transaction. A database created before
+    /// schema_migrations existed goes through every migration once, they are all written
+    /// to be safe on a schema that already has their changes.
+    async fn apply_migration(
+        conn: &mut SqliteConnection,
+        migration: &Migration,
+        checksum: &str,
+    ) -> Result<()> {
+        // Rebuilding a table must not cascade into the rows referencing it, and the pragma
+        // cannot be changed inside a transaction
+        if migration.rebuilds_tables {
+            sqlx::query("PRAGMA foreign_keys=OFF")
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        let result = async {
+            let mut tx = conn.begin().await?;
+            if Self::run_migration_code(&mut tx, migration.version).await?
+                && !migration.sql.is_empty()
+            {
+                tx.execute(migration.sql).await?;
+            }
+            sqlx::query(
+                "INSERT INTO schema_migrations (version, name, checksum, applied_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(checksum)
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            anyhow::Ok(())
+        }
+        .await;
+
+        if migration.rebuilds_tables {
+            sqlx::query("PRAGMA foreign_keys=ON")
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        result
+    }
+
+    /// Rust part of a migration, runs before its SQL. `false` skips the SQL.
+    async fn run_migration_code(conn: &mut SqliteConnection, version: i64) -> Result<bool> {
+        match version {
+            5 => Self::run_migration_005_tag_colors(conn).await?,
+            6 => Self::run_migration_006_embedding_model(conn).await?,
+            8 => Self::run_migration_008_doc_entry_hash(conn).await?,
+            9 => return Self::needs_migration_009_doc_versions(conn).await,
+            15 => Self::run_migration_015_soft_delete(conn).await?,
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    async fn run_migration_005_tag_colors(conn: &mut SqliteConnection) -> Result<()> {
         let has_color_code_column = sqlx::query(
             "SELECT 1 FROM pragma_table_info('tags') WHERE name = 'color_code' LIMIT 1",
         )
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await
         .context("Failed to inspect tags schema")?
         .is_some();
@@ -86,7 +331,7 @@ impl Storage {
         }
 
         sqlx::query("ALTER TABLE tags ADD COLUMN color_code INTEGER NOT NULL DEFAULT 0")
-            .execute(pool)
+            .execute(&mut *conn)
             .await
             .context("Failed to add tags.color_code column")?;
 
@@ -95,14 +340,181 @@ impl Storage {
              SET color_code = CAST((random() & 9223372036854775807) % ?1 AS INTEGER)",
         )
         .bind(TAG_COLOR_CODE_COUNT)
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .context("Failed to assign random tag colors for existing tags")?;
 
         Ok(())
     }
 
-    fn spawn_cleanup_unused_tags(&self) {
+    /// `deleted_at` on items and documentations: deleting moves a row to the trash,
+    /// it is removed for good once the trash retention runs out
+    async fn run_migration_015_soft_delete(conn: &mut SqliteConnection) -> Result<()> {
+        for table in ["items", "documentations"] {
+            let has_deleted_at_column = sqlx::query(&format!(
+                "SELECT 1 FROM pragma_table_info('{table}') WHERE name = 'deleted_at' LIMIT 1"
+            ))
+            .fetch_optional(&mut *conn)
+            .await
+            .with_context(|| format!("Failed to inspect {table} schema"))?
+            .is_some();
+
+            if !has_deleted_at_column {
+                sqlx::query(&format!(
+                    "ALTER TABLE {table} ADD COLUMN deleted_at INTEGER"
+                ))
+                .execute(&mut *conn)
+                .await
+                .with_context(|| format!("Failed to add {table}.deleted_at column"))?;
+            }
+
+            sqlx::query(&format!(
+                "CREATE INDEX IF NOT EXISTS idx_{table}_deleted_at ON {table}(deleted_at)
+                 WHERE deleted_at IS NOT NULL"
+            ))
+            .execute(&mut *conn)
+            .await
+            .with_context(|| format!("Failed to index {table}.deleted_at"))?;
+        }
+
+        Ok(())
+    }
+
+    async fn run_migration_006_embedding_model(conn: &mut SqliteConnection) -> Result<()> {
+        let has_model_column = sqlx::query(
+            "SELECT 1 FROM pragma_table_info('embeddings') WHERE name = 'model' LIMIT 1",
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .context("Failed to inspect embeddings schema")?
+        .is_some();
+
+        if has_model_column {
+            return Ok(());
+        }
+
+        sqlx::query("ALTER TABLE embeddings ADD COLUMN model TEXT NOT NULL DEFAULT ''")
+            .execute(&mut *conn)
+            .await
+            .context("Failed to add embeddings.model column")?;
+
+        Ok(())
+    }
+
+    async fn run_migration_008_doc_entry_hash(conn: &mut SqliteConnection) -> Result<()> {
+        let has_hash_column = sqlx::query(
+            "SELECT 1 FROM pragma_table_info('doc_entries') WHERE name = 'content_hash' LIMIT 1",
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .context("Failed to inspect doc_entries schema")?
+        .is_some();
+
+        if has_hash_column {
+            return Ok(());
+        }
+
+        // Empty hash means "not computed yet", the next doc update fills it in
+        sqlx::query("ALTER TABLE doc_entries ADD COLUMN content_hash TEXT NOT NULL DEFAULT ''")
+            .execute(&mut *conn)
+            .await
+            .context("Failed to add doc_entries.content_hash column")?;
+
+        Ok(())
+    }
+
+    /// The SQL of 009 rebuilds documentations with `is_active`
+    async fn needs_migration_009_doc_versions(conn: &mut SqliteConnection) -> Result<bool> {
+        let has_active_column = sqlx::query(
+            "SELECT 1 FROM pragma_table_info('documentations') WHERE name = 'is_active' LIMIT 1",
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .context("Failed to inspect documentations schema")?
+        .is_some();
+
+        Ok(!has_active_column)
+    }
+
+    /// Embeds items that have no vector yet or were embedded by a different model
+    pub async fn backfill_embeddings(&self) -> Result<usize> {
+        let rows = sqlx::query(
+            "SELECT i.id, i.title, i.description, i.content
+             FROM items i
+             LEFT JOIN embeddings e ON e.item_id = i.id
+             WHERE e.item_id IS NULL OR e.model != ?1",
+        )
+        .bind(self.embedder.model())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to find items without embeddings")?;
+
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        tracing::info!("🧮 Computing embeddings for {} item(s)", rows.len());
+        for row in &rows {
+            let description: Option<String> = row.get("description");
+            self.upsert_embedding(
+                row.get("id"),
+                row.get("title"),
+                description.as_deref(),
+                row.get("content"),
+            )
+            .await?;
+        }
+
+        Ok(rows.len())
+    }
+
+    async fn upsert_embedding(
+        &self,
+        item_id: i64,
+        title: &str,
+        description: Option<&str>,
+        content: &str,
+    ) -> Result<()> {
+        let vector = self
+            .embedder
+            .embed(&item_embedding_text(title, description, content));
+
+        sqlx::query(
+            "INSERT INTO embeddings (item_id, vector, model) VALUES (?1, ?2, ?3)
+             ON CONFLICT(item_id) DO UPDATE SET vector = excluded.vector, model = excluded.model",
+        )
+        .bind(item_id)
+        .bind(vector_to_blob(&vector))
+        .bind(self.embedder.model())
+        .execute(&self.pool)
+        .await
+        .context("Failed to store item embedding")?;
+
+        Ok(())
+    }
+
+    async fn refresh_embedding(&self, item_id: i64) -> Result<()> {
+        let row = sqlx::query("SELECT title, description, content FROM items WHERE id = ?1")
+            .bind(item_id)
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to load item for embedding")?;
+
+        if let Some(row) = row {
+            let description: Option<String> = row.get("description");
+            self.upsert_embedding(
+                item_id,
+                row.get("title"),
+                description.as_deref(),
+                row.get("content"),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn spawn_cleanup_unused_tags(&self) {
         let pool = self.pool.clone();
         tokio::spawn(async move {
             let _ = sqlx::query(
@@ -141,6 +553,14 @@ impl Storage {
 
         let item_id = result.last_insert_rowid();
 
+        self.upsert_embedding(
+            item_id,
+            &dto.title,
+            dto.description.as_deref(),
+            &dto.content,
+        )
+        .await?;
+
         if let Some(tag_ids) = dto.tag_ids {
             for tag_id in tag_ids {
                 sqlx::query("INSERT INTO item_tags (item_id, tag_id) VALUES (?1, ?2)")
@@ -158,7 +578,7 @@ impl Storage {
     pub async fn get_item(&self, id: i64) -> Result<Option<ItemWithTags>> {
         let row = sqlx::query(
             "SELECT id, type, title, description, content, created_at, updated_at, metadata
-             FROM items WHERE id = ?1",
+             FROM items WHERE id = ?1 AND deleted_at IS NULL",
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -193,21 +613,46 @@ impl Storage {
     pub async fn update_item(&self, dto: UpdateItemDto) -> Result<bool> {
         let now = chrono::Utc::now().timestamp();
 
-        let existing = sqlx::query("SELECT id FROM items WHERE id = ?1")
-            .bind(dto.id)
-            .fetch_optional(&self.pool)
-            .await
-            .context("Failed to check item existence")?;
+        let existing = sqlx::query(
+            "SELECT title, description, content, metadata FROM items
+                 WHERE id = ?1 AND deleted_at IS NULL",
+        )
+        .bind(dto.id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to check item existence")?;
 
-        if existing.is_none() {
+        let Some(existing) = existing else {
             return Ok(false);
+        };
+
+        let text_changed =
+            dto.title.is_some() || dto.description.is_some() || dto.content.is_some();
+
+        // Saving the same text again (autosave) leaves no revision behind
+        let old_metadata = existing
+            .get::<Option<String>, _>("metadata")
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok());
+        let revised = dto
+            .title
+            .as_ref()
+            .is_some_and(|title| *title != existing.get::<String, _>("title"))
+            || dto.description.as_ref().is_some_and(|description| {
+                Some(description) != existing.get::<Option<String>, _>("description").as_ref()
+            })
+            || dto
+                .content
+                .as_ref()
+                .is_some_and(|content| *content != existing.get::<String, _>("content"))
+            || dto
+                .metadata
+                .as_ref()
+                .is_some_and(|metadata| Some(metadata) != old_metadata.as_ref());
+        if revised {
+            self.record_revision(dto.id).await?;
         }
 
-        if dto.title.is_some()
-            || dto.description.is_some()
-            || dto.content.is_some()
-            || dto.metadata.is_some()
-        {
+        if text_changed || dto.metadata.is_some() {
             let mut parts = vec![];
             if dto.title.is_some() {
                 parts.push("t");
@@ -377,6 +822,10 @@ impl Storage {
             }
         }
 
+        if text_changed {
+            self.refresh_embedding(dto.id).await?;
+        }
+
         if let Some(item_type) = dto.item_type {
             sqlx::query("UPDATE items SET type = ?1, updated_at = ?2 WHERE id = ?3")
                 .bind(Self::item_type_to_str(item_type))
@@ -408,12 +857,233 @@ impl Storage {
         Ok(true)
     }
 
+    /// Copies the item's current text fields into `item_revisions` and applies retention
+    async fn record_revision(&self, item_id: i64) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO item_revisions
+                 (item_id, title, description, content, metadata, saved_at, created_at)
+             SELECT id, title, description, content, metadata, updated_at, ?1
+             FROM items WHERE id = ?2",
+        )
+        .bind(now)
+        .bind(item_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to save item revision")?;
+
+        self.prune_revisions(item_id).await
+    }
+
+    async fn prune_revisions(&self, item_id: i64) -> Result<()> {
+        if self.history.max_revisions_per_item > 0 {
+            sqlx::query(
+                "DELETE FROM item_revisions WHERE item_id = ?1 AND id NOT IN (
+                     SELECT id FROM item_revisions WHERE item_id = ?1
+                     ORDER BY id DESC LIMIT ?2
+                 )",
+            )
+            .bind(item_id)
+            .bind(self.history.max_revisions_per_item as i64)
+            .execute(&self.pool)
+            .await
+            .context("Failed to prune item revisions")?;
+        }
+
+        if self.history.max_age_days > 0 {
+            let cutoff = chrono::Utc::now().timestamp() - self.history.max_age_days as i64 * 86_400;
+            sqlx::query("DELETE FROM item_revisions WHERE item_id = ?1 AND created_at < ?2")
+                .bind(item_id)
+                .bind(cutoff)
+                .execute(&self.pool)
+                .await
+                .context("Failed to prune item revisions")?;
+        }
+
+        Ok(())
+    }
+
+    /// Revisions of an item, newest first
+    pub async fn list_item_revisions(&self, item_id: i64) -> Result<Vec<ItemRevision>> {
+        let rows = sqlx::query(
+            "SELECT id, item_id, title, description, content, metadata, saved_at, created_at
+             FROM item_revisions WHERE item_id = ?1 ORDER BY id DESC",
+        )
+        .bind(item_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list item revisions")?;
+
+        Ok(rows.iter().map(Self::row_to_revision).collect())
+    }
+
+    pub async fn get_item_revision(
+        &self,
+        item_id: i64,
+        revision_id: i64,
+    ) -> Result<Option<ItemRevision>> {
+        let row = sqlx::query(
+            "SELECT id, item_id, title, description, content, metadata, saved_at, created_at
+             FROM item_revisions WHERE id = ?1 AND item_id = ?2",
+        )
+        .bind(revision_id)
+        .bind(item_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to get item revision")?;
+
+        Ok(row.as_ref().map(Self::row_to_revision))
+    }
+
+    fn row_to_revision(row: &sqlx::sqlite::SqliteRow) -> ItemRevision {
+        ItemRevision {
+            id: row.get("id"),
+            item_id: row.get("item_id"),
+            title: row.get("title"),
+            description: row.get("description"),
+            content: row.get("content"),
+            metadata: row
+                .get::<Option<String>, _>("metadata")
+                .and_then(|s| serde_json::from_str(&s).ok()),
+            saved_at: row.get("saved_at"),
+            created_at: row.get("created_at"),
+        }
+    }
+
+    /// Unified diff of the content of two versions of an item; `None` stands for the
+    /// current version. Empty when the contents match.
+    pub async fn diff_item_revisions(
+        &self,
+        item_id: i64,
+        from_revision: Option<i64>,
+        to_revision: Option<i64>,
+    ) -> Result<String> {
+        let (from_label, from_content) = self.revision_text(item_id, from_revision).await?;
+        let (to_label, to_content) = self.revision_text(item_id, to_revision).await?;
+        Ok(unified_diff(
+            &from_content,
+            &to_content,
+            &from_label,
+            &to_label,
+        ))
+    }
+
+    /// (diff label, content) of a revision or of the current item
+    async fn revision_text(
+        &self,
+        item_id: i64,
+        revision_id: Option<i64>,
+    ) -> Result<(String, String)> {
+        match revision_id {
+            Some(revision_id) => {
+                let revision = self
+                    .get_item_revision(item_id, revision_id)
+                    .await?
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Revision {} of item {} not found", revision_id, item_id)
+                    })?;
+                Ok((
+                    format!("{} (revision {})", revision.title, revision.id),
+                    revision.content,
+                ))
+            }
+            None => {
+                let item = self
+                    .get_item(item_id)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("Item {} not found", item_id))?;
+                Ok((format!("{} (current)", item.item.title), item.item.content))
+            }
+        }
+    }
+
+    /// Brings back the text fields of a revision. The version being replaced becomes a
+    /// revision itself, so a restore can be undone.
+    pub async fn restore_item_revision(&self, item_id: i64, revision_id: i64) -> Result<bool> {
+        let Some(revision) = self.get_item_revision(item_id, revision_id).await? else {
+            return Ok(false);
+        };
+
+        self.record_revision(item_id).await?;
+        let metadata_json = revision
+            .metadata
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        sqlx::query(
+            "UPDATE items SET title = ?1, description = ?2, content = ?3, metadata = ?4, updated_at = ?5
+             WHERE id = ?6",
+        )
+        .bind(&revision.title)
+        .bind(&revision.description)
+        .bind(&revision.content)
+        .bind(metadata_json)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(item_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to restore item revision")?;
+
+        self.refresh_embedding(item_id).await?;
+        Ok(true)
+    }
+
+    /// Moves the item to the trash; it keeps its tags and revisions until purged
     pub async fn delete_item(&self, id: i64) -> Result<bool> {
-        let result = sqlx::query("DELETE FROM items WHERE id = ?1")
+        let result =
+            sqlx::query("UPDATE items SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL")
+                .bind(chrono::Utc::now().timestamp())
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .context("Failed to delete item")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn restore_item(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE items SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to restore item")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Items in the trash, most recently deleted first
+    pub async fn list_deleted_items(&self) -> Result<Vec<TrashEntry>> {
+        let rows = sqlx::query(
+            "SELECT id, type, title, deleted_at FROM items
+             WHERE deleted_at IS NOT NULL
+             ORDER BY deleted_at DESC, id DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list deleted items")?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(TrashEntry {
+                    kind: TrashKind::Item,
+                    id: row.get("id"),
+                    title: row.get("title"),
+                    item_type: Some(Self::parse_item_type(row.get("type"))?),
+                    deleted_at: row.get("deleted_at"),
+                })
+            })
+            .collect()
+    }
+
+    /// Removes a trashed item for good, with its tags, vector and revisions
+    pub async fn purge_item(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM items WHERE id = ?1 AND deleted_at IS NOT NULL")
             .bind(id)
             .execute(&self.pool)
             .await
-            .context("Failed to delete item")?;
+            .context("Failed to purge item")?;
 
         if result.rows_affected() > 0 {
             self.spawn_cleanup_unused_tags();
@@ -422,6 +1092,23 @@ impl Storage {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Purges items trashed before `deleted_before`, or every trashed item without it
+    pub async fn purge_deleted_items(&self, deleted_before: Option<i64>) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM items WHERE deleted_at IS NOT NULL AND (?1 IS NULL OR deleted_at < ?1)",
+        )
+        .bind(deleted_before)
+        .execute(&self.pool)
+        .await
+        .context("Failed to purge deleted items")?;
+
+        if result.rows_affected() > 0 {
+            self.spawn_cleanup_unused_tags();
+        }
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn list_items(
         &self,
         limit: Option<i64>,
@@ -434,18 +1121,16 @@ impl Storage {
 
         let mut sql = String::from(
             "SELECT id, type, title, description, content, created_at, updated_at, metadata
-             FROM items",
+             FROM items WHERE deleted_at IS NULL",
         );
-        let mut has_clause = false;
 
         if item_type.is_some() {
-            sql.push_str(" WHERE type = ?");
-            has_clause = true;
+            sql.push_str(" AND type = ?");
         }
 
         if let Some(ref tag_ids) = tag_ids {
             if !tag_ids.is_empty() {
-                sql.push_str(if has_clause { " AND " } else { " WHERE " });
+                sql.push_str(" AND ");
                 sql.push_str("EXISTS (SELECT 1 FROM item_tags it WHERE it.item_id = items.id AND it.tag_id IN (");
 
                 for index in 0..tag_ids.len() {
@@ -507,10 +1192,12 @@ impl Storage {
     }
 
     pub async fn list_item_type_counts(&self) -> Result<Vec<ItemTypeCount>> {
-        let rows = sqlx::query("SELECT type, COUNT(*) as count FROM items GROUP BY type")
-            .fetch_all(&self.pool)
-            .await
-            .context("Failed to list item counts by type")?;
+        let rows = sqlx::query(
+            "SELECT type, COUNT(*) as count FROM items WHERE deleted_at IS NULL GROUP BY type",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list item counts by type")?;
 
         let mut counts = Vec::new();
         for row in rows {
@@ -543,6 +1230,7 @@ impl Storage {
             "SELECT t.id, t.name, t.color_code, COUNT(it.item_id) as usage_count
              FROM tags t
              LEFT JOIN item_tags it ON t.id = it.tag_id
+                 AND it.item_id NOT IN (SELECT id FROM items WHERE deleted_at IS NOT NULL)
              WHERE t.name = ?1
              GROUP BY t.id, t.name, t.color_code",
         )
@@ -564,6 +1252,7 @@ impl Storage {
             "SELECT t.id, t.name, t.color_code, COUNT(it.item_id) as usage_count
              FROM tags t
              LEFT JOIN item_tags it ON t.id = it.tag_id
+                 AND it.item_id NOT IN (SELECT id FROM items WHERE deleted_at IS NOT NULL)
              GROUP BY t.id, t.name, t.color_code
              ORDER BY usage_count DESC, t.name ASC",
         )
@@ -588,6 +1277,7 @@ impl Storage {
             "SELECT t.id, t.name, t.color_code, COUNT(it.item_id) as usage_count
              FROM tags t
              LEFT JOIN item_tags it ON t.id = it.tag_id
+                 AND it.item_id NOT IN (SELECT id FROM items WHERE deleted_at IS NOT NULL)
              WHERE t.name LIKE ?1
              GROUP BY t.id, t.name, t.color_code
              ORDER BY usage_count DESC, t.name ASC
@@ -618,6 +1308,7 @@ impl Storage {
              INNER JOIN (
                  SELECT tag_id, COUNT(*) as usage_count
                  FROM item_tags
+                 WHERE item_id NOT IN (SELECT id FROM items WHERE deleted_at IS NOT NULL)
                  GROUP BY tag_id
              ) stats ON stats.tag_id = t.id
              WHERE it.item_id = ?1
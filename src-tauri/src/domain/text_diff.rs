@@ -0,0 +1,181 @@
+//! Line-based unified diff (Myers algorithm) for item revisions
+
+/// Unchanged lines shown around every change
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Keep,
+    Delete,
+    Insert,
+}
+
+/// Unified diff of `old` and `new` with `---`/`+++` headers, empty when the texts match
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let edits = shortest_edit(&old_lines, &new_lines);
+    if edits.iter().all(|edit| *edit == Edit::Keep) {
+        return String::new();
+    }
+
+    // (edit, old index, new index) for every step of the script
+    let mut steps = Vec::with_capacity(edits.len());
+    let (mut old_index, mut new_index) = (0, 0);
+    for edit in edits {
+        steps.push((edit, old_index, new_index));
+        match edit {
+            Edit::Keep => {
+                old_index += 1;
+                new_index += 1;
+            }
+            Edit::Delete => old_index += 1,
+            Edit::Insert => new_index += 1,
+        }
+    }
+
+    let mut output = format!("--- {old_label}\n+++ {new_label}\n");
+    let mut start = 0;
+    while let Some(offset) = steps[start..]
+        .iter()
+        .position(|(edit, _, _)| *edit != Edit::Keep)
+    {
+        let first_change = start + offset;
+        let hunk_start = first_change.saturating_sub(CONTEXT_LINES).max(start);
+
+        // A hunk ends once more than two contexts' worth of unchanged lines follow
+        let mut hunk_end = first_change;
+        let mut unchanged = 0;
+        for (index, (edit, _, _)) in steps.iter().enumerate().skip(first_change) {
+            if *edit == Edit::Keep {
+                unchanged += 1;
+                if unchanged > CONTEXT_LINES * 2 {
+                    break;
+                }
+            } else {
+                unchanged = 0;
+                hunk_end = index + 1;
+            }
+        }
+        let hunk_end = (hunk_end + CONTEXT_LINES).min(steps.len());
+        let hunk = &steps[hunk_start..hunk_end];
+
+        let old_count = hunk.iter().filter(|(e, _, _)| *e != Edit::Insert).count();
+        let new_count = hunk.iter().filter(|(e, _, _)| *e != Edit::Delete).count();
+        let (_, old_from, new_from) = hunk[0];
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_from, old_count),
+            hunk_range(new_from, new_count)
+        ));
+        for (edit, old_at, new_at) in hunk {
+            match edit {
+                Edit::Keep => output.push_str(&format!(" {}\n", old_lines[*old_at])),
+                Edit::Delete => output.push_str(&format!("-{}\n", old_lines[*old_at])),
+                Edit::Insert => output.push_str(&format!("+{}\n", new_lines[*new_at])),
+            }
+        }
+
+        start = hunk_end;
+    }
+
+    output
+}
+
+/// `start,count` as in GNU diff: 1-based, an empty range points at the line before it
+fn hunk_range(from: usize, count: usize) -> String {
+    match count {
+        0 => format!("{from},0"),
+        1 => format!("{}", from + 1),
+        _ => format!("{},{count}", from + 1),
+    }
+}
+
+/// Shortest edit script turning `old` into `new`. The common head and tail are kept
+/// out of the search, edits to a large file usually touch a few lines in the middle.
+fn shortest_edit(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let head = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let tail = old[head..]
+        .iter()
+        .rev()
+        .zip(new[head..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut edits = vec![Edit::Keep; head];
+    edits.extend(myers(
+        &old[head..old.len() - tail],
+        &new[head..new.len() - tail],
+    ));
+    edits.extend(std::iter::repeat_n(Edit::Keep, tail));
+    edits
+}
+
+fn myers(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = (n + m) as usize;
+    let offset = max as isize + 1;
+    let mut furthest = vec![0isize; 2 * max + 3];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: for depth in 0..=max as isize {
+        // Only diagonals -depth - 1..=depth + 1 are read at this depth
+        trace
+            .push(furthest[(offset - depth - 1) as usize..=(offset + depth + 1) as usize].to_vec());
+        for diagonal in (-depth..=depth).step_by(2) {
+            let index = (diagonal + offset) as usize;
+            let mut x = if diagonal == -depth
+                || (diagonal != depth && furthest[index - 1] < furthest[index + 1])
+            {
+                furthest[index + 1]
+            } else {
+                furthest[index - 1] + 1
+            };
+            let mut y = x - diagonal;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            furthest[index] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // Walk the recorded frontiers back from the end to recover the script
+    let mut edits = Vec::with_capacity(max);
+    let (mut x, mut y) = (n, m);
+    for (depth, furthest) in trace.iter().enumerate().rev() {
+        let depth = depth as isize;
+        let diagonal = x - y;
+        let index = (diagonal + depth + 1) as usize;
+        let previous_diagonal = if diagonal == -depth
+            || (diagonal != depth && furthest[index - 1] < furthest[index + 1])
+        {
+            diagonal + 1
+        } else {
+            diagonal - 1
+        };
+        let previous_x = furthest[(previous_diagonal + depth + 1) as usize];
+        let previous_y = previous_x - previous_diagonal;
+
+        while x > previous_x && y > previous_y {
+            edits.push(Edit::Keep);
+            x -= 1;
+            y -= 1;
+        }
+        if depth > 0 {
+            edits.push(if x == previous_x {
+                Edit::Insert
+            } else {
+                Edit::Delete
+            });
+        }
+        x = previous_x;
+        y = previous_y;
+    }
+
+    edits.reverse();
+    edits
+}
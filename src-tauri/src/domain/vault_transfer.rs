@@ -0,0 +1,443 @@
+//! Moving items between vaults: a versioned JSON bundle or a folder of Markdown files
+
+use super::front_matter;
+use super::storage::{Storage, TAG_COLOR_CODE_COUNT};
+use crate::models::*;
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Longest file name stem a Markdown export writes
+const MAX_FILE_STEM_CHARS: usize = 80;
+
+/// An item read from an import source, tags by name with an optional color
+pub(crate) struct ImportedItem {
+    pub item_type: ItemType,
+    pub title: String,
+    pub description: Option<String>,
+    pub content: String,
+    pub metadata: Option<Value>,
+    pub tags: Vec<(String, Option<i64>)>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Storage {
+    /// Writes every item outside the trash to `path`: a JSON file, or a folder for Markdown.
+    /// Returns the number of exported items.
+    pub async fn export_vault(&self, path: &Path, format: VaultFormat) -> Result<usize> {
+        let bundle = self.export_bundle().await?;
+        let count = bundle.items.len();
+        match format {
+            VaultFormat::Json => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create {}", parent.display()))?;
+                }
+                std::fs::write(path, serde_json::to_string_pretty(&bundle)?)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+            VaultFormat::Markdown => write_markdown_folder(&bundle, path)?,
+        }
+        tracing::info!("📦 Exported {} item(s) to {}", count, path.display());
+        Ok(count)
+    }
+
+    pub async fn import_vault(
+        &self,
+        path: &Path,
+        format: VaultFormat,
+        conflict: ImportConflict,
+    ) -> Result<VaultImportSummary> {
+        let summary = match format {
+            VaultFormat::Json => {
+                let text = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let bundle: VaultBundle = serde_json::from_str(&text)
+                    .with_context(|| format!("{} is not a vault bundle", path.display()))?;
+                self.import_bundle(bundle, conflict).await?
+            }
+            VaultFormat::Markdown => {
+                self.import_items(read_markdown_folder(path)?, conflict)
+                    .await?
+            }
+        };
+        tracing::info!(
+            "📥 Imported {}: {} created, {} updated, {} skipped",
+            path.display(),
+            summary.created,
+            summary.updated,
+            summary.skipped
+        );
+        Ok(summary)
+    }
+
+    pub async fn export_bundle(&self) -> Result<VaultBundle> {
+        let items = self.list_items(Some(i64::MAX), None, None, None).await?;
+
+        let mut tags = Vec::new();
+        let mut seen_tags = HashSet::new();
+        let mut item_tags = Vec::new();
+        let mut bundle_items = Vec::with_capacity(items.len());
+        for ItemWithTags {
+            item, tags: links, ..
+        } in items
+        {
+            for tag in links {
+                item_tags.push(BundleItemTag {
+                    item_id: item.id,
+                    tag_id: tag.id,
+                });
+                if seen_tags.insert(tag.id) {
+                    tags.push(BundleTag {
+                        id: tag.id,
+                        name: tag.name,
+                        color_code: tag.color_code,
+                    });
+                }
+            }
+            bundle_items.push(BundleItem {
+                id: item.id,
+                item_type: item.item_type,
+                title: item.title,
+                description: item.description,
+                content: item.content,
+                metadata: item.metadata,
+                created_at: item.created_at,
+                updated_at: item.updated_at,
+            });
+        }
+        // Creation order, an import recreates items in the same order
+        bundle_items.sort_by_key(|item| item.id);
+
+        Ok(VaultBundle {
+            version: VAULT_BUNDLE_VERSION,
+            exported_at: chrono::Utc::now().timestamp(),
+            items: bundle_items,
+            tags,
+            item_tags,
+        })
+    }
+
+    pub async fn import_bundle(
+        &self,
+        bundle: VaultBundle,
+        conflict: ImportConflict,
+    ) -> Result<VaultImportSummary> {
+        if bundle.version > VAULT_BUNDLE_VERSION {
+            anyhow::bail!(
+                "Bundle version {} is newer than this Dev Vault build supports ({})",
+                bundle.version,
+                VAULT_BUNDLE_VERSION
+            );
+        }
+
+        let tags: HashMap<i64, &BundleTag> = bundle.tags.iter().map(|tag| (tag.id, tag)).collect();
+        let mut item_tags: HashMap<i64, Vec<(String, Option<i64>)>> = HashMap::new();
+        for link in &bundle.item_tags {
+            if let Some(tag) = tags.get(&link.tag_id) {
+                item_tags
+                    .entry(link.item_id)
+                    .or_default()
+                    .push((tag.name.clone(), Some(tag.color_code)));
+            }
+        }
+
+        let items = bundle
+            .items
+            .into_iter()
+            .map(|item| ImportedItem {
+                tags: item_tags.remove(&item.id).unwrap_or_default(),
+                item_type: item.item_type,
+                title: item.title,
+                description: item.description,
+                content: item.content,
+                metadata: item.metadata,
+                created_at: item.created_at,
+                updated_at: item.updated_at,
+            })
+            .collect();
+
+        self.import_items(items, conflict).await
+    }
+
+    /// An item conflicts with an existing one of the same type and title
+    pub(crate) async fn import_items(
+        &self,
+        items: Vec<ImportedItem>,
+        conflict: ImportConflict,
+    ) -> Result<VaultImportSummary> {
+        let mut summary = VaultImportSummary::default();
+
+        for item in items {
+            let existing: Option<i64> = sqlx::query_scalar(
+                "SELECT id FROM items
+                 WHERE type = ?1 AND title = ?2 AND deleted_at IS NULL
+                 ORDER BY id LIMIT 1",
+            )
+            .bind(item_type_str(&item.item_type))
+            .bind(&item.title)
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to look up existing item")?;
+
+            if existing.is_some() && conflict == ImportConflict::Skip {
+                summary.skipped += 1;
+                continue;
+            }
+
+            match existing.filter(|_| conflict == ImportConflict::Overwrite) {
+                Some(id) => {
+                    self.update_item(UpdateItemDto {
+                        id,
+                        item_type: None,
+                        title: None,
+                        description: Some(item.description.unwrap_or_default()),
+                        content: Some(item.content),
+                        metadata: Some(item.metadata.unwrap_or_else(|| serde_json::json!({}))),
+                        tag_ids: None,
+                    })
+                    .await?;
+                    self.link_imported_tags(id, &item.tags).await?;
+                    self.spawn_cleanup_unused_tags();
+                    summary.updated += 1;
+                }
+                None => {
+                    let id = self
+                        .create_item(CreateItemDto {
+                            item_type: item.item_type,
+                            title: item.title,
+                            description: item.description,
+                            content: item.content,
+                            metadata: item.metadata,
+                            tag_ids: None,
+                        })
+                        .await?;
+                    self.link_imported_tags(id, &item.tags).await?;
+                    sqlx::query("UPDATE items SET created_at = ?1, updated_at = ?2 WHERE id = ?3")
+                        .bind(item.created_at)
+                        .bind(item.updated_at)
+                        .bind(id)
+                        .execute(&self.pool)
+                        .await
+                        .context("Failed to keep imported timestamps")?;
+                    summary.created += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Replaces the item's tags with the named ones, missing tags are created with the
+    /// bundle's color when it has one. Everything happens in one transaction so the
+    /// background cleanup of unused tags cannot drop a tag before it is linked.
+    pub(crate) async fn link_imported_tags(
+        &self,
+        item_id: i64,
+        tags: &[(String, Option<i64>)],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM item_tags WHERE item_id = ?1")
+            .bind(item_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to remove old tags")?;
+        for (name, color_code) in tags {
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            sqlx::query(
+                "INSERT INTO tags (name, color_code)
+                 VALUES (?1, COALESCE(?2, CAST((random() & 9223372036854775807) % ?3 AS INTEGER)))
+                 ON CONFLICT(name) DO NOTHING",
+            )
+            .bind(name)
+            .bind(color_code)
+            .bind(TAG_COLOR_CODE_COUNT)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to create imported tag")?;
+            sqlx::query(
+                "INSERT OR IGNORE INTO item_tags (item_id, tag_id)
+                 SELECT ?1, id FROM tags WHERE name = ?2",
+            )
+            .bind(item_id)
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to link imported tag")?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+fn item_type_str(item_type: &ItemType) -> &'static str {
+    match item_type {
+        ItemType::Snippet => "snippet",
+        ItemType::Config => "config",
+        ItemType::Note => "note",
+        ItemType::Link => "link",
+        ItemType::Documentation => "documentation",
+    }
+}
+
+fn parse_item_type(value: &str) -> Result<ItemType> {
+    serde_json::from_value(Value::String(value.to_string()))
+        .map_err(|_| anyhow::anyhow!("Unknown item type: {}", value))
+}
+
+fn write_markdown_folder(bundle: &VaultBundle, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let tag_names: HashMap<i64, &str> = bundle
+        .tags
+        .iter()
+        .map(|tag| (tag.id, tag.name.as_str()))
+        .collect();
+    let mut used_stems = HashSet::new();
+
+    for item in &bundle.items {
+        let tags: Vec<Value> = bundle
+            .item_tags
+            .iter()
+            .filter(|link| link.item_id == item.id)
+            .filter_map(|link| tag_names.get(&link.tag_id))
+            .map(|name| Value::String(name.to_string()))
+            .collect();
+
+        let mut fields = Map::new();
+        fields.insert("type".into(), item_type_str(&item.item_type).into());
+        fields.insert("title".into(), item.title.clone().into());
+        if let Some(description) = item.description.as_deref().filter(|d| !d.is_empty()) {
+            fields.insert("description".into(), description.into());
+        }
+        fields.insert("tags".into(), Value::Array(tags));
+        fields.insert("created".into(), format_timestamp(item.created_at).into());
+        fields.insert("updated".into(), format_timestamp(item.updated_at).into());
+        if let Some(metadata) = item
+            .metadata
+            .as_ref()
+            .filter(|m| m.as_object().is_none_or(|map| !map.is_empty()))
+        {
+            fields.insert("metadata".into(), metadata.clone());
+        }
+
+        let stem = unique_stem(&item.title, &mut used_stems);
+        let path = dir.join(format!("{stem}.md"));
+        std::fs::write(&path, front_matter::render(&fields, &item.content))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+fn read_markdown_folder(dir: &Path) -> Result<Vec<ImportedItem>> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let text = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            markdown_item(path, &text).with_context(|| format!("Invalid {}", path.display()))
+        })
+        .collect()
+}
+
+/// Missing fields fall back to a note titled after the file, stamped now
+fn markdown_item(path: &Path, text: &str) -> Result<ImportedItem> {
+    let (fields, body) = front_matter::parse(text)?;
+    let string = |key: &str| fields.get(key).and_then(Value::as_str).map(str::to_string);
+    let now = chrono::Utc::now().timestamp();
+
+    let item_type = match string("type") {
+        Some(value) => parse_item_type(&value)?,
+        None => ItemType::Note,
+    };
+    let title = string("title").unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    let tags = match fields.get("tags") {
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|name| (name.to_string(), None))
+            .collect(),
+        Some(Value::String(name)) => vec![(name.clone(), None)],
+        _ => Vec::new(),
+    };
+    let created_at = fields.get("created").map(parse_timestamp).transpose()?;
+    let updated_at = fields.get("updated").map(parse_timestamp).transpose()?;
+
+    Ok(ImportedItem {
+        item_type,
+        title,
+        description: string("description"),
+        content: body.to_string(),
+        metadata: fields.get("metadata").filter(|m| m.is_object()).cloned(),
+        tags,
+        created_at: created_at.or(updated_at).unwrap_or(now),
+        updated_at: updated_at.or(created_at).unwrap_or(now),
+    })
+}
+
+pub(crate) fn format_timestamp(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// RFC 3339, a plain date or unix seconds
+pub(crate) fn parse_timestamp(value: &Value) -> Result<i64> {
+    if let Some(seconds) = value.as_i64() {
+        return Ok(seconds);
+    }
+    let text = value.as_str().unwrap_or_default();
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Ok(date.timestamp());
+    }
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc().timestamp())
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp: {}", value))
+}
+
+/// File name stem from a title, unique within the export (case-insensitively)
+pub(crate) fn unique_stem(title: &str, used: &mut HashSet<String>) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| {
+            if c.is_control() || "/\\:*?\"<>|".contains(c) {
+                '-'
+            } else {
+                c
+            }
+        })
+        .take(MAX_FILE_STEM_CHARS)
+        .collect();
+    let base = match cleaned.trim().trim_matches('.') {
+        "" => "item".to_string(),
+        base => base.to_string(),
+    };
+
+    let mut stem = base.clone();
+    let mut counter = 2;
+    while !used.insert(stem.to_lowercase()) {
+        stem = format!("{base}-{counter}");
+        counter += 1;
+    }
+    stem
+}
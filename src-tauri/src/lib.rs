@@ -5,9 +5,12 @@ pub mod models;
 mod shortcuts;
 
 use commands::AppState;
+use domain::linked_configs::ChangedConfigFiles;
 use domain::{DocumentationManager, Storage};
 use shortcuts::accelerator_by_id;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::{Emitter, Manager};
 use tokio::sync::Mutex;
@@ -55,20 +58,33 @@ pub fn run() {
             let db_path = app_dir.join("dev-vault.db");
             tracing::info!("💾 Database path: {:?}", db_path);
 
+            tracing::info!("⚙️  Initializing config manager...");
+            let config_manager = domain::ConfigManager::new(app_dir.clone());
+            let config = tauri::async_runtime::block_on(config_manager.load_config())
+                .unwrap_or_else(|e| {
+                    tracing::warn!("⚠ Failed to load config, using defaults: {}", e);
+                    models::config::AppConfig::default()
+                });
+
             tracing::info!("🔌 Initializing storage...");
             let storage = tauri::async_runtime::block_on(async {
                 Storage::new(db_path.clone())
                     .await
                     .expect("Failed to initialize storage")
-            });
+            })
+            .with_history(config.history);
             tracing::info!("✅ Storage initialized successfully");
 
-            tracing::info!("⚙️  Initializing config manager...");
-            let config_manager = domain::ConfigManager::new(app_dir.clone());
+            tracing::info!("📚 Loading custom documentation sources...");
+            let sources = domain::parsers::load_custom_sources(&app_dir);
+            for error in &sources.errors {
+                tracing::warn!("⚠ {}: {}", error.file, error.message);
+            }
 
             tracing::info!("📖 Initializing documentation manager...");
             let doc_manager = tauri::async_runtime::block_on(async {
                 DocumentationManager::new(storage.pool.clone())
+                    .with_http_cache(app_dir.join(domain::parsers::HTTP_CACHE_DIR))
             });
             tracing::info!("✅ Documentation manager initialized");
 
@@ -76,8 +92,32 @@ pub fn run() {
                 storage: Arc::new(Mutex::new(storage)),
                 config_manager: Arc::new(config_manager),
                 doc_manager: Arc::new(Mutex::new(doc_manager)),
+                app_dir: app_dir.clone(),
+                doc_installs: Arc::new(Mutex::new(HashMap::new())),
             };
 
+            tracing::info!("🗑️  Starting trash purge task...");
+            tauri::async_runtime::spawn(run_trash_purge(commands::TrashPurge {
+                storage: state.storage.clone(),
+                doc_manager: state.doc_manager.clone(),
+                config_manager: state.config_manager.clone(),
+            }));
+
+            tracing::info!("🔁 Starting Markdown sync task...");
+            tauri::async_runtime::spawn(run_markdown_sync(
+                app.handle().clone(),
+                commands::MarkdownSync {
+                    storage: state.storage.clone(),
+                    config_manager: state.config_manager.clone(),
+                },
+            ));
+
+            tracing::info!("🔗 Starting linked config watcher...");
+            tauri::async_runtime::spawn(run_linked_config_watch(
+                app.handle().clone(),
+                state.storage.clone(),
+            ));
+
             app.manage(state);
             tracing::info!("✅ Application state initialized");
 
@@ -221,6 +261,25 @@ pub fn run() {
             commands::get_item,
             commands::update_item,
             commands::delete_item,
+            commands::restore_item,
+            commands::list_trash,
+            commands::purge_from_trash,
+            commands::empty_trash,
+            commands::export_vault,
+            commands::import_vault,
+            commands::import_snippets,
+            commands::export_vscode_snippets,
+            commands::sync_markdown_now,
+            commands::resolve_markdown_conflict,
+            commands::link_config_file,
+            commands::unlink_config_file,
+            commands::list_config_links,
+            commands::config_drift_report,
+            commands::preview_config_write_back,
+            commands::write_config_back,
+            commands::list_item_revisions,
+            commands::diff_item_revisions,
+            commands::restore_item_revision,
             commands::list_items,
             commands::create_tag,
             commands::get_or_create_tag,
@@ -231,10 +290,21 @@ pub fn run() {
             commands::get_config,
             commands::save_config,
             commands::list_available_docs,
+            commands::list_custom_doc_sources,
+            commands::add_custom_doc_source,
+            commands::remove_custom_doc_source,
             commands::list_installed_docs,
             commands::install_documentation,
+            commands::cancel_doc_install,
+            commands::list_doc_install_checkpoints,
+            commands::discard_doc_install_checkpoint,
+            commands::list_doc_versions,
+            commands::set_active_doc_version,
             commands::update_documentation,
+            commands::rebuild_documentation_from_cache,
+            commands::clear_doc_http_cache,
             commands::delete_documentation,
+            commands::restore_documentation,
             commands::get_doc_entries,
             commands::get_doc_entry_by_path,
             commands::get_doc_tree,
@@ -242,8 +312,74 @@ pub fn run() {
             commands::get_mcp_server_config,
             commands::list_ai_tools,
             commands::connect_mcp_server,
+            commands::disconnect_mcp_server,
             commands::mcp_health,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+/// How often the trash is checked for entries past their retention
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Runs for the lifetime of the app, the first pass right at startup
+async fn run_trash_purge(state: commands::TrashPurge) {
+    let mut interval = tokio::time::interval(TRASH_PURGE_INTERVAL);
+    loop {
+        interval.tick().await;
+        match state.purge_expired().await {
+            Ok((0, 0)) => {}
+            Ok((items, docs)) => tracing::info!(
+                "🗑️  Purged {} item(s) and {} documentation(s) from the trash",
+                items,
+                docs
+            ),
+            Err(e) => tracing::warn!("⚠ Trash purge failed: {:?}", e),
+        }
+    }
+}
+
+/// Polls the synced Markdown folder for the lifetime of the app; the frontend reloads
+/// its items when a pass changed something
+async fn run_markdown_sync(app: tauri::AppHandle, state: commands::MarkdownSync) {
+    loop {
+        let (result, delay) = state.sync_if_enabled().await;
+        match result {
+            Ok(Some(report)) if report.changed() || !report.conflicts.is_empty() => {
+                let _ = app.emit("markdown-sync", &report);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("⚠ Markdown sync failed: {:?}", e),
+        }
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// How often linked config files are checked for changes on disk
+const LINKED_CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Pulls edits of linked config files into their items for the lifetime of the app; the
+/// files are read on the blocking pool, the storage lock is held only for the queries
+async fn run_linked_config_watch(app: tauri::AppHandle, storage: Arc<Mutex<Storage>>) {
+    let mut interval = tokio::time::interval(LINKED_CONFIG_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let result = refresh_linked_configs(&storage).await;
+        match result {
+            Ok(updated) if updated.is_empty() => {}
+            Ok(updated) => {
+                let _ = app.emit("linked-configs-changed", &updated);
+            }
+            Err(e) => tracing::warn!("⚠ Linked config refresh failed: {:?}", e),
+        }
+    }
+}
+
+async fn refresh_linked_configs(storage: &Mutex<Storage>) -> anyhow::Result<Vec<i64>> {
+    let configs = storage.lock().await.linked_configs().await?;
+    if configs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let files = tokio::task::spawn_blocking(move || ChangedConfigFiles::read(configs)).await?;
+    storage.lock().await.apply_changed_config_files(files).await
+}
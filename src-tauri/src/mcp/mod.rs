@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::{Path, PathBuf};
 
+pub mod tools;
+
 pub const APP_IDENTIFIER: &str = "com.mikenoired.dev-vault";
 pub const MCP_SERVER_NAME: &str = "dev-vault";
+pub const RESOURCE_SCHEME: &str = "devvault";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,6 +17,86 @@ pub struct McpServerConfig {
     pub command_exists: bool,
 }
 
+/// Vault content addressed as an MCP resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceUri {
+    /// `devvault://item/{id}`
+    Item(i64),
+    /// `devvault://doc/{name}/{path}`, read from the active version of the documentation
+    Doc { name: String, path: String },
+}
+
+impl ResourceUri {
+    pub fn parse(uri: &str) -> Result<Self, String> {
+        let invalid = || format!("Unsupported resource URI: {uri}");
+        let rest = uri
+            .strip_prefix(RESOURCE_SCHEME)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .ok_or_else(invalid)?;
+        let (kind, rest) = rest.split_once('/').ok_or_else(invalid)?;
+
+        match kind {
+            "item" => rest.parse().map(ResourceUri::Item).map_err(|_| invalid()),
+            "doc" => {
+                let (name, path) = rest.split_once('/').ok_or_else(invalid)?;
+                let name = percent_decode(name).ok_or_else(invalid)?;
+                let path = percent_decode(path).ok_or_else(invalid)?;
+                if name.is_empty() || path.is_empty() {
+                    return Err(invalid());
+                }
+                Ok(ResourceUri::Doc { name, path })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceUri::Item(id) => write!(f, "{RESOURCE_SCHEME}://item/{id}"),
+            ResourceUri::Doc { name, path } => write!(
+                f,
+                "{RESOURCE_SCHEME}://doc/{}/{}",
+                percent_encode(name, false),
+                percent_encode(path, true)
+            ),
+        }
+    }
+}
+
+/// Escapes everything but unreserved characters, and `/` when `keep_slash` is set
+fn percent_encode(value: &str, keep_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if keep_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = value.get(index + 1..index + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
 pub fn default_db_path() -> Result<PathBuf, String> {
     let data_dir = dirs::data_dir().ok_or("Failed to resolve data directory")?;
     Ok(data_dir.join(APP_IDENTIFIER).join("dev-vault.db"))
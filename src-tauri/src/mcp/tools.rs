@@ -0,0 +1,524 @@
+use super::{McpServerConfig, MCP_SERVER_NAME};
+use crate::domain::jsonc::{JsoncDocument, PathSegment};
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// Appended to a config file name for the copy taken before every write
+pub const BACKUP_SUFFIX: &str = "devvault.bak";
+
+/// How a tool stores its MCP servers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// Codex `config.toml`: `[mcp_servers.dev-vault]`
+    CodexToml,
+    /// Claude Desktop and Cursor: `{"mcpServers": {"dev-vault": {...}}}`
+    McpServersJson,
+    /// VS Code `mcp.json`: `{"servers": {"dev-vault": {"type": "stdio", ...}}}`
+    VsCodeJson,
+    /// Zed `settings.json`: `{"context_servers": {"dev-vault": {"source": "custom", ...}}}`
+    ZedSettings,
+    /// Continue `config.json`: a named entry in `experimental.modelContextProtocolServers`
+    ContinueJson,
+}
+
+/// Directory a registry path is relative to
+#[derive(Debug, Clone, Copy)]
+enum Root {
+    Home,
+    /// `~/.config`, `~/Library/Application Support` or `%APPDATA%`
+    ConfigDir,
+    Cwd,
+    CodexHome,
+}
+
+struct ToolSpec {
+    id: &'static str,
+    name: &'static str,
+    format: ConfigFormat,
+    config_files: &'static [(Root, &'static str)],
+    markers: &'static [(Root, &'static str)],
+    executables: &'static [&'static str],
+}
+
+const TOOL_SPECS: &[ToolSpec] = &[
+    ToolSpec {
+        id: "codex",
+        name: "Codex",
+        format: ConfigFormat::CodexToml,
+        config_files: &[
+            (Root::CodexHome, "config.toml"),
+            (Root::Home, ".codex/config.toml"),
+            (Root::Cwd, ".codex/config.toml"),
+        ],
+        markers: &[(Root::Home, ".codex")],
+        executables: &["codex"],
+    },
+    ToolSpec {
+        id: "claude-desktop",
+        name: "Claude Desktop",
+        format: ConfigFormat::McpServersJson,
+        config_files: &[(Root::ConfigDir, "Claude/claude_desktop_config.json")],
+        markers: &[(Root::ConfigDir, "Claude")],
+        executables: &[],
+    },
+    ToolSpec {
+        id: "cursor",
+        name: "Cursor",
+        format: ConfigFormat::McpServersJson,
+        config_files: &[
+            (Root::Home, ".cursor/mcp.json"),
+            (Root::Cwd, ".cursor/mcp.json"),
+        ],
+        markers: &[(Root::Home, ".cursor")],
+        executables: &["cursor"],
+    },
+    ToolSpec {
+        id: "vscode",
+        name: "VS Code",
+        format: ConfigFormat::VsCodeJson,
+        config_files: &[
+            (Root::ConfigDir, "Code/User/mcp.json"),
+            (Root::Cwd, ".vscode/mcp.json"),
+        ],
+        markers: &[(Root::ConfigDir, "Code"), (Root::Cwd, ".vscode")],
+        executables: &["code"],
+    },
+    ToolSpec {
+        id: "zed",
+        name: "Zed",
+        format: ConfigFormat::ZedSettings,
+        config_files: &[
+            (Root::Home, ".config/zed/settings.json"),
+            (Root::ConfigDir, "Zed/settings.json"),
+        ],
+        markers: &[(Root::Home, ".config/zed"), (Root::ConfigDir, "Zed")],
+        executables: &["zed", "zeditor"],
+    },
+    ToolSpec {
+        id: "continue",
+        name: "Continue",
+        format: ConfigFormat::ContinueJson,
+        config_files: &[(Root::Home, ".continue/config.json")],
+        markers: &[(Root::Home, ".continue")],
+        executables: &[],
+    },
+];
+
+/// Where tool configs are looked up, tests point it at a temporary directory
+#[derive(Debug, Clone, Default)]
+pub struct ToolEnvironment {
+    pub home: Option<PathBuf>,
+    pub config_dir: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    pub codex_home: Option<PathBuf>,
+}
+
+impl ToolEnvironment {
+    pub fn current() -> Self {
+        Self {
+            home: dirs::home_dir(),
+            config_dir: dirs::config_dir(),
+            cwd: std::env::current_dir().ok(),
+            codex_home: std::env::var_os("CODEX_HOME").map(PathBuf::from),
+        }
+    }
+
+    /// Every root under one directory
+    pub fn rooted_at(dir: &Path) -> Self {
+        Self {
+            home: Some(dir.join("home")),
+            config_dir: Some(dir.join("config")),
+            cwd: Some(dir.join("project")),
+            codex_home: None,
+        }
+    }
+
+    fn resolve(&self, root: Root, relative: &str) -> Option<PathBuf> {
+        let base = match root {
+            Root::Home => self.home.as_ref(),
+            Root::ConfigDir => self.config_dir.as_ref(),
+            Root::Cwd => self.cwd.as_ref(),
+            Root::CodexHome => self.codex_home.as_ref(),
+        }?;
+        Some(base.join(relative))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolDefinition {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub format: ConfigFormat,
+    /// The first existing file is used, the first one is created when none exists
+    pub config_paths: Vec<PathBuf>,
+    /// Any of these existing means the tool is installed
+    pub markers: Vec<PathBuf>,
+    pub executables: &'static [&'static str],
+}
+
+impl ToolDefinition {
+    pub fn config_path(&self) -> Option<PathBuf> {
+        self.config_paths
+            .iter()
+            .find(|path| path.exists())
+            .or_else(|| self.config_paths.first())
+            .cloned()
+    }
+
+    /// A marker directory or the config file exists; `find_executable` covers CLIs on PATH
+    pub fn detected(&self, find_executable: impl Fn(&str) -> bool) -> bool {
+        self.markers.iter().any(|path| path.exists())
+            || self.config_paths.iter().any(|path| path.exists())
+            || self.executables.iter().any(|name| find_executable(name))
+    }
+}
+
+pub fn tool_registry(env: &ToolEnvironment) -> Vec<ToolDefinition> {
+    let resolve_all = |paths: &[(Root, &str)]| -> Vec<PathBuf> {
+        paths
+            .iter()
+            .filter_map(|(root, relative)| env.resolve(*root, relative))
+            .collect()
+    };
+
+    TOOL_SPECS
+        .iter()
+        .map(|spec| ToolDefinition {
+            id: spec.id,
+            name: spec.name,
+            format: spec.format,
+            config_paths: resolve_all(spec.config_files),
+            markers: resolve_all(spec.markers),
+            executables: spec.executables,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum McpEntryState {
+    Missing,
+    /// Present and launching the current command with the current arguments
+    Current,
+    /// Present but pointing at another binary or database
+    Outdated,
+}
+
+pub fn read_entry_state(tool: &ToolDefinition, server: &McpServerConfig) -> Result<McpEntryState> {
+    let Some(path) = tool.config_path().filter(|path| path.exists()) else {
+        return Ok(McpEntryState::Missing);
+    };
+
+    let state = match tool.format {
+        ConfigFormat::CodexToml => {
+            let table = read_toml(&path)?;
+            match codex_entry(&table) {
+                None => McpEntryState::Missing,
+                Some(entry) if codex_entry_matches(entry, server) => McpEntryState::Current,
+                Some(_) => McpEntryState::Outdated,
+            }
+        }
+        format => {
+            let document = json_root(&read_json_document(&path)?, &path)?;
+            match json_entry(&document, format) {
+                None => McpEntryState::Missing,
+                Some(entry) if is_subset(&expected_json_entry(format, server), entry) => {
+                    McpEntryState::Current
+                }
+                Some(_) => McpEntryState::Outdated,
+            }
+        }
+    };
+    Ok(state)
+}
+
+/// Adds or updates the `dev-vault` entry and returns the file written. Keys the entry does not
+/// own are kept, the rest of the file keeps its comments and layout, and the previous file
+/// is copied next to it first.
+pub fn install_entry(tool: &ToolDefinition, server: &McpServerConfig) -> Result<PathBuf> {
+    let path = tool
+        .config_path()
+        .context("No config location for this tool")?;
+
+    match tool.format {
+        ConfigFormat::CodexToml => {
+            let mut document = read_toml_document(&path)?;
+            let servers = document
+                .entry("mcp_servers")
+                .or_insert_with(|| {
+                    let mut servers = toml_edit::Table::new();
+                    servers.set_implicit(true);
+                    toml_edit::Item::Table(servers)
+                })
+                .as_table_like_mut()
+                .context("mcp_servers is not a table")?;
+            let entry = servers
+                .entry(MCP_SERVER_NAME)
+                .or_insert(toml_edit::table())
+                .as_table_like_mut()
+                .context("mcp_servers.dev-vault is not a table")?;
+            entry.insert("command", toml_edit::value(server.command.as_str()));
+            entry.insert(
+                "args",
+                toml_edit::value(
+                    server
+                        .args
+                        .iter()
+                        .map(String::as_str)
+                        .collect::<toml_edit::Array>(),
+                ),
+            );
+            write_config(&path, &document.to_string())?;
+        }
+        format => {
+            let mut document = read_json_document(&path)?;
+            let value = json_root(&document, &path)?;
+            let expected = expected_json_entry(format, server);
+            match json_entry_path(&value, format) {
+                Some(entry_path) => {
+                    let mut entry = json_entry(&value, format).cloned().unwrap_or_default();
+                    merge_into(&mut entry, &expected);
+                    document.set(&entry_path, &entry)?;
+                }
+                None if format == ConfigFormat::ContinueJson => document.push(
+                    &[
+                        PathSegment::Key("experimental"),
+                        PathSegment::Key("modelContextProtocolServers"),
+                    ],
+                    &expected,
+                )?,
+                None => document.set(
+                    &[
+                        PathSegment::Key(servers_key(format)),
+                        PathSegment::Key(MCP_SERVER_NAME),
+                    ],
+                    &expected,
+                )?,
+            }
+            write_config(&path, document.text())?;
+        }
+    }
+
+    Ok(path)
+}
+
+/// Drops the `dev-vault` entry, `false` when there was none
+pub fn remove_entry(tool: &ToolDefinition) -> Result<bool> {
+    let Some(path) = tool.config_path().filter(|path| path.exists()) else {
+        return Ok(false);
+    };
+
+    match tool.format {
+        ConfigFormat::CodexToml => {
+            let mut document = read_toml_document(&path)?;
+            let removed = document
+                .get_mut("mcp_servers")
+                .and_then(toml_edit::Item::as_table_like_mut)
+                .and_then(|servers| servers.remove(MCP_SERVER_NAME))
+                .is_some();
+            if removed {
+                write_config(&path, &document.to_string())?;
+            }
+            Ok(removed)
+        }
+        format => {
+            let mut document = read_json_document(&path)?;
+            let mut removed = false;
+            while let Some(entry_path) = json_entry_path(&json_root(&document, &path)?, format) {
+                document.remove(&entry_path)?;
+                removed = true;
+            }
+            if removed {
+                write_config(&path, document.text())?;
+            }
+            Ok(removed)
+        }
+    }
+}
+
+fn codex_entry(table: &toml::Table) -> Option<&toml::Table> {
+    table
+        .get("mcp_servers")?
+        .as_table()?
+        .get(MCP_SERVER_NAME)?
+        .as_table()
+}
+
+fn codex_entry_matches(entry: &toml::Table, server: &McpServerConfig) -> bool {
+    let args: Option<Vec<&str>> = entry
+        .get("args")
+        .and_then(toml::Value::as_array)
+        .map(|args| args.iter().filter_map(toml::Value::as_str).collect());
+    entry.get("command").and_then(toml::Value::as_str) == Some(server.command.as_str())
+        && args == Some(server.args.iter().map(String::as_str).collect())
+}
+
+/// The keys Dev Vault owns in the tool's entry
+fn expected_json_entry(format: ConfigFormat, server: &McpServerConfig) -> Value {
+    let command = json!(server.command);
+    let args = json!(server.args);
+    match format {
+        ConfigFormat::McpServersJson => json!({ "command": command, "args": args }),
+        ConfigFormat::VsCodeJson => json!({ "type": "stdio", "command": command, "args": args }),
+        ConfigFormat::ZedSettings => {
+            json!({ "source": "custom", "command": command, "args": args })
+        }
+        ConfigFormat::ContinueJson => json!({
+            "name": MCP_SERVER_NAME,
+            "transport": { "type": "stdio", "command": command, "args": args }
+        }),
+        ConfigFormat::CodexToml => unreachable!("Codex config is TOML"),
+    }
+}
+
+/// Key holding the named servers map, Continue keeps a list instead
+fn servers_key(format: ConfigFormat) -> &'static str {
+    match format {
+        ConfigFormat::McpServersJson => "mcpServers",
+        ConfigFormat::VsCodeJson => "servers",
+        ConfigFormat::ZedSettings => "context_servers",
+        ConfigFormat::ContinueJson | ConfigFormat::CodexToml => unreachable!(),
+    }
+}
+
+fn is_continue_entry(entry: &Value) -> bool {
+    entry.get("name").and_then(Value::as_str) == Some(MCP_SERVER_NAME)
+}
+
+fn json_entry(document: &Value, format: ConfigFormat) -> Option<&Value> {
+    match format {
+        ConfigFormat::ContinueJson => document
+            .get("experimental")?
+            .get("modelContextProtocolServers")?
+            .as_array()?
+            .iter()
+            .find(|entry| is_continue_entry(entry)),
+        format => document.get(servers_key(format))?.get(MCP_SERVER_NAME),
+    }
+}
+
+/// Where `json_entry` is in the document
+fn json_entry_path(document: &Value, format: ConfigFormat) -> Option<Vec<PathSegment<'static>>> {
+    match format {
+        ConfigFormat::ContinueJson => {
+            let index = document
+                .get("experimental")?
+                .get("modelContextProtocolServers")?
+                .as_array()?
+                .iter()
+                .position(is_continue_entry)?;
+            Some(vec![
+                PathSegment::Key("experimental"),
+                PathSegment::Key("modelContextProtocolServers"),
+                PathSegment::Index(index),
+            ])
+        }
+        format => {
+            json_entry(document, format)?;
+            Some(vec![
+                PathSegment::Key(servers_key(format)),
+                PathSegment::Key(MCP_SERVER_NAME),
+            ])
+        }
+    }
+}
+
+/// Every key of `expected` is in `actual` with the same value, extra keys are fine
+fn is_subset(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual| is_subset(value, actual))
+        }),
+        (expected, actual) => expected == actual,
+    }
+}
+
+/// Overwrites the keys of `patch` in `target`, keeping the rest
+fn merge_into(target: &mut Value, patch: &Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                match target.get_mut(key) {
+                    Some(existing) if existing.is_object() && value.is_object() => {
+                        merge_into(existing, value)
+                    }
+                    _ => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (target, patch) => *target = patch.clone(),
+    }
+}
+
+fn read_toml(path: &Path) -> Result<toml::Table> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))
+}
+
+/// Codex config as an editable document, comments and layout survive the write
+fn read_toml_document(path: &Path) -> Result<toml_edit::DocumentMut> {
+    if !path.exists() {
+        return Ok(toml_edit::DocumentMut::new());
+    }
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    content
+        .parse()
+        .with_context(|| format!("Failed to parse {:?}", path))
+}
+
+/// Zed and VS Code allow comments and trailing commas, edits keep them
+fn read_json_document(path: &Path) -> Result<JsoncDocument> {
+    let content = if path.exists() {
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?
+    } else {
+        String::new()
+    };
+    let content = if content.trim().is_empty() {
+        "{}\n"
+    } else {
+        &content
+    };
+    JsoncDocument::parse(content).with_context(|| format!("Failed to parse {:?}", path))
+}
+
+fn json_root(document: &JsoncDocument, path: &Path) -> Result<Value> {
+    let value = document
+        .value()
+        .with_context(|| format!("Failed to parse {:?}", path))?;
+    if !value.is_object() {
+        bail!("Config root is not a JSON object");
+    }
+    Ok(value)
+}
+
+/// Backs the current file up, then replaces it through a temporary file
+fn write_config(path: &Path, content: &str) -> Result<()> {
+    if path.exists() {
+        std::fs::copy(path, backup_path(path))
+            .with_context(|| format!("Failed to back up {:?}", path))?;
+    } else if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+
+    let tmp_path = path.with_extension("devvault.tmp");
+    std::fs::write(&tmp_path, content)
+        .with_context(|| format!("Failed to write {:?}", tmp_path))?;
+    std::fs::rename(&tmp_path, path).with_context(|| format!("Failed to replace {:?}", path))?;
+    Ok(())
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(BACKUP_SUFFIX);
+    path.with_file_name(name)
+}
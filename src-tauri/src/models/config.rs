@@ -5,6 +5,9 @@ use serde::{Deserialize, Serialize};
 pub struct AppConfig {
     pub search: SearchConfig,
     pub ui: UiConfig,
+    pub history: HistoryConfig,
+    pub trash: TrashConfig,
+    pub markdown_sync: MarkdownSyncConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -15,6 +18,32 @@ pub struct SearchConfig {
     pub results_limit: usize,
 }
 
+/// Retention of item revisions; `0` lifts a limit
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub max_revisions_per_item: u32,
+    pub max_age_days: u32,
+}
+
+/// How long deleted items and documentations stay restorable; `0` keeps them until
+/// the trash is emptied
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct TrashConfig {
+    pub retention_days: u32,
+}
+
+/// Two-way sync of notes with a folder of Markdown files, off while `directory` is empty
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct MarkdownSyncConfig {
+    pub enabled: bool,
+    pub directory: String,
+    /// How often the folder is checked for changes
+    pub interval_secs: u32,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum Theme {
@@ -54,6 +83,31 @@ impl Default for SearchConfig {
     }
 }
 
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_revisions_per_item: 50,
+            max_age_days: 90,
+        }
+    }
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+impl Default for MarkdownSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: String::new(),
+            interval_secs: 10,
+        }
+    }
+}
+
 impl Default for UiConfig {
     fn default() -> Self {
         Self {
@@ -72,6 +126,9 @@ impl Default for AppConfig {
         Self {
             search: SearchConfig::default(),
             ui: UiConfig::default(),
+            history: HistoryConfig::default(),
+            trash: TrashConfig::default(),
+            markdown_sync: MarkdownSyncConfig::default(),
         }
     }
 }
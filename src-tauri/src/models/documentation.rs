@@ -14,6 +14,9 @@ pub struct Documentation {
     #[serde(rename = "updatedAt")]
     pub updated_at: i64,
     pub metadata: Option<serde_json::Value>,
+    /// The version search and lookups by name use when several are installed
+    #[serde(rename = "isActive")]
+    pub is_active: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,14 +41,37 @@ pub struct AvailableDocumentation {
     #[serde(rename = "displayName")]
     pub display_name: String,
     pub version: String,
+    /// Versions that can be installed, the default `version` first
+    #[serde(rename = "availableVersions")]
+    pub available_versions: Vec<String>,
     pub description: String,
     #[serde(rename = "sourceUrl")]
     pub source_url: String,
 }
 
+/// Crawl progress saved by an install that did not finish
+#[derive(Debug, Clone, Serialize)]
+pub struct DocInstallCheckpoint {
+    pub name: String,
+    pub version: String,
+    /// `running` (the app stopped mid-install), `cancelled` or `failed`
+    pub status: String,
+    #[serde(rename = "visitedCount")]
+    pub visited_count: i64,
+    #[serde(rename = "queuedCount")]
+    pub queued_count: i64,
+    #[serde(rename = "entriesCount")]
+    pub entries_count: i64,
+    #[serde(rename = "startedAt")]
+    pub started_at: i64,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: i64,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct InstallDocumentationDto {
     pub name: String,
+    pub version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -61,6 +87,15 @@ pub struct DocTreeNode {
     pub has_children: bool,
 }
 
+/// An entry of an active documentation version, addressed by documentation name and path
+#[derive(Debug, Clone, Serialize)]
+pub struct DocEntryRef {
+    #[serde(rename = "docName")]
+    pub doc_name: String,
+    pub path: String,
+    pub title: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct DocumentationGraphNode {
     pub path: String,
@@ -89,6 +124,45 @@ pub struct DocumentationGraph {
     pub edges: Vec<DocumentationGraphEdge>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomDocSource {
+    pub name: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    /// "scraper" or "github"
+    pub kind: String,
+    pub file: String,
+}
+
+/// A source file that could not be loaded, the rest of the directory still is
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomDocSourceError {
+    pub file: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CustomDocSourcesReport {
+    pub sources: Vec<CustomDocSource>,
+    pub errors: Vec<CustomDocSourceError>,
+}
+
+/// What an incremental documentation update did to the stored entries
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DocUpdateSummary {
+    pub added: usize,
+    pub changed: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentationUpdate {
+    #[serde(flatten)]
+    pub documentation: Documentation,
+    pub summary: DocUpdateSummary,
+}
+
 #[derive(Debug, Clone)]
 pub struct ParsedDocEntry {
     pub path: String,
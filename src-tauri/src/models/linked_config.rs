@@ -0,0 +1,40 @@
+use serde::Serialize;
+
+/// A config item linked to a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigLink {
+    #[serde(rename = "itemId")]
+    pub item_id: i64,
+    /// As entered, `~` not expanded
+    pub path: String,
+}
+
+/// A config item whose file on disk no longer matches its content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigDrift {
+    #[serde(rename = "itemId")]
+    pub item_id: i64,
+    pub title: String,
+    /// As stored in the link
+    pub path: String,
+    /// The file is gone or unreadable
+    pub missing: bool,
+    /// The file was edited since the item last matched it
+    #[serde(rename = "fileChanged")]
+    pub file_changed: bool,
+    /// The item was edited in the vault and not written back yet
+    #[serde(rename = "itemChanged")]
+    pub item_changed: bool,
+}
+
+/// What writing a config item back to its file would change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigWriteBackPreview {
+    #[serde(rename = "itemId")]
+    pub item_id: i64,
+    pub path: String,
+    /// False when the write would create the file
+    pub exists: bool,
+    /// Unified diff from the file to the item content, empty when they match
+    pub diff: String,
+}
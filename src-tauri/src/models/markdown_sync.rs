@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// What one pass of the Markdown folder sync did
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MarkdownSyncReport {
+    /// Notes written to new files
+    pub exported: usize,
+    /// Files that became new notes
+    pub imported: usize,
+    /// Files rewritten after the note changed in the vault
+    #[serde(rename = "updatedFiles")]
+    pub updated_files: usize,
+    /// Notes updated after their file was edited
+    #[serde(rename = "updatedItems")]
+    pub updated_items: usize,
+    /// Files removed because their note was deleted
+    #[serde(rename = "deletedFiles")]
+    pub deleted_files: usize,
+    /// Notes moved to the trash because their file was deleted
+    #[serde(rename = "deletedItems")]
+    pub deleted_items: usize,
+    /// Notes changed on both sides since the last sync, left as they are
+    pub conflicts: Vec<MarkdownSyncConflict>,
+}
+
+impl MarkdownSyncReport {
+    pub fn changed(&self) -> bool {
+        self.exported
+            + self.imported
+            + self.updated_files
+            + self.updated_items
+            + self.deleted_files
+            + self.deleted_items
+            > 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MarkdownSyncConflict {
+    #[serde(rename = "itemId")]
+    pub item_id: i64,
+    pub title: String,
+    /// Relative to the synced folder
+    pub path: String,
+    #[serde(rename = "itemUpdatedAt")]
+    pub item_updated_at: i64,
+    #[serde(rename = "fileModifiedAt")]
+    pub file_modified_at: i64,
+}
+
+/// Which version wins when a conflict is resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncSide {
+    Vault,
+    File,
+}
@@ -1,11 +1,17 @@
 pub mod config;
 pub mod documentation;
+pub mod linked_config;
+pub mod markdown_sync;
+pub mod vault;
 
 use serde::{Deserialize, Serialize};
 
 pub use documentation::*;
+pub use linked_config::*;
+pub use markdown_sync::*;
+pub use vault::*;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ItemType {
     Snippet,
@@ -30,6 +36,44 @@ pub struct Item {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// A previous version of an item's text fields
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemRevision {
+    pub id: i64,
+    #[serde(rename = "itemId")]
+    pub item_id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub content: String,
+    pub metadata: Option<serde_json::Value>,
+    /// When this version was written
+    #[serde(rename = "savedAt")]
+    pub saved_at: i64,
+    /// When a later update replaced it
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrashKind {
+    Item,
+    Documentation,
+}
+
+/// An item or an installed documentation version waiting in the trash
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashEntry {
+    pub kind: TrashKind,
+    pub id: i64,
+    pub title: String,
+    /// Set for items only
+    #[serde(rename = "itemType")]
+    pub item_type: Option<ItemType>,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tag {
     pub id: i64,
@@ -81,7 +125,7 @@ pub struct UpdateItemDto {
     pub tag_ids: Option<Vec<i64>>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[allow(dead_code)]
 pub struct SearchQuery {
     pub query: String,
@@ -91,10 +135,25 @@ pub struct SearchQuery {
     pub tag_ids: Option<Vec<i64>>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    /// Opaque `nextCursor` from the previous page
+    pub cursor: Option<String>,
+    /// Only entries of these installed documentations (any version); items are left out
+    #[serde(rename = "docIds")]
+    pub doc_ids: Option<Vec<i64>>,
+    /// Only doc entries at this path or below it, e.g. `api/fs`
+    #[serde(rename = "pathPrefix")]
+    pub path_prefix: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SearchResult {
     pub items: Vec<ItemWithTags>,
     pub total: i64,
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<String>,
+    /// Query the results are for when misspelled words had to be replaced
+    #[serde(rename = "correctedQuery")]
+    pub corrected_query: Option<String>,
 }
@@ -0,0 +1,82 @@
+use super::ItemType;
+use serde::{Deserialize, Serialize};
+
+/// Version of the JSON bundle layout written by this build
+pub const VAULT_BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VaultFormat {
+    /// One JSON file with items, tags and their links
+    Json,
+    /// A folder with one Markdown file per item, fields in YAML front-matter
+    Markdown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnippetFormat {
+    /// `.code-snippets` / `<language>.json` user snippets
+    VsCode,
+    /// Live template XML of IntelliJ-based IDEs
+    JetBrains,
+}
+
+/// What an import does with an item that has the same type and title as an existing one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportConflict {
+    Skip,
+    Overwrite,
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VaultImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultBundle {
+    pub version: u32,
+    #[serde(rename = "exportedAt")]
+    pub exported_at: i64,
+    pub items: Vec<BundleItem>,
+    pub tags: Vec<BundleTag>,
+    #[serde(rename = "itemTags")]
+    pub item_tags: Vec<BundleItemTag>,
+}
+
+/// Ids in a bundle only link its items and tags, an import assigns new ones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleItem {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub item_type: ItemType,
+    pub title: String,
+    pub description: Option<String>,
+    pub content: String,
+    pub metadata: Option<serde_json::Value>,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleTag {
+    pub id: i64,
+    pub name: String,
+    #[serde(rename = "colorCode")]
+    pub color_code: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleItemTag {
+    #[serde(rename = "itemId")]
+    pub item_id: i64,
+    #[serde(rename = "tagId")]
+    pub tag_id: i64,
+}
@@ -0,0 +1,120 @@
+use anyhow::Result;
+use dev_vault_lib::domain::parsers::custom_sources::sources_dir;
+use dev_vault_lib::domain::parsers::doc_registry::{
+    get_definition_by_name, get_github_config_by_name,
+};
+use dev_vault_lib::domain::parsers::{
+    add_custom_source, get_available_documentations, load_custom_sources, remove_custom_source,
+    SourceFormat,
+};
+use uuid::Uuid;
+
+const ACME_TOML: &str = r#"
+kind = "scraper"
+name = "acme"
+display_name = "Acme Framework"
+version = "2.1"
+base_url = "https://docs.acme.internal/"
+
+[options]
+initial_paths = ["guide", "api"]
+skip_patterns = ["^blog", "^changelog"]
+only_patterns = ["^guide", "^api"]
+max_pages = 50
+
+[selectors]
+title = "h1"
+content = "article"
+links = "nav a, article a"
+remove_selectors = [".sidebar"]
+"#;
+
+const TOOLKIT_JSON: &str = r#"{
+  "kind": "github",
+  "name": "toolkit",
+  "display_name": "Toolkit",
+  "version": "main",
+  "base_url": "https://github.com/acme/toolkit/tree/main/docs"
+}"#;
+
+#[test]
+fn custom_sources_load_validate_and_merge() -> Result<()> {
+    let app_dir = std::env::temp_dir().join(format!("devvault_sources_{}", Uuid::new_v4()));
+    let dir = sources_dir(&app_dir);
+    std::fs::create_dir_all(&dir)?;
+
+    std::fs::write(dir.join("acme.toml"), ACME_TOML)?;
+    std::fs::write(dir.join("toolkit.json"), TOOLKIT_JSON)?;
+    std::fs::write(
+        dir.join("bad-regex.toml"),
+        ACME_TOML
+            .replace("\"acme\"", "\"bad-regex\"")
+            .replace("^blog", "(unclosed"),
+    )?;
+    std::fs::write(
+        dir.join("builtin.toml"),
+        ACME_TOML.replace("\"acme\"", "\"react\""),
+    )?;
+    std::fs::write(
+        dir.join("not-github.json"),
+        TOOLKIT_JSON
+            .replace("toolkit\"", "not-github\"")
+            .replace("github.com", "gitlab.com"),
+    )?;
+    std::fs::write(dir.join("notes.txt"), "ignored")?;
+
+    let report = load_custom_sources(&app_dir);
+    let names: Vec<&str> = report.sources.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["acme", "toolkit"]);
+
+    let mut failed: Vec<&str> = report.errors.iter().map(|e| e.file.as_str()).collect();
+    failed.sort();
+    assert_eq!(
+        failed,
+        vec!["bad-regex.toml", "builtin.toml", "not-github.json"]
+    );
+    let builtin_error = report
+        .errors
+        .iter()
+        .find(|e| e.file == "builtin.toml")
+        .unwrap();
+    assert!(builtin_error.message.contains("built-in"));
+
+    let acme = get_definition_by_name("acme").expect("custom scraper source");
+    assert_eq!(acme.options.initial_paths, vec!["guide", "api"]);
+    assert!(acme.options.should_skip("blog/post"));
+    assert!(!acme.options.should_skip("guide/intro"));
+    assert_eq!(acme.options.max_pages, Some(50));
+    assert_eq!(acme.options.max_depth, Some(3));
+    assert_eq!(acme.selectors.remove_selectors, vec![".sidebar"]);
+    assert!(get_github_config_by_name("toolkit").is_some());
+
+    let available = get_available_documentations();
+    assert!(available.iter().any(|doc| doc.name == "acme"));
+    assert!(available.iter().any(|doc| doc.name == "toolkit"));
+    assert!(available.iter().any(|doc| doc.name == "react"));
+
+    let invalid = add_custom_source(
+        &app_dir,
+        &TOOLKIT_JSON.replace("toolkit\"", "Bad Name\""),
+        SourceFormat::Json,
+    );
+    assert!(invalid.is_err());
+
+    let report = add_custom_source(
+        &app_dir,
+        &ACME_TOML.replace("\"2.1\"", "\"3.0\""),
+        SourceFormat::Toml,
+    )?;
+    assert_eq!(report.sources.len(), 2);
+    assert_eq!(get_definition_by_name("acme").unwrap().version, "3.0");
+
+    let report = remove_custom_source(&app_dir, "toolkit")?;
+    assert!(report.sources.iter().all(|s| s.name != "toolkit"));
+    assert!(!dir.join("toolkit.json").exists());
+    assert!(get_github_config_by_name("toolkit").is_none());
+    assert!(remove_custom_source(&app_dir, "toolkit").is_err());
+
+    std::fs::remove_dir_all(&app_dir)?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+mod local_site;
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::parsers::{
+    add_custom_source, ScrapeCancelled, ScrapeProgress, SourceFormat,
+};
+use dev_vault_lib::domain::DocumentationManager;
+use local_site::{page, source_toml, LocalSite};
+use test_support::TestDb;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn cancelled_install_resumes_from_checkpoint() -> Result<()> {
+    let db = TestDb::new("doc_install_resume").await?;
+    let manager = DocumentationManager::new(db.storage.pool.clone());
+
+    let server = LocalSite::start().await?;
+    server.set_page(
+        "/",
+        page(
+            "Local Site",
+            "index",
+            &["guide/alpha", "guide/beta", "guide/gamma"],
+        ),
+    );
+    server.set_page("/guide/alpha", page("Alpha", "alpha content", &[]));
+    server.set_page("/guide/beta", page("Beta", "beta content", &[]));
+    server.set_page("/guide/gamma", page("Gamma", "gamma content", &[]));
+
+    let first_cancel = CancellationToken::new();
+    server.cancel_on("/guide/beta", first_cancel.clone());
+
+    let app_dir = std::env::temp_dir().join(format!("devvault_resume_{}", Uuid::new_v4()));
+    add_custom_source(
+        &app_dir,
+        &source_toml("local-site", &server.base_url),
+        SourceFormat::Toml,
+    )?;
+
+    let (progress_tx, mut progress_rx) = mpsc::channel::<ScrapeProgress>(100);
+    let progress = tokio::spawn(async move {
+        let mut statuses = Vec::new();
+        while let Some(progress) = progress_rx.recv().await {
+            statuses.push(serde_json::to_value(progress.status).unwrap());
+        }
+        statuses
+    });
+
+    let cancelled = manager
+        .install_documentation_with_progress("local-site", None, progress_tx, first_cancel)
+        .await
+        .expect_err("install is cancelled on the second page");
+    assert!(cancelled.is::<ScrapeCancelled>());
+    assert_eq!(
+        progress.await?.last(),
+        Some(&serde_json::json!("cancelled"))
+    );
+    assert!(manager.list_installed_documentations().await?.is_empty());
+
+    let checkpoints = manager.list_install_checkpoints().await?;
+    assert_eq!(checkpoints.len(), 1);
+    assert_eq!(checkpoints[0].status, "cancelled");
+    assert_eq!(checkpoints[0].visited_count, 2);
+    assert_eq!(checkpoints[0].queued_count, 2);
+    assert!(checkpoints[0].entries_count >= 2);
+
+    let (progress_tx, _progress_rx) = mpsc::channel(100);
+    let doc = manager
+        .install_documentation_with_progress(
+            "local-site",
+            None,
+            progress_tx,
+            CancellationToken::new(),
+        )
+        .await?;
+
+    // Pages fetched before the cancellation are not requested again
+    assert_eq!(server.hits("/"), 1);
+    assert_eq!(server.hits("/guide/alpha"), 1);
+    assert_eq!(server.hits("/guide/beta"), 2);
+    assert_eq!(server.hits("/guide/gamma"), 1);
+
+    let paths: Vec<String> =
+        sqlx::query_scalar("SELECT path FROM doc_entries WHERE doc_id = ?1 ORDER BY path")
+            .bind(doc.id)
+            .fetch_all(&db.storage.pool)
+            .await?;
+    for expected in ["guide", "guide/alpha", "guide/beta", "guide/gamma"] {
+        assert_eq!(
+            paths.iter().filter(|path| *path == expected).count(),
+            1,
+            "{expected} in {paths:?}"
+        );
+    }
+    assert!(manager.list_install_checkpoints().await?.is_empty());
+
+    std::fs::remove_dir_all(&app_dir)?;
+    sqlx::query("DELETE FROM documentations")
+        .execute(&db.storage.pool)
+        .await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
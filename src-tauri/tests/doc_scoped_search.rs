@@ -0,0 +1,188 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::DocumentationManager;
+use dev_vault_lib::models::{CreateItemDto, ItemType, SearchQuery};
+use test_support::{entry, TestDb};
+
+async fn found_paths(db: &TestDb, query: SearchQuery) -> Result<Vec<String>> {
+    let result = db
+        .search_engine()
+        .with_weights(1.0, 0.0)
+        .search(SearchQuery {
+            limit: Some(50),
+            ..query
+        })
+        .await?;
+    let mut paths: Vec<String> = result
+        .items
+        .into_iter()
+        .map(|item| item.item.description.unwrap_or_default())
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+#[tokio::test]
+async fn search_is_scoped_to_docs_and_sections() -> Result<()> {
+    let db = TestDb::new("doc_scoped_search").await?;
+    db.storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Note,
+            title: "Reading files".to_string(),
+            description: None,
+            content: "read a file line by line".to_string(),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+
+    let manager = DocumentationManager::new(db.storage.pool.clone());
+    let rust = manager
+        .install_documentation_with_entries(
+            "rust",
+            None,
+            vec![
+                entry("std/fs", "read a file into a string"),
+                entry("std/io", "read a file through a buffer"),
+            ],
+        )
+        .await?;
+    let node = manager
+        .install_documentation_with_entries(
+            "nodejs",
+            None,
+            vec![
+                entry("api/fs", "read a file asynchronously"),
+                entry("api/fs/promises", "read a file with promises"),
+                entry("api/fsevents", "read a file change event"),
+                entry("api/http", "read a file from a request"),
+            ],
+        )
+        .await?;
+
+    let everything = found_paths(
+        &db,
+        SearchQuery {
+            query: "read file".to_string(),
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(everything.len(), 7);
+
+    let rust_only = found_paths(
+        &db,
+        SearchQuery {
+            query: "read file".to_string(),
+            doc_ids: Some(vec![rust.id]),
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(rust_only, vec!["std/fs", "std/io"]);
+
+    // The section and what is under it, but not a sibling sharing the prefix
+    let fs_section = found_paths(
+        &db,
+        SearchQuery {
+            query: "read file".to_string(),
+            doc_ids: Some(vec![node.id]),
+            path_prefix: Some("api/fs/".to_string()),
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(fs_section, vec!["api/fs", "api/fs/promises"]);
+
+    let across_docs = found_paths(
+        &db,
+        SearchQuery {
+            query: "read file".to_string(),
+            doc_ids: Some(vec![rust.id, node.id]),
+            path_prefix: Some("std".to_string()),
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(across_docs, vec!["std/fs", "std/io"]);
+
+    // A filter-only query lists the whole section
+    let listing = found_paths(
+        &db,
+        SearchQuery {
+            query: String::new(),
+            path_prefix: Some("api/fs".to_string()),
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(listing, vec!["api/fs", "api/fs/promises"]);
+
+    // An empty list is no filter at all
+    let unscoped = found_paths(
+        &db,
+        SearchQuery {
+            query: "read file".to_string(),
+            doc_ids: Some(Vec::new()),
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(unscoped.len(), 7);
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn doc_ids_reach_inactive_versions() -> Result<()> {
+    let db = TestDb::new("doc_scoped_versions").await?;
+    let manager = DocumentationManager::new(db.storage.pool.clone());
+    let main = manager
+        .install_documentation_with_entries(
+            "python",
+            None,
+            vec![entry("library/asyncio", "event loop on main")],
+        )
+        .await?;
+    manager
+        .install_documentation_with_entries(
+            "python",
+            Some("3.12"),
+            vec![entry("library/asyncio", "event loop in 3.12")],
+        )
+        .await?;
+
+    let active = found_paths(
+        &db,
+        SearchQuery {
+            query: "event loop".to_string(),
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(active.len(), 1);
+
+    let result = db
+        .search_engine()
+        .with_weights(1.0, 0.0)
+        .search(SearchQuery {
+            query: "event loop".to_string(),
+            doc_ids: Some(vec![main.id]),
+            ..Default::default()
+        })
+        .await?;
+    assert_eq!(result.total, 1);
+    assert_eq!(
+        result.items[0].item.metadata.as_ref().unwrap()["docId"],
+        serde_json::json!(main.id)
+    );
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -0,0 +1,163 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::DocumentationManager;
+use dev_vault_lib::models::DocUpdateSummary;
+use sqlx::Row;
+use test_support::{entry, TestDb};
+
+async fn entry_ids(db: &TestDb, doc_id: i64) -> Result<Vec<(String, i64, i64)>> {
+    let rows =
+        sqlx::query("SELECT path, id, created_at FROM doc_entries WHERE doc_id = ?1 ORDER BY path")
+            .bind(doc_id)
+            .fetch_all(&db.storage.pool)
+            .await?;
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("path"), row.get("id"), row.get("created_at")))
+        .collect())
+}
+
+#[tokio::test]
+async fn update_applies_only_the_diff() -> Result<()> {
+    let db = TestDb::new("doc_updates").await?;
+    let manager = DocumentationManager::new(db.storage.pool.clone());
+
+    let doc = manager
+        .install_documentation_with_entries(
+            "rust",
+            None,
+            vec![
+                entry("book/ownership", "borrowing rules"),
+                entry("book/traits", "trait objects"),
+                entry("book/macros", "macro_rules"),
+            ],
+        )
+        .await?;
+
+    // Simulate rows stored before content hashes existed
+    sqlx::query("UPDATE doc_entries SET created_at = 1, content_hash = '' WHERE doc_id = ?1")
+        .bind(doc.id)
+        .execute(&db.storage.pool)
+        .await?;
+    let before = entry_ids(&db, doc.id).await?;
+
+    let update = manager
+        .update_documentation_with_entries(
+            doc.id,
+            vec![
+                entry("book/ownership", "borrowing rules"),
+                entry("book/traits", "trait objects and generics"),
+                entry("book/async", "futures and executors"),
+                entry("book/async", "duplicate path from the scraper"),
+            ],
+        )
+        .await?;
+
+    assert_eq!(
+        update.summary,
+        DocUpdateSummary {
+            added: 1,
+            changed: 1,
+            removed: 1,
+            unchanged: 1,
+        }
+    );
+
+    let after = entry_ids(&db, doc.id).await?;
+    let find = |entries: &[(String, i64, i64)], path: &str| {
+        entries
+            .iter()
+            .find(|(entry_path, _, _)| entry_path == path)
+            .map(|(_, id, created_at)| (*id, *created_at))
+    };
+    assert_eq!(after.len(), 3);
+    assert_eq!(
+        find(&after, "book/ownership"),
+        find(&before, "book/ownership")
+    );
+    assert_eq!(find(&after, "book/traits"), find(&before, "book/traits"));
+    assert!(find(&after, "book/macros").is_none());
+
+    let search_rows = |text: &'static str| {
+        let pool = db.storage.pool.clone();
+        async move {
+            sqlx::query("SELECT rowid AS id FROM docs_fts WHERE docs_fts MATCH ?1")
+                .bind(text)
+                .fetch_all(&pool)
+                .await
+                .map(|rows| {
+                    rows.iter()
+                        .map(|row| row.get::<i64, _>("id"))
+                        .collect::<Vec<_>>()
+                })
+        }
+    };
+    assert_eq!(
+        search_rows("generics").await?,
+        vec![find(&after, "book/traits").unwrap().0]
+    );
+    assert!(search_rows("macro_rules").await?.is_empty());
+    assert_eq!(search_rows("executors").await?.len(), 1);
+
+    let repeat = manager
+        .update_documentation_with_entries(
+            doc.id,
+            vec![
+                entry("book/ownership", "borrowing rules"),
+                entry("book/traits", "trait objects and generics"),
+                entry("book/async", "futures and executors"),
+            ],
+        )
+        .await?;
+    assert_eq!(
+        repeat.summary,
+        DocUpdateSummary {
+            unchanged: 3,
+            ..Default::default()
+        }
+    );
+
+    sqlx::query("DELETE FROM documentations")
+        .execute(&db.storage.pool)
+        .await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn update_does_not_take_a_version_installed_side_by_side() -> Result<()> {
+    let db = TestDb::new("doc_updates_side_by_side").await?;
+    let manager = DocumentationManager::new(db.storage.pool.clone());
+
+    let old = manager
+        .install_documentation_with_entries("rust", None, vec![entry("book/ownership", "old")])
+        .await?;
+    // An install from before the definition moved on, next to one of the current version
+    sqlx::query("UPDATE documentations SET version = 'older' WHERE id = ?1")
+        .bind(old.id)
+        .execute(&db.storage.pool)
+        .await?;
+    manager
+        .install_documentation_with_entries("rust", None, vec![entry("book/ownership", "new")])
+        .await?;
+
+    let error = manager
+        .update_documentation_with_entries(old.id, vec![entry("book/ownership", "newer")])
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("already installed"), "{error}");
+    let version: String = sqlx::query_scalar("SELECT version FROM documentations WHERE id = ?1")
+        .bind(old.id)
+        .fetch_one(&db.storage.pool)
+        .await?;
+    assert_eq!(version, "older");
+    assert_eq!(entry_ids(&db, old.id).await?.len(), 1);
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -0,0 +1,174 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::{DocumentationManager, Storage};
+use sqlx::sqlite::SqlitePool;
+use sqlx::{Executor, Row};
+use test_support::{entry, found_ids, TestDb};
+
+async fn entry_id(db: &TestDb, doc_id: i64, path: &str) -> Result<i64> {
+    Ok(
+        sqlx::query_scalar("SELECT -id FROM doc_entries WHERE doc_id = ?1 AND path = ?2")
+            .bind(doc_id)
+            .bind(path)
+            .fetch_one(&db.storage.pool)
+            .await?,
+    )
+}
+
+#[tokio::test]
+async fn versions_install_side_by_side_and_switch() -> Result<()> {
+    let db = TestDb::new("doc_versions").await?;
+    let manager = DocumentationManager::new(db.storage.pool.clone());
+
+    let main = manager
+        .install_documentation_with_entries(
+            "python",
+            None,
+            vec![entry("library/asyncio", "asyncio event loop on main")],
+        )
+        .await?;
+    assert_eq!(main.version, "main");
+    assert!(main.is_active);
+
+    let v312 = manager
+        .install_documentation_with_entries(
+            "python",
+            Some("3.12"),
+            vec![
+                entry("library/asyncio", "asyncio event loop in 3.12"),
+                entry("library/tomllib", "tomllib parser"),
+            ],
+        )
+        .await?;
+    assert_eq!(v312.version, "3.12");
+    assert_eq!(
+        v312.source_url,
+        "https://github.com/python/cpython/tree/3.12/Doc"
+    );
+    assert!(v312.is_active);
+
+    let duplicate = manager
+        .install_documentation_with_entries("python", Some("3.12"), Vec::new())
+        .await;
+    assert!(duplicate
+        .unwrap_err()
+        .to_string()
+        .contains("already installed"));
+    let unknown = manager
+        .install_documentation_with_entries("python", Some("2.7"), Vec::new())
+        .await;
+    assert!(unknown.unwrap_err().to_string().contains("not available"));
+
+    let versions = manager.list_doc_versions("python").await?;
+    let versions: Vec<(&str, bool)> = versions
+        .iter()
+        .map(|doc| (doc.version.as_str(), doc.is_active))
+        .collect();
+    assert_eq!(versions, vec![("3.12", true), ("main", false)]);
+
+    let main_asyncio = entry_id(&db, main.id, "library/asyncio").await?;
+    let v312_asyncio = entry_id(&db, v312.id, "library/asyncio").await?;
+
+    // Only the active version is searched unless a version is pinned
+    assert_eq!(found_ids(&db, "asyncio").await?, vec![v312_asyncio]);
+    assert_eq!(
+        found_ids(&db, "asyncio doc:python@main").await?,
+        vec![main_asyncio]
+    );
+    assert_eq!(
+        found_ids(&db, "asyncio doc:Python@3.12").await?,
+        vec![v312_asyncio]
+    );
+
+    assert_eq!(
+        manager.resolve_doc_version(main.id, Some("3.12")).await?,
+        v312.id
+    );
+    assert_eq!(manager.resolve_doc_version(main.id, None).await?, main.id);
+    assert!(manager
+        .resolve_doc_version(main.id, Some("3.11"))
+        .await
+        .is_err());
+    let tree = manager
+        .get_doc_tree_level(
+            manager.resolve_doc_version(main.id, Some("3.12")).await?,
+            None,
+        )
+        .await?;
+    assert_eq!(tree.len(), 2);
+
+    let switched = manager.set_active_version(main.id).await?;
+    assert!(switched.is_active);
+    assert_eq!(
+        manager.find_documentation("python", None).await?.id,
+        main.id
+    );
+    assert_eq!(found_ids(&db, "asyncio").await?, vec![main_asyncio]);
+
+    manager.delete_documentation(main.id).await?;
+    let remaining = manager.find_documentation("python", None).await?;
+    assert_eq!(remaining.id, v312.id);
+    assert!(remaining.is_active);
+
+    sqlx::query("DELETE FROM documentations")
+        .execute(&db.storage.pool)
+        .await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn migration_keeps_single_version_installs() -> Result<()> {
+    let db_path = test_support::temp_db_path("doc_versions_migration");
+
+    // Schema as it was before versions could coexist
+    let legacy = SqlitePool::connect(&format!("sqlite:{}?mode=rwc", db_path.display())).await?;
+    legacy
+        .execute(include_str!("../migrations/001_initial_schema.sql"))
+        .await?;
+    legacy
+        .execute(include_str!("../migrations/002_documentation_system.sql"))
+        .await?;
+    legacy
+        .execute(
+            "INSERT INTO documentations (name, display_name, version, source_url, installed_at, updated_at)
+             VALUES ('python', 'Python', 'main', 'https://github.com/python/cpython/tree/main/Doc', 1, 1);
+             INSERT INTO doc_entries (doc_id, path, title, content, created_at)
+             VALUES (1, 'library/asyncio', 'asyncio', 'event loop', 1);",
+        )
+        .await?;
+    legacy.close().await;
+
+    let storage = Storage::new(db_path.clone()).await?;
+    let manager = DocumentationManager::new(storage.pool.clone());
+
+    let installed = manager.list_installed_documentations().await?;
+    assert_eq!(installed.len(), 1);
+    assert!(installed[0].is_active);
+
+    let entries: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM doc_entries")
+        .fetch_one(&storage.pool)
+        .await?;
+    assert_eq!(entries, 1);
+
+    manager
+        .install_documentation_with_entries("python", Some("3.13"), Vec::new())
+        .await?;
+    assert_eq!(manager.list_doc_versions("python").await?.len(), 2);
+
+    // The rebuilt table keeps its foreign key, purging cascades to entries
+    manager.delete_documentation(installed[0].id).await?;
+    manager.purge_documentation(installed[0].id).await?;
+    let row = sqlx::query("SELECT COUNT(*) AS count FROM doc_entries")
+        .fetch_one(&storage.pool)
+        .await?;
+    assert_eq!(row.get::<i64, _>("count"), 0);
+
+    drop(manager);
+    drop(storage);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::models::{ItemType, SearchQuery, SearchResult};
+use test_support::{item, TestDb};
+
+async fn search(db: &TestDb, text: &str) -> Result<SearchResult> {
+    db.search_engine()
+        .with_weights(1.0, 0.0)
+        .search(SearchQuery {
+            query: text.to_string(),
+            limit: Some(10),
+            ..Default::default()
+        })
+        .await
+}
+
+fn ids(result: &SearchResult) -> Vec<i64> {
+    result.items.iter().map(|item| item.item.id).collect()
+}
+
+#[tokio::test]
+async fn identifiers_are_found_by_prefix_and_substring() -> Result<()> {
+    let db = TestDb::new("fuzzy_substring").await?;
+    let hook = db
+        .storage
+        .create_item(item(
+            ItemType::Note,
+            "React state hook",
+            "const [count, setCount] = useState(0);",
+        ))
+        .await?;
+    db.storage
+        .create_item(item(ItemType::Note, "Effects", "useEffect(() => {}, [])"))
+        .await?;
+
+    let prefix = search(&db, "usestat").await?;
+    assert_eq!(ids(&prefix), vec![hook]);
+    assert!(prefix.items[0].highlights.as_ref().unwrap()[0].contains("useStat"));
+
+    // Inside the token, where neither words nor prefixes reach
+    let inner = search(&db, "etCount").await?;
+    assert_eq!(ids(&inner), vec![hook]);
+    assert!(inner.items[0].highlights.as_ref().unwrap()[0].contains("**"));
+    assert_eq!(inner.corrected_query, None);
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn misspelled_words_are_corrected() -> Result<()> {
+    let db = TestDb::new("fuzzy_typos").await?;
+    let kubectl = db
+        .storage
+        .create_item(item(
+            ItemType::Note,
+            "Cluster access",
+            "kubectl get pods --namespace kube-system",
+        ))
+        .await?;
+    db.storage
+        .create_item(item(
+            ItemType::Note,
+            "Docker cleanup",
+            "docker system prune --volumes",
+        ))
+        .await?;
+
+    let result = search(&db, "kubctl pods").await?;
+    assert_eq!(ids(&result), vec![kubectl]);
+    assert_eq!(result.corrected_query.as_deref(), Some("kubectl pods"));
+
+    // Exact hits are returned as they are
+    let exact = search(&db, "docker").await?;
+    assert_eq!(exact.total, 1);
+    assert_eq!(exact.corrected_query, None);
+
+    // Nothing close enough in the vocabulary
+    let unknown = search(&db, "terraform").await?;
+    assert_eq!(unknown.total, 0);
+    assert_eq!(unknown.corrected_query, None);
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
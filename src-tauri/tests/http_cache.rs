@@ -0,0 +1,153 @@
+mod local_site;
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::parsers::{add_custom_source, SourceFormat, HTTP_CACHE_DIR};
+use dev_vault_lib::domain::DocumentationManager;
+use local_site::{page, source_toml, LocalSite};
+use test_support::TestDb;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+async fn entry_content(db: &TestDb, doc_id: i64, path: &str) -> Result<String> {
+    Ok(
+        sqlx::query_scalar("SELECT content FROM doc_entries WHERE doc_id = ?1 AND path = ?2")
+            .bind(doc_id)
+            .bind(path)
+            .fetch_one(&db.storage.pool)
+            .await?,
+    )
+}
+
+#[tokio::test]
+async fn updates_revalidate_and_rebuild_offline() -> Result<()> {
+    let db = TestDb::new("http_cache").await?;
+    let app_dir = std::env::temp_dir().join(format!("devvault_http_cache_{}", Uuid::new_v4()));
+    let manager = DocumentationManager::new(db.storage.pool.clone())
+        .with_http_cache(app_dir.join(HTTP_CACHE_DIR));
+
+    let server = LocalSite::start().await?;
+    server.set_page_with_etag(
+        "/",
+        page("Cached Site", "index", &["guide/alpha", "guide/beta"]),
+        Some("\"index-1\""),
+    );
+    server.set_page_with_etag(
+        "/guide/alpha",
+        page("Alpha", "alpha content", &[]),
+        Some("\"alpha-1\""),
+    );
+    server.set_page_with_etag(
+        "/guide/beta",
+        page("Beta", "beta content", &[]),
+        Some("\"beta-1\""),
+    );
+
+    add_custom_source(
+        &app_dir,
+        &source_toml("cached-site", &server.base_url),
+        SourceFormat::Toml,
+    )?;
+
+    let (progress_tx, _progress_rx) = mpsc::channel(100);
+    let doc = manager
+        .install_documentation_with_progress(
+            "cached-site",
+            None,
+            progress_tx,
+            CancellationToken::new(),
+        )
+        .await?;
+    assert_eq!(manager.http_cache("cached-site").unwrap().len(), 3);
+
+    // Nothing changed upstream: every page is revalidated and answered with 304
+    let seen = server.requests().len();
+    let (progress_tx, _progress_rx) = mpsc::channel(100);
+    let update = manager
+        .update_documentation_with_progress(doc.id, progress_tx)
+        .await?;
+    let revalidations: Vec<_> = server.requests()[seen..]
+        .iter()
+        .filter(|request| request.path != "/robots.txt")
+        .cloned()
+        .collect();
+    assert_eq!(revalidations.len(), 3);
+    assert!(revalidations.iter().all(|request| request.status == 304));
+    assert_eq!(
+        revalidations
+            .iter()
+            .find(|request| request.path == "/guide/alpha")
+            .and_then(|request| request.headers.get("if-none-match"))
+            .map(String::as_str),
+        Some("\"alpha-1\"")
+    );
+    assert_eq!(update.summary.added, 0);
+    assert_eq!(update.summary.changed, 0);
+    assert_eq!(update.summary.removed, 0);
+    assert!(update.summary.unchanged >= 3);
+
+    // A new ETag means a full response, which replaces the cached copy
+    server.set_page_with_etag(
+        "/guide/alpha",
+        page("Alpha", "alpha content, revised", &[]),
+        Some("\"alpha-2\""),
+    );
+    let seen = server.requests().len();
+    let (progress_tx, _progress_rx) = mpsc::channel(100);
+    let update = manager
+        .update_documentation_with_progress(doc.id, progress_tx)
+        .await?;
+    let statuses: Vec<(String, u16)> = server.requests()[seen..]
+        .iter()
+        .map(|request| (request.path.clone(), request.status))
+        .collect();
+    assert!(statuses.contains(&("/guide/alpha".to_string(), 200)));
+    assert!(statuses.contains(&("/guide/beta".to_string(), 304)));
+    assert_eq!(update.summary.changed, 1);
+    assert!(entry_content(&db, doc.id, "guide/alpha")
+        .await?
+        .contains("revised"));
+
+    // Tuning the selectors and rebuilding never touches the network
+    let seen = server.requests().len();
+    assert!(entry_content(&db, doc.id, "").await?.contains("guide/beta"));
+    add_custom_source(
+        &app_dir,
+        &source_toml("cached-site", &server.base_url)
+            .replace("content = \"article\"", "content = \"p\""),
+        SourceFormat::Toml,
+    )?;
+    let (progress_tx, _progress_rx) = mpsc::channel(100);
+    let rebuilt = manager
+        .rebuild_documentation_from_cache(doc.id, progress_tx)
+        .await?;
+    assert_eq!(server.requests().len(), seen);
+    assert!(rebuilt.summary.changed >= 1);
+    assert_eq!(rebuilt.summary.removed, 0);
+    let index = entry_content(&db, doc.id, "").await?;
+    assert!(index.contains("index"));
+    assert!(!index.contains("guide/beta"));
+    assert!(entry_content(&db, doc.id, "guide/alpha")
+        .await?
+        .contains("revised"));
+
+    assert_eq!(manager.clear_http_cache("cached-site")?, 3);
+    let (progress_tx, _progress_rx) = mpsc::channel(100);
+    let without_cache = manager
+        .rebuild_documentation_from_cache(doc.id, progress_tx)
+        .await;
+    assert!(without_cache
+        .unwrap_err()
+        .to_string()
+        .contains("No cached pages"));
+
+    std::fs::remove_dir_all(&app_dir)?;
+    sqlx::query("DELETE FROM documentations")
+        .execute(&db.storage.pool)
+        .await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -0,0 +1,146 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::text_diff::unified_diff;
+use dev_vault_lib::models::config::HistoryConfig;
+use dev_vault_lib::models::{CreateItemDto, ItemType, UpdateItemDto};
+use test_support::TestDb;
+
+fn config_item(content: &str) -> CreateItemDto {
+    CreateItemDto {
+        item_type: ItemType::Config,
+        title: "nginx.conf".to_string(),
+        description: None,
+        content: content.to_string(),
+        metadata: Some(serde_json::json!({ "language": "nginx" })),
+        tag_ids: None,
+    }
+}
+
+fn content_update(id: i64, content: &str) -> UpdateItemDto {
+    UpdateItemDto {
+        id,
+        item_type: None,
+        title: None,
+        description: None,
+        content: Some(content.to_string()),
+        metadata: None,
+        tag_ids: None,
+    }
+}
+
+#[tokio::test]
+async fn updates_keep_previous_versions_and_restore_them() -> Result<()> {
+    let db = TestDb::new("item_revisions").await?;
+    let storage = &db.storage;
+    let id = storage
+        .create_item(config_item("worker_processes 1;\nlisten 80;\n"))
+        .await?;
+
+    storage
+        .update_item(content_update(id, "worker_processes 4;\nlisten 80;\n"))
+        .await?;
+    // Autosave of unchanged text and tag-only updates leave no revision
+    storage
+        .update_item(content_update(id, "worker_processes 4;\nlisten 80;\n"))
+        .await?;
+    storage
+        .update_item(UpdateItemDto {
+            tag_ids: Some(Vec::new()),
+            ..content_update(id, "")
+        })
+        .await?;
+
+    let revisions = storage.list_item_revisions(id).await?;
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0].content, "worker_processes 4;\nlisten 80;\n");
+    assert_eq!(revisions[1].content, "worker_processes 1;\nlisten 80;\n");
+    assert_eq!(
+        revisions[1].metadata,
+        Some(serde_json::json!({ "language": "nginx" }))
+    );
+
+    let diff = storage
+        .diff_item_revisions(id, Some(revisions[1].id), Some(revisions[0].id))
+        .await?;
+    assert!(diff.contains("-worker_processes 1;\n+worker_processes 4;\n"));
+    assert!(diff.contains(" listen 80;\n"));
+    assert_eq!(
+        storage
+            .diff_item_revisions(id, Some(revisions[0].id), Some(revisions[0].id))
+            .await?,
+        ""
+    );
+
+    assert!(storage.restore_item_revision(id, revisions[1].id).await?);
+    let restored = storage.get_item(id).await?.unwrap().item;
+    assert_eq!(restored.content, "worker_processes 1;\nlisten 80;\n");
+    // The version that was replaced by the restore is kept as well
+    let revisions = storage.list_item_revisions(id).await?;
+    assert_eq!(revisions.len(), 3);
+    assert_eq!(revisions[0].content, "");
+    assert!(
+        !storage
+            .restore_item_revision(id + 1, revisions[0].id)
+            .await?
+    );
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn retention_keeps_the_newest_revisions() -> Result<()> {
+    let mut db = TestDb::new("item_revisions_retention").await?;
+    db.storage.set_history(HistoryConfig {
+        max_revisions_per_item: 3,
+        max_age_days: 0,
+    });
+    let id = db.storage.create_item(config_item("v0")).await?;
+    for version in 1..=6 {
+        db.storage
+            .update_item(content_update(id, &format!("v{version}")))
+            .await?;
+    }
+
+    let contents: Vec<String> = db
+        .storage
+        .list_item_revisions(id)
+        .await?
+        .into_iter()
+        .map(|revision| revision.content)
+        .collect();
+    assert_eq!(contents, vec!["v5", "v4", "v3"]);
+
+    db.storage.delete_item(id).await?;
+    db.storage.purge_item(id).await?;
+    assert!(db.storage.list_item_revisions(id).await?.is_empty());
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[test]
+fn unified_diff_groups_changes_into_hunks() {
+    let old: String = (1..=20).map(|line| format!("line {line}\n")).collect();
+    let new = old
+        .replace("line 2\n", "line two\n")
+        .replace("line 18\n", "")
+        .replace("line 20\n", "line 20\nline 21\n");
+
+    assert_eq!(
+        unified_diff(&old, &new, "a", "b"),
+        "--- a\n+++ b\n\
+         @@ -1,5 +1,5 @@\n line 1\n-line 2\n+line two\n line 3\n line 4\n line 5\n\
+         @@ -15,6 +15,6 @@\n line 15\n line 16\n line 17\n-line 18\n line 19\n line 20\n+line 21\n"
+    );
+    assert_eq!(unified_diff(&old, &old, "a", "b"), "");
+    assert_eq!(
+        unified_diff("", "added\n", "a", "b"),
+        "--- a\n+++ b\n@@ -0,0 +1 @@\n+added\n"
+    );
+}
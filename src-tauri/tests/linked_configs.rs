@@ -0,0 +1,219 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::models::{ConfigLink, CreateItemDto, ItemType, UpdateItemDto};
+use test_support::TestDb;
+use uuid::Uuid;
+
+async fn create_item(db: &TestDb, item_type: ItemType, title: &str) -> Result<i64> {
+    db.storage
+        .create_item(CreateItemDto {
+            item_type,
+            title: title.to_string(),
+            description: None,
+            content: String::new(),
+            metadata: Some(serde_json::json!({ "language": "lua" })),
+            tag_ids: None,
+        })
+        .await
+}
+
+async fn content(db: &TestDb, id: i64) -> Result<String> {
+    Ok(db.storage.get_item(id).await?.unwrap().item.content)
+}
+
+async fn metadata(db: &TestDb, id: i64) -> Result<serde_json::Value> {
+    Ok(db
+        .storage
+        .get_item(id)
+        .await?
+        .unwrap()
+        .item
+        .metadata
+        .unwrap())
+}
+
+async fn set_content(db: &TestDb, id: i64, content: &str) -> Result<()> {
+    db.storage
+        .update_item(UpdateItemDto {
+            id,
+            item_type: None,
+            title: None,
+            description: None,
+            content: Some(content.to_string()),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn linked_config_follows_its_file() -> Result<()> {
+    let db = TestDb::new("linked_configs").await?;
+    let dir = std::env::temp_dir().join(format!("devvault_linked_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    let file = dir.join("init.lua");
+    std::fs::write(&file, "vim.o.number = true\n")?;
+
+    let id = create_item(&db, ItemType::Config, "nvim").await?;
+    db.storage
+        .link_config_file(id, file.to_str().unwrap())
+        .await?;
+    assert_eq!(content(&db, id).await?, "vim.o.number = true\n");
+    assert_eq!(
+        metadata(&db, id).await?,
+        serde_json::json!({ "language": "lua" })
+    );
+    assert_eq!(
+        db.storage.list_config_links().await?,
+        vec![ConfigLink {
+            item_id: id,
+            path: file.to_str().unwrap().to_string(),
+        }]
+    );
+    assert!(db.storage.refresh_linked_configs().await?.is_empty());
+    assert!(db.storage.config_drift_report().await?.is_empty());
+
+    // Edited on disk: the item follows and keeps the old text in its history
+    std::fs::write(&file, "vim.o.number = false\n")?;
+    assert_eq!(db.storage.refresh_linked_configs().await?, vec![id]);
+    assert_eq!(content(&db, id).await?, "vim.o.number = false\n");
+    assert_eq!(
+        db.storage.list_item_revisions(id).await?[0].content,
+        "vim.o.number = true\n"
+    );
+
+    // Edited in the vault: reported as drift, the file is left alone until written back
+    set_content(&db, id, "vim.o.number = false\nvim.o.wrap = false\n").await?;
+    assert!(db.storage.refresh_linked_configs().await?.is_empty());
+    let drift = db.storage.config_drift_report().await?;
+    assert_eq!(drift.len(), 1);
+    assert_eq!(
+        (
+            drift[0].item_id,
+            drift[0].missing,
+            drift[0].file_changed,
+            drift[0].item_changed
+        ),
+        (id, false, false, true)
+    );
+
+    let preview = db.storage.preview_config_write_back(id).await?;
+    assert!(preview.exists);
+    assert!(preview.diff.contains("\n+vim.o.wrap = false\n"));
+    assert_eq!(std::fs::read_to_string(&file)?, "vim.o.number = false\n");
+
+    db.storage.write_config_back(id).await?;
+    assert_eq!(
+        std::fs::read_to_string(&file)?,
+        "vim.o.number = false\nvim.o.wrap = false\n"
+    );
+    assert!(db.storage.config_drift_report().await?.is_empty());
+    assert!(db.storage.refresh_linked_configs().await?.is_empty());
+
+    std::fs::remove_file(&file)?;
+    let drift = db.storage.config_drift_report().await?;
+    assert!(drift[0].missing);
+    assert!(db.storage.refresh_linked_configs().await?.is_empty());
+
+    db.storage.unlink_config_file(id).await?;
+    assert!(db.storage.config_drift_report().await?.is_empty());
+    assert!(db.storage.list_config_links().await?.is_empty());
+
+    std::fs::remove_dir_all(dir)?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_config_items_can_be_linked() -> Result<()> {
+    let db = TestDb::new("linked_configs_note").await?;
+    let id = create_item(&db, ItemType::Note, "not a config").await?;
+
+    let error = db
+        .storage
+        .link_config_file(id, "/etc/hosts")
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("Only config items"));
+    let error = db.storage.write_config_back(id).await.unwrap_err();
+    assert!(error.to_string().contains("not linked to a file"));
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn edits_on_both_sides_are_not_pulled() -> Result<()> {
+    let db = TestDb::new("linked_configs_conflict").await?;
+    let dir = std::env::temp_dir().join(format!("devvault_linked_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    let file = dir.join("config.toml");
+    std::fs::write(&file, "theme = \"dark\"\n")?;
+
+    let id = create_item(&db, ItemType::Config, "app").await?;
+    db.storage
+        .link_config_file(id, file.to_str().unwrap())
+        .await?;
+    set_content(&db, id, "theme = \"light\"\n").await?;
+    std::fs::write(&file, "theme = \"solarized\"\n")?;
+
+    assert!(db.storage.refresh_linked_configs().await?.is_empty());
+    assert_eq!(content(&db, id).await?, "theme = \"light\"\n");
+    let drift = db.storage.config_drift_report().await?;
+    assert_eq!(
+        (
+            drift[0].missing,
+            drift[0].file_changed,
+            drift[0].item_changed
+        ),
+        (false, true, true)
+    );
+
+    std::fs::remove_dir_all(dir)?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn metadata_cannot_link_a_file() -> Result<()> {
+    let db = TestDb::new("linked_configs_metadata").await?;
+    let dir = std::env::temp_dir().join(format!("devvault_linked_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    let file = dir.join("secret");
+    std::fs::write(&file, "do not read\n")?;
+    let link = serde_json::json!({ "language": "lua", "sourcePath": file.to_str().unwrap() });
+
+    let id = db
+        .storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Config,
+            title: "planted".to_string(),
+            description: None,
+            content: String::new(),
+            metadata: Some(link.clone()),
+            tag_ids: None,
+        })
+        .await?;
+    // Kept as plain metadata, it links nothing
+    assert_eq!(metadata(&db, id).await?, link);
+    assert!(db.storage.list_config_links().await?.is_empty());
+    assert!(db.storage.refresh_linked_configs().await?.is_empty());
+    assert_eq!(content(&db, id).await?, "");
+    let error = db.storage.write_config_back(id).await.unwrap_err();
+    assert!(error.to_string().contains("not linked to a file"));
+    assert_eq!(std::fs::read_to_string(&file)?, "do not read\n");
+
+    std::fs::remove_dir_all(dir)?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -0,0 +1,247 @@
+// Every test crate that includes this module uses a different subset of it
+#![allow(dead_code)]
+
+use anyhow::Result;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+pub struct Page {
+    pub body: String,
+    pub etag: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SeenRequest {
+    pub path: String,
+    /// Header names are lowercased
+    pub headers: HashMap<String, String>,
+    pub status: u16,
+    pub at: Instant,
+}
+
+/// A canned answer served once, before the page itself
+struct OneShot {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+#[derive(Default)]
+struct SiteState {
+    pages: HashMap<String, Page>,
+    one_shots: HashMap<String, VecDeque<OneShot>>,
+    requests: Vec<SeenRequest>,
+    cancel_on: Option<(String, CancellationToken)>,
+}
+
+/// Minimal HTTP/1.1 server for scraper tests. Answers `If-None-Match` with 304 when the
+/// page's ETag matches and records every request it sees.
+#[derive(Clone)]
+pub struct LocalSite {
+    pub base_url: String,
+    state: Arc<Mutex<SiteState>>,
+}
+
+impl LocalSite {
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let site = Self {
+            base_url: format!("http://{}", listener.local_addr()?),
+            state: Arc::new(Mutex::new(SiteState::default())),
+        };
+
+        let state = site.state.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let mut request: Vec<u8> = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&buf[..n]),
+                        }
+                    }
+
+                    let response = Self::respond(&state, &String::from_utf8_lossy(&request));
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        Ok(site)
+    }
+
+    fn respond(state: &Mutex<SiteState>, request: &str) -> String {
+        let mut lines = request.lines();
+        let path = lines
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or("/")
+            .to_string();
+        let headers: HashMap<String, String> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+            .collect();
+
+        let mut state = state.lock().unwrap();
+        if state
+            .cancel_on
+            .as_ref()
+            .is_some_and(|(cancel_path, _)| *cancel_path == path)
+        {
+            if let Some((_, cancel)) = state.cancel_on.take() {
+                cancel.cancel();
+            }
+        }
+
+        let one_shot = state
+            .one_shots
+            .get_mut(&path)
+            .and_then(|queue| queue.pop_front());
+        let (status, response) = match (one_shot, state.pages.get(&path)) {
+            (Some(one_shot), _) => {
+                let headers: String = one_shot
+                    .headers
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}\r\n", name, value))
+                    .collect();
+                (
+                    one_shot.status,
+                    format!(
+                        "HTTP/1.1 {} Canned\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        one_shot.status,
+                        headers,
+                        one_shot.body.len(),
+                        one_shot.body
+                    ),
+                )
+            }
+            (None, Some(page))
+                if page.etag.is_some() && page.etag.as_ref() == headers.get("if-none-match") =>
+            {
+                (
+                    304,
+                    format!(
+                        "HTTP/1.1 304 Not Modified\r\nETag: {}\r\nConnection: close\r\n\r\n",
+                        page.etag.as_deref().unwrap_or_default()
+                    ),
+                )
+            }
+            (None, Some(page)) => {
+                let etag = page
+                    .etag
+                    .as_ref()
+                    .map(|etag| format!("ETag: {}\r\n", etag))
+                    .unwrap_or_default();
+                (
+                    200,
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        etag,
+                        page.body.len(),
+                        page.body
+                    ),
+                )
+            }
+            (None, None) => (
+                404,
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string(),
+            ),
+        };
+
+        state.requests.push(SeenRequest {
+            path,
+            headers,
+            status,
+            at: Instant::now(),
+        });
+        response
+    }
+
+    pub fn set_page(&self, path: &str, body: String) {
+        self.set_page_with_etag(path, body, None);
+    }
+
+    pub fn set_page_with_etag(&self, path: &str, body: String, etag: Option<&str>) {
+        self.state.lock().unwrap().pages.insert(
+            path.to_string(),
+            Page {
+                body,
+                etag: etag.map(str::to_string),
+            },
+        );
+    }
+
+    /// Answers the next request for `path` with `status` instead of the page
+    pub fn push_response(&self, path: &str, status: u16, headers: &[(&str, &str)], body: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .one_shots
+            .entry(path.to_string())
+            .or_default()
+            .push_back(OneShot {
+                status,
+                headers: headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+                body: body.to_string(),
+            });
+    }
+
+    /// Cancels `cancel` on the first request for `path`
+    pub fn cancel_on(&self, path: &str, cancel: CancellationToken) {
+        self.state.lock().unwrap().cancel_on = Some((path.to_string(), cancel));
+    }
+
+    pub fn requests(&self) -> Vec<SeenRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn hits(&self, path: &str) -> usize {
+        self.requests()
+            .iter()
+            .filter(|request| request.path == path)
+            .count()
+    }
+}
+
+/// Page the test sources select with `h1` / `article` / `a[href]`
+pub fn page(title: &str, body: &str, links: &[&str]) -> String {
+    let links: String = links
+        .iter()
+        .map(|link| format!("<a href=\"{link}\">{link}</a>"))
+        .collect();
+    format!("<html><body><h1>{title}</h1><article><p>{body}</p>{links}</article></body></html>")
+}
+
+/// Custom scraper source definition for a site served by [`LocalSite`]
+pub fn source_toml(name: &str, base_url: &str) -> String {
+    format!(
+        r#"
+kind = "scraper"
+name = "{name}"
+display_name = "Local Site"
+version = "1.0"
+base_url = "{base_url}"
+
+[options]
+delay_ms = 0
+concurrent_requests = 1
+
+[selectors]
+title = "h1"
+content = "article"
+links = "a[href]"
+remove_selectors = []
+"#
+    )
+}
@@ -0,0 +1,221 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::models::{
+    CreateItemDto, ItemType, ItemWithTags, MarkdownSyncReport, SyncSide, UpdateItemDto,
+};
+use std::path::{Path, PathBuf};
+use test_support::TestDb;
+use uuid::Uuid;
+
+fn sync_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("devvault_{}_{}", name, Uuid::new_v4()))
+}
+
+async fn create_note(db: &TestDb, title: &str, content: &str, tags: &[&str]) -> Result<i64> {
+    let mut tag_ids = Vec::new();
+    for tag in tags {
+        tag_ids.push(db.storage.create_tag(tag.to_string()).await?);
+    }
+    db.storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Note,
+            title: title.to_string(),
+            description: None,
+            content: content.to_string(),
+            metadata: None,
+            tag_ids: Some(tag_ids),
+        })
+        .await
+}
+
+async fn set_content(db: &TestDb, id: i64, content: &str) -> Result<()> {
+    db.storage
+        .update_item(UpdateItemDto {
+            id,
+            item_type: None,
+            title: None,
+            description: None,
+            content: Some(content.to_string()),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+    Ok(())
+}
+
+async fn note(db: &TestDb, title: &str) -> Result<Option<ItemWithTags>> {
+    Ok(db
+        .storage
+        .list_items(None, None, Some(ItemType::Note), None)
+        .await?
+        .into_iter()
+        .find(|note| note.item.title == title))
+}
+
+fn tag_names(item: &ItemWithTags) -> Vec<&str> {
+    let mut tags: Vec<&str> = item.tags.iter().map(|tag| tag.name.as_str()).collect();
+    tags.sort();
+    tags
+}
+
+fn read(dir: &Path, name: &str) -> Result<String> {
+    Ok(std::fs::read_to_string(dir.join(name))?)
+}
+
+#[tokio::test]
+async fn notes_and_files_follow_each_other() -> Result<()> {
+    let db = TestDb::new("markdown_sync").await?;
+    let dir = sync_dir("markdown_sync");
+    std::fs::create_dir_all(&dir)?;
+    let id = create_note(&db, "Shell tips", "Use `set -e`\n", &["shell"]).await?;
+    db.storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Snippet,
+            title: "Not a note".to_string(),
+            description: None,
+            content: "ls".to_string(),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+
+    let report = db.storage.sync_markdown_folder(&dir).await?;
+    assert_eq!(report.exported, 1);
+    let text = read(&dir, "Shell tips.md")?;
+    assert!(text.starts_with("---\ntitle: Shell tips\ntags: [\"shell\"]\ncreated: "));
+    assert!(text.ends_with("---\nUse `set -e`\n"));
+    assert_eq!(
+        db.storage.sync_markdown_folder(&dir).await?,
+        MarkdownSyncReport::default()
+    );
+
+    // Edited in the other editor: front-matter list and inline tags both count
+    std::fs::write(
+        dir.join("Shell tips.md"),
+        "---\ntitle: Shell tips\ntags:\n  - shell\n  - linux\n---\nUse `set -euo pipefail` #obsidian\n",
+    )?;
+    assert_eq!(db.storage.sync_markdown_folder(&dir).await?.updated_items, 1);
+    let synced = note(&db, "Shell tips").await?.unwrap();
+    assert_eq!(synced.item.content, "Use `set -euo pipefail` #obsidian\n");
+    assert_eq!(tag_names(&synced), vec!["linux", "obsidian", "shell"]);
+    assert_eq!(db.storage.list_item_revisions(id).await?.len(), 1);
+    assert_eq!(
+        db.storage.sync_markdown_folder(&dir).await?,
+        MarkdownSyncReport::default()
+    );
+
+    // A renamed file keeps its note
+    std::fs::rename(dir.join("Shell tips.md"), dir.join("Shell.md"))?;
+    assert_eq!(
+        db.storage.sync_markdown_folder(&dir).await?,
+        MarkdownSyncReport::default()
+    );
+    set_content(&db, id, "echo done\n").await?;
+    assert_eq!(db.storage.sync_markdown_folder(&dir).await?.updated_files, 1);
+    assert!(read(&dir, "Shell.md")?.ends_with("---\necho done\n"));
+
+    // New files in subfolders become notes, hidden folders are left alone
+    std::fs::create_dir_all(dir.join("Inbox"))?;
+    std::fs::create_dir_all(dir.join(".obsidian"))?;
+    std::fs::write(dir.join("Inbox/Idea.md"), "Rewrite it in Rust #idea\n")?;
+    std::fs::write(dir.join(".obsidian/workspace.md"), "editor state")?;
+    assert_eq!(db.storage.sync_markdown_folder(&dir).await?.imported, 1);
+    let idea = note(&db, "Idea").await?.unwrap();
+    assert_eq!(tag_names(&idea), vec!["idea"]);
+
+    // Deleting either side deletes the other, the note and the file go to their trash
+    std::fs::remove_file(dir.join("Inbox/Idea.md"))?;
+    assert_eq!(db.storage.sync_markdown_folder(&dir).await?.deleted_items, 1);
+    assert!(note(&db, "Idea").await?.is_none());
+    db.storage.delete_item(id).await?;
+    assert_eq!(db.storage.sync_markdown_folder(&dir).await?.deleted_files, 1);
+    assert!(!dir.join("Shell.md").exists());
+    assert!(read(&dir, ".devvault-trash/Shell.md")?.ends_with("---\necho done\n"));
+
+    std::fs::remove_dir_all(dir)?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn conflicts_wait_for_a_resolution() -> Result<()> {
+    let db = TestDb::new("markdown_conflicts").await?;
+    let dir = sync_dir("markdown_conflicts");
+    std::fs::create_dir_all(&dir)?;
+    let id = create_note(&db, "Plan", "draft\n", &[]).await?;
+    db.storage.sync_markdown_folder(&dir).await?;
+
+    std::fs::write(dir.join("Plan.md"), "---\ntitle: Plan\n---\nfrom the file\n")?;
+    set_content(&db, id, "from the vault\n").await?;
+    for _ in 0..2 {
+        let report = db.storage.sync_markdown_folder(&dir).await?;
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].item_id, id);
+        assert_eq!(report.conflicts[0].path, "Plan.md");
+        assert_eq!(read(&dir, "Plan.md")?, "---\ntitle: Plan\n---\nfrom the file\n");
+    }
+
+    db.storage
+        .resolve_markdown_conflict(&dir, id, SyncSide::File)
+        .await?;
+    assert_eq!(
+        db.storage.get_item(id).await?.unwrap().item.content,
+        "from the file\n"
+    );
+    assert_eq!(
+        db.storage.sync_markdown_folder(&dir).await?,
+        MarkdownSyncReport::default()
+    );
+
+    // A note meets an older copy of itself in a folder it was never synced with
+    let other_dir = sync_dir("markdown_conflicts_other");
+    std::fs::create_dir_all(&other_dir)?;
+    std::fs::write(other_dir.join("Plan.md"), "old copy\n")?;
+    let report = db.storage.sync_markdown_folder(&other_dir).await?;
+    assert_eq!((report.imported, report.conflicts.len()), (0, 1));
+    db.storage
+        .resolve_markdown_conflict(&other_dir, id, SyncSide::Vault)
+        .await?;
+    assert!(read(&other_dir, "Plan.md")?.ends_with("---\nfrom the file\n"));
+
+    std::fs::remove_dir_all(dir)?;
+    std::fs::remove_dir_all(other_dir)?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn a_missing_or_emptied_folder_deletes_nothing() -> Result<()> {
+    let db = TestDb::new("markdown_sync_missing").await?;
+    let dir = sync_dir("markdown_sync_missing");
+    create_note(&db, "Plan", "draft\n", &[]).await?;
+    create_note(&db, "Ideas", "later\n", &[]).await?;
+
+    let error = db.storage.sync_markdown_folder(&dir).await.unwrap_err();
+    assert!(error.to_string().contains("does not exist"));
+    assert!(!dir.exists());
+
+    std::fs::create_dir_all(&dir)?;
+    assert_eq!(db.storage.sync_markdown_folder(&dir).await?.exported, 2);
+
+    // Every synced file gone at once, e.g. the vault was emptied elsewhere: the notes stay
+    std::fs::remove_file(dir.join("Plan.md"))?;
+    std::fs::remove_file(dir.join("Ideas.md"))?;
+    assert_eq!(
+        db.storage.sync_markdown_folder(&dir).await?,
+        MarkdownSyncReport::default()
+    );
+    assert!(note(&db, "Plan").await?.is_some());
+    assert!(note(&db, "Ideas").await?.is_some());
+
+    std::fs::remove_dir_all(dir)?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
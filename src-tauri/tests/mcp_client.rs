@@ -0,0 +1,170 @@
+// Every test crate that includes this module uses a different subset of it
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+/// `dev-vault-mcp` running as a child process, spoken to over stdio like an MCP client
+pub struct McpClient {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    next_id: i64,
+}
+
+/// Decoded `tools/call` result: the JSON inside the text content and the `isError` flag
+pub struct ToolOutput {
+    pub value: Value,
+    pub text: String,
+    pub is_error: bool,
+}
+
+impl McpClient {
+    pub async fn spawn(db_path: &Path, flags: &[&str]) -> Result<Self> {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_dev-vault-mcp"))
+            .arg("--db-path")
+            .arg(db_path)
+            .args(flags)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to start dev-vault-mcp")?;
+
+        let stdin = child.stdin.take().context("No stdin")?;
+        let stdout = BufReader::new(child.stdout.take().context("No stdout")?).lines();
+        Ok(Self {
+            child,
+            stdin,
+            stdout,
+            next_id: 1,
+        })
+    }
+
+    pub async fn send_line(&mut self, line: &str) -> Result<()> {
+        self.stdin.write_all(line.as_bytes()).await?;
+        self.stdin.write_all(b"\n").await?;
+        self.stdin.flush().await?;
+        Ok(())
+    }
+
+    pub async fn read_message(&mut self) -> Result<Value> {
+        let line = self
+            .stdout
+            .next_line()
+            .await?
+            .context("dev-vault-mcp closed stdout")?;
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    pub async fn send(&mut self, message: &Value) -> Result<()> {
+        self.send_line(&message.to_string()).await
+    }
+
+    /// Sends a notification, no response is expected
+    pub async fn notify(&mut self, method: &str, params: Value) -> Result<()> {
+        self.send(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        }))
+        .await
+    }
+
+    /// Sends a request and returns the whole response message
+    pub async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params
+        });
+        self.send_line(&request.to_string()).await?;
+        self.read_message().await
+    }
+
+    pub async fn tool_names(&mut self) -> Result<Vec<String>> {
+        let response = self.request("tools/list", serde_json::json!({})).await?;
+        Ok(response["result"]["tools"]
+            .as_array()
+            .context("tools/list returned no tools")?
+            .iter()
+            .filter_map(|tool| tool["name"].as_str().map(str::to_string))
+            .collect())
+    }
+
+    pub async fn call_tool(&mut self, name: &str, arguments: Value) -> Result<ToolOutput> {
+        let response = self
+            .request(
+                "tools/call",
+                serde_json::json!({ "name": name, "arguments": arguments }),
+            )
+            .await?;
+        let result = &response["result"];
+        let text = result["content"][0]["text"]
+            .as_str()
+            .with_context(|| format!("No text content in {response}"))?
+            .to_string();
+        Ok(ToolOutput {
+            value: serde_json::from_str(&text).unwrap_or(Value::Null),
+            text,
+            is_error: result["isError"].as_bool().unwrap_or(false),
+        })
+    }
+
+    /// Closes stdin and waits for the server to exit
+    pub async fn shutdown(mut self) -> Result<()> {
+        drop(self.stdin);
+        self.child.wait().await?;
+        Ok(())
+    }
+}
+
+/// `dev-vault-mcp --http` on a free localhost port
+pub struct McpHttpServer {
+    child: Child,
+    /// Full endpoint URL, e.g. `http://127.0.0.1:41234/mcp`
+    pub url: String,
+}
+
+impl McpHttpServer {
+    pub async fn start(db_path: &Path, token: &str, flags: &[&str]) -> Result<Self> {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_dev-vault-mcp"))
+            .arg("--db-path")
+            .arg(db_path)
+            .args(["--http", "127.0.0.1:0", "--token", token])
+            .args(flags)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to start dev-vault-mcp --http")?;
+
+        let mut stderr = BufReader::new(child.stderr.take().context("No stderr")?).lines();
+        let url = loop {
+            let line = stderr
+                .next_line()
+                .await?
+                .context("dev-vault-mcp exited before listening")?;
+            if let Some(url) = line.strip_prefix("dev-vault-mcp listening on ") {
+                break url.to_string();
+            }
+        };
+        // Keep draining so later diagnostics never hit a closed pipe
+        tokio::spawn(async move { while let Ok(Some(_)) = stderr.next_line().await {} });
+        Ok(Self { child, url })
+    }
+
+    pub async fn stop(mut self) -> Result<()> {
+        self.child.kill().await?;
+        Ok(())
+    }
+}
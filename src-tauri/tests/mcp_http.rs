@@ -0,0 +1,282 @@
+mod mcp_client;
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::models::{CreateItemDto, ItemType};
+use mcp_client::McpHttpServer;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use std::process::Stdio;
+use test_support::TestDb;
+
+const TOKEN: &str = "test-token";
+const ACCEPT_BOTH: &str = "application/json, text/event-stream";
+
+async fn post(
+    client: &Client,
+    server: &McpHttpServer,
+    session: Option<&str>,
+    accept: &str,
+    body: Value,
+) -> Result<reqwest::Response> {
+    let mut request = client
+        .post(&server.url)
+        .bearer_auth(TOKEN)
+        .header("Accept", accept)
+        .json(&body);
+    if let Some(session) = session {
+        request = request.header("Mcp-Session-Id", session);
+    }
+    Ok(request.send().await?)
+}
+
+/// `data:` payload of the single event the server streams back
+fn sse_message(body: &str) -> Value {
+    let data = body
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .expect("no data line in event stream");
+    serde_json::from_str(data).unwrap()
+}
+
+async fn initialize(client: &Client, server: &McpHttpServer) -> Result<String> {
+    let response = post(
+        client,
+        server,
+        None,
+        ACCEPT_BOTH,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": { "protocolVersion": "2025-03-26" }
+        }),
+    )
+    .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"].to_str()?,
+        "text/event-stream"
+    );
+    let session = response.headers()["mcp-session-id"].to_str()?.to_string();
+    let message = sse_message(&response.text().await?);
+    assert_eq!(message["result"]["protocolVersion"], json!("2025-03-26"));
+    Ok(session)
+}
+
+#[tokio::test]
+async fn http_transport_serves_sessions_behind_a_token() -> Result<()> {
+    let db = TestDb::new("mcp_http").await?;
+    db.storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Snippet,
+            title: "Tail logs".to_string(),
+            description: None,
+            content: "tail -f /var/log/syslog".to_string(),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+
+    let server = McpHttpServer::start(&db.db_path, TOKEN, &[]).await?;
+    let client = Client::new();
+    let ping = json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" });
+
+    let anonymous = client.post(&server.url).json(&ping).send().await?;
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+    let wrong_token = client
+        .post(&server.url)
+        .bearer_auth("nope")
+        .json(&ping)
+        .send()
+        .await?;
+    assert_eq!(wrong_token.status(), StatusCode::UNAUTHORIZED);
+    let foreign_origin = client
+        .post(&server.url)
+        .bearer_auth(TOKEN)
+        .header("Origin", "https://evil.example")
+        .json(&ping)
+        .send()
+        .await?;
+    assert_eq!(foreign_origin.status(), StatusCode::FORBIDDEN);
+
+    let no_session = post(&client, &server, None, ACCEPT_BOTH, ping.clone()).await?;
+    assert_eq!(no_session.status(), StatusCode::BAD_REQUEST);
+    let stale_session = post(&client, &server, Some("stale"), ACCEPT_BOTH, ping.clone()).await?;
+    assert_eq!(stale_session.status(), StatusCode::NOT_FOUND);
+
+    // Two agents share the server, each with its own session
+    let first = initialize(&client, &server).await?;
+    let second = initialize(&client, &server).await?;
+    assert_ne!(first, second);
+
+    let notification = post(
+        &client,
+        &server,
+        Some(&first),
+        ACCEPT_BOTH,
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await?;
+    assert_eq!(notification.status(), StatusCode::ACCEPTED);
+    assert!(notification.text().await?.is_empty());
+
+    let tools = post(
+        &client,
+        &server,
+        Some(&first),
+        "application/json",
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/list" }),
+    )
+    .await?;
+    assert_eq!(
+        tools.headers()["content-type"].to_str()?,
+        "application/json"
+    );
+    let tools: Value = tools.json().await?;
+    assert!(tools["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|tool| tool["name"] == json!("devvault.items.list")));
+
+    let listed = post(
+        &client,
+        &server,
+        Some(&second),
+        ACCEPT_BOTH,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "tools/call",
+            "params": { "name": "devvault.items.list", "arguments": {} }
+        }),
+    )
+    .await?;
+    let listed = sse_message(&listed.text().await?);
+    let items: Value =
+        serde_json::from_str(listed["result"]["content"][0]["text"].as_str().unwrap())?;
+    assert_eq!(items[0]["title"], json!("Tail logs"));
+
+    // Batches go through the same dispatch as stdio
+    let batch = post(
+        &client,
+        &server,
+        Some(&second),
+        "application/json",
+        json!([ping, { "jsonrpc": "2.0", "id": 5, "method": "nope" }]),
+    )
+    .await?;
+    let batch: Value = batch.json().await?;
+    assert_eq!(batch[0]["result"], json!({}));
+    assert_eq!(batch[1]["error"]["code"], json!(-32601));
+
+    let stream = client
+        .get(&server.url)
+        .bearer_auth(TOKEN)
+        .header("Mcp-Session-Id", &first)
+        .send()
+        .await?;
+    assert_eq!(stream.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let closed = client
+        .delete(&server.url)
+        .bearer_auth(TOKEN)
+        .header("Mcp-Session-Id", &first)
+        .send()
+        .await?;
+    assert_eq!(closed.status(), StatusCode::NO_CONTENT);
+    let after_close = post(&client, &server, Some(&first), ACCEPT_BOTH, ping.clone()).await?;
+    assert_eq!(after_close.status(), StatusCode::NOT_FOUND);
+    let still_open = post(&client, &server, Some(&second), ACCEPT_BOTH, ping).await?;
+    assert_eq!(still_open.status(), StatusCode::OK);
+
+    server.stop().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn sessions_cancel_only_their_own_requests() -> Result<()> {
+    let db = TestDb::new("mcp_http_cancel").await?;
+    let server = McpHttpServer::start(&db.db_path, TOKEN, &["--allow-write"]).await?;
+    let client = Client::new();
+    let first = initialize(&client, &server).await?;
+    let second = initialize(&client, &server).await?;
+
+    // A write held up by the test's own transaction keeps both requests in flight
+    let mut lock = db.storage.pool.acquire().await?;
+    sqlx::query("BEGIN IMMEDIATE").execute(&mut *lock).await?;
+
+    // Both agents number their requests from 1
+    let create = |session: String, title: &str| {
+        let client = client.clone();
+        let url = server.url.clone();
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "devvault.items.create",
+                "arguments": { "type": "snippet", "title": title, "content": "ls" }
+            }
+        });
+        tokio::spawn(async move {
+            client
+                .post(url)
+                .bearer_auth(TOKEN)
+                .header("Accept", "application/json")
+                .header("Mcp-Session-Id", session)
+                .json(&body)
+                .send()
+                .await
+        })
+    };
+    let cancelled = create(first.clone(), "from the first agent");
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let kept = create(second.clone(), "from the second agent");
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let cancel = post(
+        &client,
+        &server,
+        Some(&first),
+        ACCEPT_BOTH,
+        json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/cancelled",
+            "params": { "requestId": 1 }
+        }),
+    )
+    .await?;
+    assert_eq!(cancel.status(), StatusCode::ACCEPTED);
+    sqlx::query("ROLLBACK").execute(&mut *lock).await?;
+    drop(lock);
+
+    // A cancelled request is answered with nothing
+    let cancelled = cancelled.await??;
+    assert_eq!(cancelled.status(), StatusCode::ACCEPTED);
+    let kept: Value = kept.await??.json().await?;
+    assert_eq!(kept["id"], json!(1));
+    assert_eq!(kept["result"]["isError"], json!(false));
+
+    server.stop().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn http_mode_only_binds_localhost() -> Result<()> {
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_dev-vault-mcp"))
+        .args(["--http", "0.0.0.0:0", "--token", TOKEN])
+        .stdin(Stdio::null())
+        .output()
+        .await?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("localhost"));
+    Ok(())
+}
@@ -0,0 +1,159 @@
+mod mcp_client;
+mod test_support;
+
+use anyhow::Result;
+use mcp_client::McpClient;
+use serde_json::{json, Value};
+use test_support::TestDb;
+
+fn error_code(response: &Value) -> i64 {
+    response["error"]["code"].as_i64().unwrap_or_default()
+}
+
+#[tokio::test]
+async fn initialize_negotiates_protocol_version() -> Result<()> {
+    let db = TestDb::new("mcp_protocol_init").await?;
+    let mut client = McpClient::spawn(&db.db_path, &[]).await?;
+
+    let init = client
+        .request("initialize", json!({ "protocolVersion": "2024-11-05" }))
+        .await?;
+    assert_eq!(init["result"]["protocolVersion"], json!("2024-11-05"));
+    assert_eq!(init["result"]["serverInfo"]["name"], json!("dev-vault"));
+
+    let init = client
+        .request("initialize", json!({ "protocolVersion": "1999-01-01" }))
+        .await?;
+    assert_eq!(init["result"]["protocolVersion"], json!("2025-03-26"));
+
+    // Notifications are never answered, the next line belongs to the ping
+    client
+        .notify("notifications/initialized", json!({}))
+        .await?;
+    client
+        .notify("notifications/cancelled", json!({ "requestId": 999 }))
+        .await?;
+    client.notify("notifications/unknown", json!({})).await?;
+    let ping = client.request("ping", json!({})).await?;
+    assert_eq!(ping["id"], json!(3));
+    assert_eq!(ping["result"], json!({}));
+
+    client.shutdown().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn errors_use_json_rpc_codes() -> Result<()> {
+    let db = TestDb::new("mcp_protocol_errors").await?;
+    let mut client = McpClient::spawn(&db.db_path, &[]).await?;
+
+    client.send_line("{not json").await?;
+    let parse_error = client.read_message().await?;
+    assert_eq!(error_code(&parse_error), -32700);
+    assert!(parse_error["id"].is_null());
+
+    client.send(&json!({ "jsonrpc": "2.0", "id": 7 })).await?;
+    let invalid = client.read_message().await?;
+    assert_eq!(error_code(&invalid), -32600);
+    assert_eq!(invalid["id"], json!(7));
+
+    client
+        .send(&json!({ "jsonrpc": "1.0", "id": "old", "method": "ping" }))
+        .await?;
+    let invalid = client.read_message().await?;
+    assert_eq!(error_code(&invalid), -32600);
+    assert_eq!(invalid["id"], json!("old"));
+
+    let unknown = client.request("tools/destroy", json!({})).await?;
+    assert_eq!(error_code(&unknown), -32601);
+
+    let missing_name = client
+        .request("tools/call", json!({ "arguments": {} }))
+        .await?;
+    assert_eq!(error_code(&missing_name), -32602);
+
+    let unknown_tool = client
+        .request("tools/call", json!({ "name": "devvault.nope" }))
+        .await?;
+    assert_eq!(error_code(&unknown_tool), -32602);
+
+    let bad_uri = client
+        .request("resources/read", json!({ "uri": "https://example.com" }))
+        .await?;
+    assert_eq!(error_code(&bad_uri), -32602);
+
+    let missing_item = client
+        .request("resources/read", json!({ "uri": "devvault://item/424242" }))
+        .await?;
+    assert_eq!(error_code(&missing_item), -32002);
+
+    // Failures inside a tool stay tool results so the model can read them
+    let tool_failure = client
+        .call_tool("devvault.items.get", json!({ "id": "not a number" }))
+        .await?;
+    assert!(tool_failure.is_error);
+
+    client.shutdown().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn batches_answer_every_request() -> Result<()> {
+    let db = TestDb::new("mcp_protocol_batch").await?;
+    let mut client = McpClient::spawn(&db.db_path, &[]).await?;
+
+    client
+        .send(&json!([
+            { "jsonrpc": "2.0", "id": 10, "method": "ping" },
+            { "jsonrpc": "2.0", "method": "notifications/initialized" },
+            { "jsonrpc": "2.0", "id": 11, "method": "tools/list" },
+            { "jsonrpc": "2.0", "id": 12, "method": "nope" },
+            { "id": 13 }
+        ]))
+        .await?;
+    let responses = client.read_message().await?;
+    let responses = responses.as_array().unwrap();
+    let ids: Vec<&Value> = responses.iter().map(|response| &response["id"]).collect();
+    assert_eq!(ids, vec![&json!(10), &json!(11), &json!(12), &json!(13)]);
+    assert_eq!(responses[0]["result"], json!({}));
+    assert!(responses[1]["result"]["tools"].is_array());
+    assert_eq!(error_code(&responses[2]), -32601);
+    assert_eq!(error_code(&responses[3]), -32600);
+
+    client.send(&json!([])).await?;
+    assert_eq!(error_code(&client.read_message().await?), -32600);
+
+    // A batch of notifications only gets no reply
+    client
+        .send(&json!([{ "jsonrpc": "2.0", "method": "notifications/initialized" }]))
+        .await?;
+    let ping = client.request("ping", json!({})).await?;
+    assert_eq!(ping["id"], json!(1));
+
+    client.shutdown().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn server_refuses_to_start_without_a_database() -> Result<()> {
+    let db_path = std::env::temp_dir()
+        .join(format!("devvault_missing_{}", uuid::Uuid::new_v4()))
+        .join("dev-vault.db");
+    let mut client = McpClient::spawn(&db_path, &[]).await?;
+
+    // The process may already be gone, so the write itself is allowed to fail
+    let _ = client
+        .send_line(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#)
+        .await;
+    assert!(client.read_message().await.is_err());
+    Ok(())
+}
@@ -0,0 +1,233 @@
+mod mcp_client;
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::DocumentationManager;
+use dev_vault_lib::mcp::ResourceUri;
+use dev_vault_lib::models::{CreateItemDto, ItemType};
+use mcp_client::McpClient;
+use serde_json::{json, Value};
+use test_support::{entry, item, TestDb};
+
+fn uris(page: &Value) -> Vec<String> {
+    page["result"]["resources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|resource| resource["uri"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn resource_uris_round_trip() {
+    let doc = ResourceUri::Doc {
+        name: "python".to_string(),
+        path: "library/my file#1".to_string(),
+    };
+    assert_eq!(
+        doc.to_string(),
+        "devvault://doc/python/library/my%20file%231"
+    );
+    assert_eq!(ResourceUri::parse(&doc.to_string()), Ok(doc));
+    assert_eq!(
+        ResourceUri::parse("devvault://item/42"),
+        Ok(ResourceUri::Item(42))
+    );
+    assert!(ResourceUri::parse("devvault://item/abc").is_err());
+    assert!(ResourceUri::parse("devvault://doc/python").is_err());
+    assert!(ResourceUri::parse("file:///etc/passwd").is_err());
+}
+
+#[tokio::test]
+async fn resources_and_prompts_expose_vault_content() -> Result<()> {
+    let db = TestDb::new("mcp_resources").await?;
+    let (mock_ids, _) = test_support::seed_mock_items(&db.storage, 95, "resources").await?;
+
+    let prompt_tag = db.storage.create_tag("prompt".to_string()).await?;
+    let snippet = db
+        .storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Snippet,
+            title: "Grep recursively".to_string(),
+            description: Some("Search a tree".to_string()),
+            content: "grep -rn pattern .".to_string(),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+    db.storage
+        .create_item(CreateItemDto {
+            tag_ids: Some(vec![prompt_tag]),
+            ..item(
+                ItemType::Note,
+                "Review code",
+                "Review this {{language}} code:\n{{code}}\nStick to {{ language }} idioms.",
+            )
+        })
+        .await?;
+    let review_again = db
+        .storage
+        .create_item(CreateItemDto {
+            tag_ids: Some(vec![prompt_tag]),
+            ..item(ItemType::Note, "Review code", "Be brief.")
+        })
+        .await?;
+    db.storage
+        .create_item(item(ItemType::Note, "Daily log", "Not a {{prompt}}"))
+        .await?;
+
+    let manager = DocumentationManager::new(db.storage.pool.clone());
+    manager
+        .install_documentation_with_entries(
+            "python",
+            None,
+            vec![entry("library/old", "only in main")],
+        )
+        .await?;
+    manager
+        .install_documentation_with_entries(
+            "python",
+            Some("3.12"),
+            vec![
+                entry("library/asyncio", "asyncio event loop"),
+                entry("library/my file", "a path with a space"),
+                entry("library/empty", ""),
+            ],
+        )
+        .await?;
+
+    let mut client = McpClient::spawn(&db.db_path, &[]).await?;
+
+    let init = client.request("initialize", json!({})).await?;
+    let capabilities = &init["result"]["capabilities"];
+    assert!(capabilities["resources"].is_object());
+    assert!(capabilities["prompts"].is_object());
+
+    let templates = client
+        .request("resources/templates/list", json!({}))
+        .await?;
+    let templates: Vec<&str> = templates["result"]["resourceTemplates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|template| template["uriTemplate"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        templates,
+        vec!["devvault://item/{id}", "devvault://doc/{name}/{path}"]
+    );
+
+    // 99 items fill the first page up with one doc entry of the active version
+    let first = client.request("resources/list", json!({})).await?;
+    let first_uris = uris(&first);
+    assert_eq!(first_uris.len(), 100);
+    assert!(first_uris.contains(&format!("devvault://item/{snippet}")));
+    assert!(first_uris.contains(&format!("devvault://item/{}", mock_ids[0])));
+    assert_eq!(first_uris[99], "devvault://doc/python/library/asyncio");
+    let cursor = first["result"]["nextCursor"].as_str().unwrap().to_string();
+
+    let second = client
+        .request("resources/list", json!({ "cursor": cursor }))
+        .await?;
+    assert_eq!(
+        uris(&second),
+        vec!["devvault://doc/python/library/my%20file"]
+    );
+    assert!(second["result"]["nextCursor"].is_null());
+
+    let read = client
+        .request(
+            "resources/read",
+            json!({ "uri": format!("devvault://item/{snippet}") }),
+        )
+        .await?;
+    let contents = &read["result"]["contents"][0];
+    assert_eq!(contents["text"], json!("grep -rn pattern ."));
+    assert_eq!(contents["mimeType"], json!("text/plain"));
+
+    let read = client
+        .request(
+            "resources/read",
+            json!({ "uri": "devvault://doc/python/library/my%20file" }),
+        )
+        .await?;
+    assert_eq!(
+        read["result"]["contents"][0]["text"],
+        json!("a path with a space")
+    );
+    assert_eq!(
+        read["result"]["contents"][0]["mimeType"],
+        json!("text/markdown")
+    );
+
+    for uri in [
+        "devvault://item/999999",
+        "devvault://doc/python/library/old",
+        "devvault://doc/rust/std",
+        "devvault://note/1",
+    ] {
+        let missing = client
+            .request("resources/read", json!({ "uri": uri }))
+            .await?;
+        assert!(missing["error"]["message"].is_string(), "{uri}: {missing}");
+    }
+
+    let prompts = client.request("prompts/list", json!({})).await?;
+    let prompts = prompts["result"]["prompts"].as_array().unwrap().clone();
+    assert_eq!(prompts.len(), 2);
+    assert_eq!(prompts[0]["name"], json!("review-code"));
+    assert_eq!(
+        prompts[0]["arguments"],
+        json!([
+            {"name": "language", "required": true},
+            {"name": "code", "required": true}
+        ])
+    );
+    assert_eq!(
+        prompts[1]["name"],
+        json!(format!("review-code-{review_again}"))
+    );
+    assert_eq!(prompts[1]["arguments"], json!([]));
+
+    let prompt = client
+        .request(
+            "prompts/get",
+            json!({
+                "name": "review-code",
+                "arguments": {"language": "Rust", "code": "fn main() {}"}
+            }),
+        )
+        .await?;
+    assert_eq!(
+        prompt["result"]["messages"][0],
+        json!({
+            "role": "user",
+            "content": {
+                "type": "text",
+                "text": "Review this Rust code:\nfn main() {}\nStick to Rust idioms."
+            }
+        })
+    );
+
+    let incomplete = client
+        .request(
+            "prompts/get",
+            json!({ "name": "review-code", "arguments": {"language": "Rust"} }),
+        )
+        .await?;
+    assert!(incomplete["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("code"));
+
+    let unknown = client
+        .request("prompts/get", json!({ "name": "daily-log" }))
+        .await?;
+    assert!(unknown["error"].is_object());
+
+    client.shutdown().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
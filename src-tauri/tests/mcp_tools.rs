@@ -0,0 +1,238 @@
+use anyhow::Result;
+use dev_vault_lib::domain::jsonc::strip_json_comments;
+use dev_vault_lib::mcp::tools::{
+    backup_path, install_entry, read_entry_state, remove_entry, tool_registry, McpEntryState,
+    ToolDefinition, ToolEnvironment,
+};
+use dev_vault_lib::mcp::McpServerConfig;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+fn temp_env() -> (PathBuf, ToolEnvironment) {
+    let dir = std::env::temp_dir().join(format!("devvault_tools_{}", uuid::Uuid::new_v4()));
+    let env = ToolEnvironment::rooted_at(&dir);
+    (dir, env)
+}
+
+fn server(db_path: &str) -> McpServerConfig {
+    McpServerConfig {
+        name: "dev-vault".to_string(),
+        command: "/opt/dev-vault/dev-vault-mcp".to_string(),
+        args: vec!["--db-path".to_string(), db_path.to_string()],
+        command_exists: true,
+    }
+}
+
+fn tool(env: &ToolEnvironment, id: &str) -> ToolDefinition {
+    tool_registry(env)
+        .into_iter()
+        .find(|tool| tool.id == id)
+        .unwrap()
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+#[test]
+fn registry_covers_supported_tools() {
+    let (_, env) = temp_env();
+    let ids: Vec<&str> = tool_registry(&env).iter().map(|tool| tool.id).collect();
+    assert_eq!(
+        ids,
+        vec![
+            "codex",
+            "claude-desktop",
+            "cursor",
+            "vscode",
+            "zed",
+            "continue"
+        ]
+    );
+}
+
+#[test]
+fn json_tools_merge_and_remove_their_entry() -> Result<()> {
+    let (dir, env) = temp_env();
+    let current = server("/data/dev-vault.db");
+
+    // (tool, existing config, pointer to the dev-vault entry, unrelated key that must survive)
+    let cases = [
+        (
+            "claude-desktop",
+            json!({ "mcpServers": { "other": { "command": "other" } }, "theme": "dark" }),
+            "/mcpServers/dev-vault",
+        ),
+        (
+            "cursor",
+            json!({ "mcpServers": { "other": { "command": "other" } }, "theme": "dark" }),
+            "/mcpServers/dev-vault",
+        ),
+        (
+            "vscode",
+            json!({ "servers": { "other": { "type": "stdio", "command": "other" } }, "theme": "dark" }),
+            "/servers/dev-vault",
+        ),
+        (
+            "zed",
+            json!({ "context_servers": { "other": { "command": "other" } }, "theme": "dark" }),
+            "/context_servers/dev-vault",
+        ),
+        (
+            "continue",
+            json!({
+                "experimental": { "modelContextProtocolServers": [{ "name": "other" }] },
+                "theme": "dark"
+            }),
+            "/experimental/modelContextProtocolServers/1",
+        ),
+    ];
+
+    for (id, existing, pointer) in cases {
+        let tool = tool(&env, id);
+        let path = tool.config_path().unwrap();
+        write(&path, &serde_json::to_string_pretty(&existing)?)?;
+        assert_eq!(
+            read_entry_state(&tool, &current)?,
+            McpEntryState::Missing,
+            "{id}"
+        );
+
+        assert_eq!(install_entry(&tool, &current)?, path);
+        assert_eq!(read_json(&backup_path(&path))?, existing, "{id}");
+        let installed = read_json(&path)?;
+        assert_eq!(installed["theme"], json!("dark"), "{id}");
+        assert!(
+            installed
+                .pointer(pointer)
+                .unwrap()
+                .to_string()
+                .contains("dev-vault-mcp"),
+            "{id}"
+        );
+        assert_eq!(
+            read_entry_state(&tool, &current)?,
+            McpEntryState::Current,
+            "{id}"
+        );
+
+        // The user's own keys on the entry outlive a reconnect
+        let mut edited = installed.clone();
+        edited.pointer_mut(pointer).unwrap()["env"] = json!({ "RUST_LOG": "debug" });
+        write(&path, &serde_json::to_string_pretty(&edited)?)?;
+        let moved = server("/elsewhere/dev-vault.db");
+        assert_eq!(
+            read_entry_state(&tool, &moved)?,
+            McpEntryState::Outdated,
+            "{id}"
+        );
+        install_entry(&tool, &moved)?;
+        assert_eq!(
+            read_entry_state(&tool, &moved)?,
+            McpEntryState::Current,
+            "{id}"
+        );
+        let reinstalled = read_json(&path)?;
+        assert_eq!(
+            reinstalled.pointer(pointer).unwrap()["env"],
+            json!({ "RUST_LOG": "debug" }),
+            "{id}"
+        );
+
+        assert!(remove_entry(&tool)?, "{id}");
+        assert!(!remove_entry(&tool)?, "{id}");
+        assert_eq!(read_json(&path)?, existing, "{id}");
+    }
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn codex_entry_is_written_to_toml() -> Result<()> {
+    let (dir, env) = temp_env();
+    let current = server("/data/dev-vault.db");
+    let tool = tool(&env, "codex");
+    let path = tool.config_path().unwrap();
+    let original =
+        "# Codex\nmodel = \"o3\" # fast enough\n\n[mcp_servers.other]\ncommand = \"other\"\n";
+    write(&path, original)?;
+
+    install_entry(&tool, &current)?;
+    let text = std::fs::read_to_string(&path)?;
+    assert!(text.starts_with(original));
+    let table: toml::Table = toml::from_str(&text)?;
+    assert_eq!(table["model"].as_str(), Some("o3"));
+    assert_eq!(
+        table["mcp_servers"]["dev-vault"]["command"].as_str(),
+        Some("/opt/dev-vault/dev-vault-mcp")
+    );
+    assert!(backup_path(&path).exists());
+    assert_eq!(read_entry_state(&tool, &current)?, McpEntryState::Current);
+    assert_eq!(
+        read_entry_state(&tool, &server("/elsewhere/dev-vault.db"))?,
+        McpEntryState::Outdated
+    );
+
+    assert!(remove_entry(&tool)?);
+    assert_eq!(std::fs::read_to_string(&path)?, original);
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn zed_settings_keep_their_comments() -> Result<()> {
+    let (dir, env) = temp_env();
+    let current = server("/data/dev-vault.db");
+    let tool = tool(&env, "zed");
+    let path = tool.config_path().unwrap();
+    let original = "// Zed settings\n{\n  /* font */ \"buffer_font_size\": 15,\n  \"url\": \"https://zed.dev\" // docs\n}\n";
+    write(&path, original)?;
+
+    install_entry(&tool, &current)?;
+    let text = std::fs::read_to_string(&path)?;
+    assert!(text.starts_with(
+        "// Zed settings\n{\n  /* font */ \"buffer_font_size\": 15,\n  \"url\": \"https://zed.dev\", // docs\n  \"context_servers\": {\n"
+    ));
+    let installed: Value = serde_json::from_str(&strip_json_comments(&text))?;
+    assert_eq!(installed["buffer_font_size"], json!(15));
+    assert_eq!(
+        installed["context_servers"]["dev-vault"]["source"],
+        json!("custom")
+    );
+    assert_eq!(read_entry_state(&tool, &current)?, McpEntryState::Current);
+
+    // Reconnecting rewrites only the entry
+    install_entry(&tool, &server("/elsewhere/dev-vault.db"))?;
+    let text = std::fs::read_to_string(&path)?;
+    assert!(text.contains("/* font */") && text.contains("// docs"));
+    assert!(text.contains("/elsewhere/dev-vault.db"));
+
+    assert!(remove_entry(&tool)?);
+    let text = std::fs::read_to_string(&path)?;
+    assert!(text.starts_with("// Zed settings\n{\n  /* font */ \"buffer_font_size\": 15,"));
+    assert!(!text.contains("dev-vault"));
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn tools_are_detected_by_their_config_directory() -> Result<()> {
+    let (dir, env) = temp_env();
+    let cursor = tool(&env, "cursor");
+    assert!(!cursor.detected(|_| false));
+    std::fs::create_dir_all(env.home.as_ref().unwrap().join(".cursor"))?;
+    assert!(cursor.detected(|_| false));
+    assert!(tool(&env, "zed").detected(|name| name == "zeditor"));
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
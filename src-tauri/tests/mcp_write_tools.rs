@@ -0,0 +1,192 @@
+mod mcp_client;
+mod test_support;
+
+use anyhow::Result;
+use mcp_client::McpClient;
+use serde_json::json;
+use test_support::TestDb;
+
+const WRITE_TOOLS: [&str; 4] = [
+    "devvault.items.create",
+    "devvault.items.update",
+    "devvault.items.delete",
+    "devvault.tags.upsert",
+];
+
+/// Items outside the trash
+async fn item_count(db: &TestDb) -> Result<i64> {
+    Ok(sqlx::query_scalar("SELECT COUNT(*) FROM items WHERE deleted_at IS NULL")
+        .fetch_one(&db.storage.pool)
+        .await?)
+}
+
+#[tokio::test]
+async fn write_tools_are_off_by_default() -> Result<()> {
+    let db = TestDb::new("mcp_read_only").await?;
+
+    for flags in [&[][..], &["--read-only"], &["--allow-write", "--read-only"]] {
+        let mut client = McpClient::spawn(&db.db_path, flags).await?;
+        let tools = client.tool_names().await?;
+        assert!(tools.contains(&"devvault.items.get".to_string()));
+        assert!(WRITE_TOOLS
+            .iter()
+            .all(|tool| !tools.contains(&tool.to_string())));
+
+        let denied = client
+            .call_tool(
+                "devvault.items.create",
+                json!({"type": "snippet", "title": "ls", "content": "ls -la"}),
+            )
+            .await?;
+        assert!(denied.is_error);
+        assert!(denied.text.contains("--allow-write"));
+        client.shutdown().await?;
+    }
+    assert_eq!(item_count(&db).await?, 0);
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn write_tools_create_update_tag_and_delete() -> Result<()> {
+    let db = TestDb::new("mcp_write_tools").await?;
+    let mut client = McpClient::spawn(&db.db_path, &["--allow-write"]).await?;
+
+    let tools = client.tool_names().await?;
+    assert!(WRITE_TOOLS
+        .iter()
+        .all(|tool| tools.contains(&tool.to_string())));
+
+    let create = json!({
+        "type": "snippet",
+        "title": "Find large files",
+        "content": "du -ah . | sort -rh | head -n 20",
+        "metadata": {"language": "bash"},
+        "tags": ["shell", "disk", "shell"]
+    });
+
+    let mut dry_create = create.clone();
+    dry_create["dryRun"] = json!(true);
+    let preview = client
+        .call_tool("devvault.items.create", dry_create)
+        .await?;
+    assert!(!preview.is_error, "{}", preview.text);
+    assert_eq!(preview.value["dryRun"], json!(true));
+    assert_eq!(preview.value["item"]["tags"], json!(["shell", "disk"]));
+    assert!(preview.value["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|tag| tag["created"] == json!(true) && tag["id"].is_null()));
+    assert_eq!(item_count(&db).await?, 0);
+    assert!(db.storage.get_tag_by_name("shell").await?.is_none());
+
+    let created = client.call_tool("devvault.items.create", create).await?;
+    assert!(!created.is_error, "{}", created.text);
+    let id = created.value["item"]["id"].as_i64().unwrap();
+    let item = db.storage.get_item(id).await?.unwrap();
+    assert_eq!(item.item.title, "Find large files");
+    let mut tags: Vec<String> = item.tags.iter().map(|tag| tag.name.clone()).collect();
+    tags.sort();
+    assert_eq!(tags, vec!["disk", "shell"]);
+
+    let preview = client
+        .call_tool(
+            "devvault.items.update",
+            json!({"id": id, "title": "Largest files", "tags": ["shell"], "dryRun": true}),
+        )
+        .await?;
+    assert_eq!(preview.value["before"]["title"], json!("Find large files"));
+    assert_eq!(preview.value["after"]["title"], json!("Largest files"));
+    assert_eq!(preview.value["after"]["tags"], json!(["shell"]));
+    assert_eq!(
+        db.storage.get_item(id).await?.unwrap().item.title,
+        "Find large files"
+    );
+
+    let updated = client
+        .call_tool(
+            "devvault.items.update",
+            json!({"id": id, "title": "Largest files", "tags": ["shell"]}),
+        )
+        .await?;
+    assert!(!updated.is_error, "{}", updated.text);
+    assert_eq!(updated.value["item"]["title"], json!("Largest files"));
+    assert_eq!(updated.value["item"]["tags"], json!(["shell"]));
+
+    let tagged = client
+        .call_tool(
+            "devvault.tags.upsert",
+            json!({"names": ["bash", "shell"], "itemId": id}),
+        )
+        .await?;
+    assert!(!tagged.is_error, "{}", tagged.text);
+    let mut tags: Vec<&str> = tagged.value["item"]["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|tag| tag.as_str())
+        .collect();
+    tags.sort();
+    assert_eq!(tags, vec!["bash", "shell"]);
+    assert_eq!(tagged.value["tags"][0]["created"], json!(true));
+    assert_eq!(tagged.value["tags"][1]["created"], json!(false));
+
+    let unknown_tag = client
+        .call_tool(
+            "devvault.items.update",
+            json!({"id": id, "tagIds": [999_999]}),
+        )
+        .await?;
+    assert!(unknown_tag.is_error);
+
+    // Documentation is installed, not written as an item
+    let documentation = client
+        .call_tool(
+            "devvault.items.create",
+            json!({"type": "documentation", "title": "Docs", "content": "text"}),
+        )
+        .await?;
+    assert!(documentation.is_error);
+    assert!(documentation.text.contains("type must be one of"));
+    let retyped = client
+        .call_tool(
+            "devvault.items.update",
+            json!({"id": id, "type": "documentation"}),
+        )
+        .await?;
+    assert!(retyped.is_error);
+    assert_eq!(item_count(&db).await?, 1);
+    let item_type: String = sqlx::query_scalar("SELECT type FROM items WHERE id = ?1")
+        .bind(id)
+        .fetch_one(&db.storage.pool)
+        .await?;
+    assert_eq!(item_type, "snippet");
+
+    let dry_delete = client
+        .call_tool("devvault.items.delete", json!({"id": id, "dryRun": true}))
+        .await?;
+    assert_eq!(dry_delete.value["item"]["title"], json!("Largest files"));
+    assert_eq!(item_count(&db).await?, 1);
+
+    let deleted = client
+        .call_tool("devvault.items.delete", json!({"id": id}))
+        .await?;
+    assert_eq!(deleted.value["dryRun"], json!(false));
+    assert_eq!(item_count(&db).await?, 0);
+
+    let missing = client
+        .call_tool("devvault.items.delete", json!({"id": id}))
+        .await?;
+    assert!(missing.is_error);
+    assert!(missing.text.contains("not found"));
+
+    client.shutdown().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -26,6 +26,9 @@ async fn creates_and_finds_mock_items() -> Result<()> {
         tag_ids: None,
         limit: Some(count as i64),
         offset: Some(0),
+        cursor: None,
+        doc_ids: None,
+        path_prefix: None,
     };
 
     let started = Instant::now();
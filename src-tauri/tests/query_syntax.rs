@@ -0,0 +1,181 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::ParsedQuery;
+use dev_vault_lib::models::{CreateItemDto, ItemType, SearchQuery};
+use test_support::{found_ids, TestDb};
+
+fn item(item_type: ItemType, title: &str, content: &str, language: Option<&str>) -> CreateItemDto {
+    CreateItemDto {
+        item_type,
+        title: title.to_string(),
+        description: None,
+        content: content.to_string(),
+        metadata: language.map(|language| serde_json::json!({ "language": language })),
+        tag_ids: None,
+    }
+}
+
+#[test]
+fn malformed_queries_report_the_column() {
+    let cases = [
+        ("\"compose file", 1, "unclosed quote"),
+        ("docker type:snipet", 13, "unknown type 'snipet'"),
+        ("updated:>2026-13-01", 9, "invalid date"),
+        ("compose tag:", 9, "needs a value"),
+        ("compose -", 9, "nothing to exclude"),
+    ];
+
+    for (query, column, message) in cases {
+        let err = ParsedQuery::parse(query).expect_err(query);
+        assert_eq!(err.column, column, "{query}");
+        assert!(err.to_string().contains(message), "{query}: {err}");
+    }
+
+    let parsed = ParsedQuery::parse("http://localhost:8080 std::fs -\"old api\"").unwrap();
+    assert_eq!(parsed.terms.len(), 2);
+    assert_eq!(parsed.excluded_terms.len(), 1);
+    assert!(parsed.filters.is_empty());
+}
+
+#[tokio::test]
+async fn structured_filters_narrow_results() -> Result<()> {
+    let db = TestDb::new("query_syntax").await?;
+    let storage = &db.storage;
+
+    let compose = storage
+        .create_item(item(
+            ItemType::Snippet,
+            "Compose file",
+            "services: web: image: nginx",
+            Some("yaml"),
+        ))
+        .await?;
+    let script = storage
+        .create_item(item(
+            ItemType::Snippet,
+            "Compose cleanup",
+            "docker compose down --volumes",
+            Some("bash"),
+        ))
+        .await?;
+    let note = storage
+        .create_item(item(
+            ItemType::Note,
+            "Compose notes",
+            "remember the compose file version",
+            None,
+        ))
+        .await?;
+
+    let docker_tag = storage.create_tag("docker".to_string()).await?;
+    sqlx::query("INSERT INTO item_tags (item_id, tag_id) VALUES (?1, ?3), (?2, ?3)")
+        .bind(compose)
+        .bind(script)
+        .bind(docker_tag)
+        .execute(&storage.pool)
+        .await?;
+
+    let day = 24 * 60 * 60;
+    let jan_2026 = 1_767_225_600;
+    sqlx::query("UPDATE items SET created_at = ?1, updated_at = ?1 WHERE id = ?2")
+        .bind(jan_2026 - 30 * day)
+        .bind(note)
+        .execute(&storage.pool)
+        .await?;
+    sqlx::query("UPDATE items SET created_at = ?1, updated_at = ?2 WHERE id IN (?3, ?4)")
+        .bind(jan_2026 - 30 * day)
+        .bind(jan_2026 + 10 * day)
+        .bind(compose)
+        .bind(script)
+        .execute(&storage.pool)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO documentations (name, display_name, version, source_url, installed_at, updated_at)
+         VALUES ('compose', 'Docker Compose', 'latest', 'https://docs.docker.com', ?1, ?1)",
+    )
+    .bind(jan_2026)
+    .execute(&storage.pool)
+    .await?;
+    sqlx::query(
+        "INSERT INTO doc_entries (doc_id, path, title, content, created_at)
+         SELECT id, 'compose-file', 'Compose file reference', 'compose file services', ?1
+         FROM documentations WHERE name = 'compose'",
+    )
+    .bind(jan_2026)
+    .execute(&storage.pool)
+    .await?;
+    let doc_entry: i64 = sqlx::query_scalar("SELECT -id FROM doc_entries")
+        .fetch_one(&storage.pool)
+        .await?;
+
+    let mut items = vec![compose, script, note];
+    items.sort();
+    let mut everything = items.clone();
+    everything.insert(0, doc_entry);
+    assert_eq!(found_ids(&db, "compose").await?, everything);
+
+    assert_eq!(
+        found_ids(&db, "compose type:snippet").await?,
+        vec![compose, script]
+    );
+    assert_eq!(
+        found_ids(&db, "compose type:note type:documentation").await?,
+        vec![doc_entry, note]
+    );
+    assert_eq!(found_ids(&db, "compose -type:documentation").await?, items);
+    assert_eq!(found_ids(&db, "tag:docker lang:yaml").await?, vec![compose]);
+    assert_eq!(found_ids(&db, "tag:DOCKER -lang:yaml").await?, vec![script]);
+    assert_eq!(
+        found_ids(&db, "compose -tag:docker").await?,
+        vec![doc_entry, note]
+    );
+    assert_eq!(
+        found_ids(&db, "\"compose file\" type:snippet").await?,
+        vec![compose]
+    );
+    assert_eq!(
+        found_ids(&db, "compose -volumes -\"file version\"").await?,
+        vec![doc_entry, compose]
+    );
+    // Excludes the word, not the words it starts: "services" stays
+    assert_eq!(found_ids(&db, "compose -serv").await?, everything);
+    assert_eq!(
+        found_ids(&db, "compose doc:\"Docker Compose\"").await?,
+        vec![doc_entry]
+    );
+    assert_eq!(found_ids(&db, "compose -doc:compose").await?, items);
+    assert_eq!(
+        found_ids(&db, "compose updated:>2026-01-01").await?,
+        vec![compose, script]
+    );
+    assert_eq!(
+        found_ids(&db, "compose updated:2026-01-11").await?,
+        vec![compose, script]
+    );
+    assert_eq!(
+        found_ids(&db, "compose after:2025-12-31").await?,
+        vec![doc_entry]
+    );
+    assert_eq!(
+        found_ids(&db, "before:2026-01-01 type:note").await?,
+        vec![note]
+    );
+
+    let err = db
+        .search_engine()
+        .search(SearchQuery {
+            query: "compose type:snipet".to_string(),
+            ..Default::default()
+        })
+        .await
+        .expect_err("unknown type must be rejected");
+    assert!(err.to_string().contains("expected one of"));
+
+    db.cleanup().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
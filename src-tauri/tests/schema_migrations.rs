@@ -0,0 +1,99 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::Storage;
+use dev_vault_lib::models::{CreateItemDto, ItemType};
+use sqlx::{Pool, Sqlite};
+use test_support::TestDb;
+
+async fn applied_migrations(pool: &Pool<Sqlite>) -> Result<Vec<(i64, String, i64)>> {
+    Ok(sqlx::query_as(
+        "SELECT version, checksum, applied_at FROM schema_migrations ORDER BY version",
+    )
+    .fetch_all(pool)
+    .await?)
+}
+
+async fn reopen_error(db_path: &std::path::Path) -> String {
+    match Storage::new(db_path.to_path_buf()).await {
+        Ok(_) => panic!("the database should be refused"),
+        Err(error) => format!("{error:#}"),
+    }
+}
+
+#[tokio::test]
+async fn reopening_a_migrated_database_applies_nothing() -> Result<()> {
+    let db = TestDb::new("schema_migrations_reopen").await?;
+    let item = db
+        .storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Snippet,
+            title: "List ports".to_string(),
+            description: None,
+            content: "lsof -i -P -n".to_string(),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+
+    let applied = applied_migrations(&db.storage.pool).await?;
+    assert_eq!(applied.first().map(|m| m.0), Some(1));
+    assert!(applied.windows(2).all(|pair| pair[1].0 == pair[0].0 + 1));
+
+    // Re-running the index migrations would bring this row back
+    sqlx::query("DELETE FROM items_fts WHERE rowid = ?1")
+        .bind(item)
+        .execute(&db.storage.pool)
+        .await?;
+
+    let db_path = db.db_path.clone();
+    db.storage.pool.close().await;
+    let storage = Storage::new(db_path.clone()).await?;
+
+    assert_eq!(applied_migrations(&storage.pool).await?, applied);
+    let indexed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items_fts WHERE rowid = ?1")
+        .bind(item)
+        .fetch_one(&storage.pool)
+        .await?;
+    assert_eq!(indexed, 0);
+    let search_index: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE name = 'search_index'")
+            .fetch_one(&storage.pool)
+            .await?;
+    assert_eq!(search_index, 0);
+
+    drop(storage);
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn newer_or_edited_schemas_are_refused() -> Result<()> {
+    let db = TestDb::new("schema_migrations_refuse").await?;
+    let db_path = db.db_path.clone();
+
+    sqlx::query(
+        "INSERT INTO schema_migrations (version, name, checksum, applied_at)
+         VALUES (999, 'from_the_future', '', 0)",
+    )
+    .execute(&db.storage.pool)
+    .await?;
+    assert!(reopen_error(&db_path)
+        .await
+        .contains("Database schema version 999 is newer"));
+
+    sqlx::query("DELETE FROM schema_migrations WHERE version = 999")
+        .execute(&db.storage.pool)
+        .await?;
+    sqlx::query("UPDATE schema_migrations SET checksum = 'edited' WHERE version = 4")
+        .execute(&db.storage.pool)
+        .await?;
+    assert!(reopen_error(&db_path)
+        .await
+        .contains("Migration 004 (fix_fts_for_docs) was changed"));
+
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -0,0 +1,212 @@
+mod local_site;
+mod test_support;
+
+use anyhow::Result;
+use chrono::{TimeZone, Utc};
+use dev_vault_lib::domain::parsers::backoff::{exponential_delay, parse_retry_after};
+use dev_vault_lib::domain::parsers::robots::RobotsRules;
+use dev_vault_lib::domain::parsers::sitemap::Sitemap;
+use dev_vault_lib::domain::parsers::{add_custom_source, SourceFormat};
+use dev_vault_lib::domain::DocumentationManager;
+use local_site::{page, source_toml, LocalSite};
+use std::time::Duration;
+use test_support::TestDb;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+const ROBOTS: &str = "
+# Everyone else stays out of /private
+User-agent: *
+Disallow: /private
+Crawl-delay: 5
+
+User-agent: Googlebot
+User-agent: DevVault
+Disallow: /guide/drafts
+Allow: /guide/drafts/published$
+Disallow: /*.pdf$
+Crawl-delay: 0.05
+
+Sitemap: https://docs.example.com/sitemap.xml
+";
+
+#[test]
+fn robots_rules_follow_the_agent_group() {
+    let ours = RobotsRules::parse(ROBOTS, "DevVault/1.0 (Documentation Scraper)");
+    assert!(ours.is_allowed("/private/page"));
+    assert!(!ours.is_allowed("/guide/drafts/wip"));
+    assert!(ours.is_allowed("/guide/drafts/published"));
+    assert!(!ours.is_allowed("/guide/drafts/published/more"));
+    assert!(!ours.is_allowed("/files/manual.pdf"));
+    assert!(ours.is_allowed("/files/manual.pdf?download=1"));
+    assert_eq!(ours.crawl_delay, Some(Duration::from_millis(50)));
+    assert_eq!(ours.sitemaps, vec!["https://docs.example.com/sitemap.xml"]);
+
+    let others = RobotsRules::parse(ROBOTS, "OtherBot/2.0");
+    assert!(!others.is_allowed("/private/page"));
+    assert!(others.is_allowed("/guide/drafts/wip"));
+    assert_eq!(others.crawl_delay, Some(Duration::from_secs(5)));
+
+    let empty = RobotsRules::parse("User-agent: *\nDisallow:\n", "DevVault/1.0");
+    assert!(empty.is_allowed("/anything"));
+}
+
+#[test]
+fn sitemaps_and_retry_after_parse() {
+    let index = Sitemap::parse(
+        r#"<?xml version="1.0"?>
+        <sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+          <sitemap><loc>https://docs.example.com/sitemap-1.xml</loc></sitemap>
+        </sitemapindex>"#,
+    );
+    assert!(index.urls.is_empty());
+    assert_eq!(
+        index.sitemaps,
+        vec!["https://docs.example.com/sitemap-1.xml"]
+    );
+
+    let urlset = Sitemap::parse(
+        "<urlset><url><loc>
+            https://docs.example.com/search?q=a&amp;page=2
+        </loc></url><url><loc>https://docs.example.com/guide</loc></url></urlset>",
+    );
+    assert_eq!(
+        urlset.urls,
+        vec![
+            "https://docs.example.com/search?q=a&page=2",
+            "https://docs.example.com/guide"
+        ]
+    );
+
+    let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+    assert_eq!(
+        parse_retry_after("120", now),
+        Some(Duration::from_secs(120))
+    );
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(parse_retry_after("soon", now), None);
+    assert_eq!(exponential_delay(3, 100), Duration::from_millis(800));
+}
+
+#[tokio::test]
+async fn crawl_honours_robots_sitemaps_and_backoff() -> Result<()> {
+    let db = TestDb::new("scraper_politeness").await?;
+    let manager = DocumentationManager::new(db.storage.pool.clone());
+
+    let server = LocalSite::start().await?;
+    let base_url = server.base_url.clone();
+    server.set_page(
+        "/robots.txt",
+        format!(
+            "User-agent: *\nDisallow: /guide/secret\nCrawl-delay: 0.2\nSitemap: {base_url}/sitemap_index.xml\n"
+        ),
+    );
+    server.set_page(
+        "/sitemap_index.xml",
+        format!(
+            "<sitemapindex><sitemap><loc>{base_url}/sitemap-pages.xml</loc></sitemap></sitemapindex>"
+        ),
+    );
+    server.set_page(
+        "/sitemap-pages.xml",
+        ["guide/alpha", "guide/orphan", "guide/secret", "guide/busy"]
+            .iter()
+            .map(|path| format!("<url><loc>{base_url}/{path}</loc></url>"))
+            .collect::<String>(),
+    );
+    server.set_page(
+        "/",
+        page("Polite Site", "index", &["guide/alpha", "guide/secret"]),
+    );
+    server.set_page("/guide/alpha", page("Alpha", "alpha content", &[]));
+    server.set_page("/guide/orphan", page("Orphan", "only in the sitemap", &[]));
+    server.set_page("/guide/secret", page("Secret", "never fetched", &[]));
+    server.set_page("/guide/busy", page("Busy", "always throttled", &[]));
+
+    server.push_response("/guide/alpha", 429, &[("Retry-After", "1")], "");
+    server.push_response("/guide/orphan", 503, &[], "");
+    for _ in 0..3 {
+        server.push_response("/guide/busy", 429, &[("Retry-After", "0")], "");
+    }
+
+    let app_dir = std::env::temp_dir().join(format!("devvault_polite_{}", Uuid::new_v4()));
+    add_custom_source(
+        &app_dir,
+        &source_toml("polite-site", &base_url).replace(
+            "[options]\n",
+            "[options]\nuse_sitemap = true\nmax_retries = 2\nretry_delay_ms = 50\n",
+        ),
+        SourceFormat::Toml,
+    )?;
+
+    let (progress_tx, _progress_rx) = mpsc::channel(100);
+    let doc = manager
+        .install_documentation_with_progress(
+            "polite-site",
+            None,
+            progress_tx,
+            CancellationToken::new(),
+        )
+        .await?;
+
+    let paths: Vec<String> =
+        sqlx::query_scalar("SELECT path FROM doc_entries WHERE doc_id = ?1 ORDER BY path")
+            .bind(doc.id)
+            .fetch_all(&db.storage.pool)
+            .await?;
+    assert!(paths.contains(&"guide/alpha".to_string()));
+    assert!(paths.contains(&"guide/orphan".to_string()));
+    assert!(!paths.contains(&"guide/secret".to_string()));
+    assert!(!paths.contains(&"guide/busy".to_string()));
+
+    // Disallowed by robots.txt even though both the index and the sitemap link it
+    assert_eq!(server.hits("/guide/secret"), 0);
+
+    let requests = server.requests();
+    let alpha: Vec<_> = requests
+        .iter()
+        .filter(|request| request.path == "/guide/alpha")
+        .collect();
+    assert_eq!(
+        alpha
+            .iter()
+            .map(|request| request.status)
+            .collect::<Vec<_>>(),
+        vec![429, 200]
+    );
+    assert!(alpha[1].at - alpha[0].at >= Duration::from_millis(950));
+
+    let orphan: Vec<u16> = requests
+        .iter()
+        .filter(|request| request.path == "/guide/orphan")
+        .map(|request| request.status)
+        .collect();
+    assert_eq!(orphan, vec![503, 200]);
+
+    // The first attempt plus `max_retries`, then the page is given up on
+    assert_eq!(server.hits("/guide/busy"), 3);
+
+    // Crawl-delay spaces out page fetches
+    let index = requests
+        .iter()
+        .position(|request| request.path == "/")
+        .unwrap();
+    assert!(requests[index + 1].at - requests[index].at >= Duration::from_millis(190));
+
+    std::fs::remove_dir_all(&app_dir)?;
+    sqlx::query("DELETE FROM documentations")
+        .execute(&db.storage.pool)
+        .await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -0,0 +1,106 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::SearchEngine;
+use dev_vault_lib::models::{CreateItemDto, ItemType, SearchQuery};
+use std::collections::HashSet;
+use test_support::TestDb;
+
+async fn collect_pages(engine: &SearchEngine, text: &str, limit: i64) -> Result<(i64, Vec<i64>)> {
+    let mut ids = Vec::new();
+    let mut cursor = None;
+    let mut total = None;
+
+    loop {
+        let page = engine
+            .search(SearchQuery {
+                query: text.to_string(),
+                limit: Some(limit),
+                cursor: cursor.take(),
+                ..Default::default()
+            })
+            .await?;
+
+        assert!(page.items.len() as i64 <= limit);
+        assert_eq!(*total.get_or_insert(page.total), page.total);
+        ids.extend(page.items.iter().map(|item| item.item.id));
+
+        if !page.has_more {
+            assert!(page.next_cursor.is_none());
+            break;
+        }
+        cursor = page.next_cursor;
+        assert!(cursor.is_some());
+    }
+
+    Ok((total.unwrap_or_default(), ids))
+}
+
+#[tokio::test]
+async fn cursor_pages_cover_every_match_once() -> Result<()> {
+    let db = TestDb::new("search_pagination").await?;
+
+    // More matches than the blended window so paging crosses into the plain FTS tail
+    let mut expected = HashSet::new();
+    for i in 0..230 {
+        let id = db
+            .storage
+            .create_item(CreateItemDto {
+                item_type: ItemType::Note,
+                title: format!("Pagination note {i}"),
+                description: None,
+                content: "pagination ".repeat(i % 7 + 1),
+                metadata: None,
+                tag_ids: None,
+            })
+            .await?;
+        expected.insert(id);
+    }
+    db.storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Note,
+            title: "Unrelated".to_string(),
+            description: None,
+            content: "nothing to see here".to_string(),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+
+    for engine in [
+        db.search_engine().with_weights(1.0, 0.0),
+        db.search_engine().with_weights(0.6, 0.4),
+    ] {
+        let (total, ids) = collect_pages(&engine, "pagination", 40).await?;
+        assert_eq!(total, expected.len() as i64);
+        assert_eq!(ids.len(), expected.len());
+        assert_eq!(ids.iter().copied().collect::<HashSet<_>>(), expected);
+    }
+
+    let first_page = db
+        .search_engine()
+        .search(SearchQuery {
+            query: "pagination".to_string(),
+            limit: Some(10),
+            ..Default::default()
+        })
+        .await?;
+    assert_eq!(first_page.total, 230);
+    assert!(first_page.has_more);
+
+    let invalid = db
+        .search_engine()
+        .search(SearchQuery {
+            query: "pagination".to_string(),
+            cursor: Some("not a cursor".to_string()),
+            ..Default::default()
+        })
+        .await;
+    assert!(invalid.is_err());
+
+    db.cleanup().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
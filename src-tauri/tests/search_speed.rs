@@ -26,6 +26,9 @@ async fn search_speed_for_mock_items() -> Result<()> {
         tag_ids: None,
         limit: Some(100),
         offset: Some(0),
+        cursor: None,
+        doc_ids: None,
+        path_prefix: None,
     };
 
     let started = Instant::now();
@@ -0,0 +1,121 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::models::{ItemType, SearchQuery, UpdateItemDto};
+use sqlx::Row;
+use test_support::{item, TestDb};
+
+fn query(text: &str) -> SearchQuery {
+    SearchQuery {
+        query: text.to_string(),
+        item_type: None,
+        tag_ids: None,
+        limit: Some(20),
+        offset: Some(0),
+        cursor: None,
+        doc_ids: None,
+        path_prefix: None,
+    }
+}
+
+#[tokio::test]
+async fn embeddings_follow_item_lifecycle() -> Result<()> {
+    let db = TestDb::new("semantic_lifecycle").await?;
+
+    let id = db
+        .storage
+        .create_item(item(
+            ItemType::Snippet,
+            "Compose file",
+            "docker compose up -d",
+        ))
+        .await?;
+
+    let original: Vec<u8> = sqlx::query("SELECT vector FROM embeddings WHERE item_id = ?1")
+        .bind(id)
+        .fetch_one(&db.storage.pool)
+        .await?
+        .get("vector");
+    assert!(!original.is_empty());
+
+    db.storage
+        .update_item(UpdateItemDto {
+            id,
+            item_type: None,
+            title: None,
+            description: None,
+            content: Some("kubectl apply -f deployment.yaml".to_string()),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+
+    let updated: Vec<u8> = sqlx::query("SELECT vector FROM embeddings WHERE item_id = ?1")
+        .bind(id)
+        .fetch_one(&db.storage.pool)
+        .await?
+        .get("vector");
+    assert_ne!(original, updated);
+
+    db.storage.delete_item(id).await?;
+    db.storage.purge_item(id).await?;
+    let remaining: i64 = sqlx::query("SELECT COUNT(*) AS count FROM embeddings")
+        .fetch_one(&db.storage.pool)
+        .await?
+        .get("count");
+    assert_eq!(remaining, 0);
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn semantic_weight_controls_fuzzy_matches() -> Result<()> {
+    let db = TestDb::new("semantic_weights").await?;
+
+    let restart_id = db
+        .storage
+        .create_item(item(
+            ItemType::Snippet,
+            "Kubernetes rollout restart",
+            "kubectl rollout restart deployment/api --namespace production",
+        ))
+        .await?;
+    db.storage
+        .create_item(item(
+            ItemType::Snippet,
+            "Git stash",
+            "git stash push -m 'wip' && git pull --rebase",
+        ))
+        .await?;
+
+    // "pods" never appears in the vault, so the FTS AND-query alone finds nothing
+    let fts_only = db
+        .search_engine()
+        .with_weights(1.0, 0.0)
+        .search(query("kubernetes restart pods"))
+        .await?;
+    assert!(fts_only.items.is_empty());
+
+    let blended = db
+        .search_engine()
+        .with_weights(0.6, 0.4)
+        .search(query("kubernetes restart pods"))
+        .await?;
+    assert_eq!(
+        blended.items.first().map(|item| item.item.id),
+        Some(restart_id)
+    );
+    assert!(blended
+        .items
+        .iter()
+        .all(|item| item.item.title != "Git stash"));
+
+    db.cleanup().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -0,0 +1,207 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::snippet_formats;
+use dev_vault_lib::models::{ImportConflict, ItemType, ItemWithTags, SnippetFormat};
+use serde_json::{json, Value};
+use test_support::TestDb;
+use uuid::Uuid;
+
+const VSCODE_SNIPPETS: &str = r#"{
+    // Comments and trailing commas are allowed in snippet files
+    "Print to console": {
+        "prefix": ["log", "cl"],
+        "body": ["console.log('${1:message}', $1);", "$0"],
+        "description": "Log output to console",
+        "scope": "javascript,typescript",
+    },
+    "Docker run": {
+        "prefix": "drun",
+        "body": "docker run --rm ${1|alpine,ubuntu|} \\$HOME ${2:cmd}$TM_FILENAME${1/(.*)/${1:/upcase}/}",
+    },
+}"#;
+
+const LIVE_TEMPLATES: &str = r#"<templateSet group="user">
+  <!-- Kotlin helpers -->
+  <template name="sout" value="println(&quot;$EXPR$ = ${$EXPR$}&quot;)$END$" description="Print a value" toReformat="true" toShortenFQNames="true">
+    <variable name="EXPR" expression="variableOfType(&quot;&quot;)" defaultValue="&quot;value&quot;" alwaysStopAt="true" />
+    <context>
+      <option name="KOTLIN_STATEMENT" value="true" />
+      <option name="JAVA_CODE" value="false" />
+    </context>
+  </template>
+  <template name="todo" value="// TODO($USER$): $$ $SELECTION$" description="" toReformat="false">
+    <variable name="USER" expression="user()" defaultValue="" alwaysStopAt="true" />
+    <context>
+      <option name="OTHER" value="true" />
+    </context>
+  </template>
+</templateSet>"#;
+
+fn write_temp(name: &str, content: &str) -> Result<std::path::PathBuf> {
+    let path = std::env::temp_dir().join(format!("devvault_{}_{}", Uuid::new_v4(), name));
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+fn find<'a>(items: &'a [ItemWithTags], title: &str) -> &'a ItemWithTags {
+    items.iter().find(|item| item.item.title == title).unwrap()
+}
+
+fn tag_names(item: &ItemWithTags) -> Vec<&str> {
+    let mut tags: Vec<&str> = item.tags.iter().map(|tag| tag.name.as_str()).collect();
+    tags.sort();
+    tags
+}
+
+#[tokio::test]
+async fn vscode_snippets_are_imported() -> Result<()> {
+    let db = TestDb::new("snippets_vscode").await?;
+    let path = write_temp("snippets.code-snippets", VSCODE_SNIPPETS)?;
+
+    let summary = db
+        .storage
+        .import_snippets(&path, SnippetFormat::VsCode, ImportConflict::Skip)
+        .await?;
+    assert_eq!(summary.created, 2);
+
+    let items = db.storage.list_items(None, None, None, None).await?;
+    let log = find(&items, "Print to console");
+    assert_eq!(log.item.item_type, ItemType::Snippet);
+    assert_eq!(
+        log.item.description.as_deref(),
+        Some("Log output to console")
+    );
+    assert_eq!(log.item.content, "console.log('message', message);\n");
+    assert_eq!(tag_names(log), vec!["javascript", "typescript"]);
+    assert_eq!(
+        log.item.metadata,
+        Some(json!({
+            "language": "javascript",
+            "snippet": {
+                "prefix": ["log", "cl"],
+                "body": "console.log('${1:message}', $1);\n$0",
+                "scope": "javascript,typescript",
+            },
+        }))
+    );
+
+    let docker = find(&items, "Docker run");
+    assert_eq!(
+        docker.item.content,
+        "docker run --rm alpine $HOME cmd$TM_FILENAME"
+    );
+    assert!(docker.tags.is_empty());
+    assert_eq!(
+        docker.item.metadata.as_ref().unwrap()["language"],
+        Value::Null
+    );
+
+    // A second import finds the snippets by title
+    let summary = db
+        .storage
+        .import_snippets(&path, SnippetFormat::VsCode, ImportConflict::Skip)
+        .await?;
+    assert_eq!(summary.skipped, 2);
+
+    std::fs::remove_file(path)?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn jetbrains_live_templates_are_imported() -> Result<()> {
+    let db = TestDb::new("snippets_jetbrains").await?;
+    let path = write_temp("templates.xml", LIVE_TEMPLATES)?;
+
+    let summary = db
+        .storage
+        .import_snippets(&path, SnippetFormat::JetBrains, ImportConflict::Skip)
+        .await?;
+    assert_eq!(summary.created, 2);
+
+    let items = db.storage.list_items(None, None, None, None).await?;
+    let sout = find(&items, "Print a value");
+    assert_eq!(sout.item.content, "println(\"value = ${value}\")");
+    assert_eq!(tag_names(sout), vec!["kotlin"]);
+    let template = &sout.item.metadata.as_ref().unwrap()["snippet"];
+    assert_eq!(template["prefix"], json!(["sout"]));
+    assert_eq!(template["body"], "println(\"${1:value} = \\${$1\\}\")$0");
+
+    // Without a description the abbreviation is the title
+    let todo = find(&items, "todo");
+    assert_eq!(todo.item.content, "// TODO(USER): $ $TM_SELECTED_TEXT");
+    assert!(todo.tags.is_empty());
+
+    let error = db
+        .storage
+        .import_snippets(&path, SnippetFormat::VsCode, ImportConflict::Skip)
+        .await
+        .unwrap_err();
+    assert!(format!("{error:#}").contains("not valid JSON"));
+
+    std::fs::remove_file(path)?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn vscode_export_keeps_placeholder_syntax() -> Result<()> {
+    let db = TestDb::new("snippets_export").await?;
+    let source = write_temp("source.code-snippets", VSCODE_SNIPPETS)?;
+    db.storage
+        .import_snippets(&source, SnippetFormat::VsCode, ImportConflict::Skip)
+        .await?;
+
+    let items = db.storage.list_items(None, None, None, None).await?;
+    let mut edited = find(&items, "Docker run").clone();
+    edited.item.content = "docker run --rm $IMAGE".to_string();
+    let exported: Value = serde_json::from_str(&snippet_formats::vscode_snippets_file(&[
+        find(&items, "Print to console").clone(),
+        edited,
+    ])?)?;
+
+    assert_eq!(
+        exported["Print to console"],
+        json!({
+            "prefix": ["log", "cl"],
+            "body": ["console.log('${1:message}', $1);", "$0"],
+            "description": "Log output to console",
+            "scope": "javascript,typescript",
+        })
+    );
+    // Edited content no longer matches the template and is exported as plain text
+    assert_eq!(
+        exported["Docker run"]["body"],
+        json!(["docker run --rm \\$IMAGE"])
+    );
+
+    // Exporting and importing again gives the same snippets
+    let target = TestDb::new("snippets_reimport").await?;
+    let path = std::env::temp_dir().join(format!("devvault_{}.code-snippets", Uuid::new_v4()));
+    assert_eq!(snippet_formats::write_vscode_snippets(&path, &items)?, 2);
+    target
+        .storage
+        .import_snippets(&path, SnippetFormat::VsCode, ImportConflict::Skip)
+        .await?;
+    let reimported = target.storage.list_items(None, None, None, None).await?;
+    for item in &items {
+        let copy = find(&reimported, &item.item.title);
+        assert_eq!(copy.item.content, item.item.content);
+        assert_eq!(copy.item.metadata, item.item.metadata);
+        assert_eq!(tag_names(copy), tag_names(item));
+    }
+
+    std::fs::remove_file(source)?;
+    std::fs::remove_file(path)?;
+    for db in [db, target] {
+        let db_path = db.db_path.clone();
+        drop(db);
+        test_support::remove_db_file(db_path)?;
+    }
+    Ok(())
+}
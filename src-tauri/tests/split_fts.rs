@@ -0,0 +1,143 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::{DocumentationManager, Storage};
+use dev_vault_lib::models::{CreateItemDto, ItemType, SearchQuery};
+use sqlx::Executor;
+use test_support::{entry, found_ids, item, ranked_ids, text_query, TestDb};
+
+#[tokio::test]
+async fn items_keep_their_place_next_to_a_large_doc_set() -> Result<()> {
+    let db = TestDb::new("split_fts_ranking").await?;
+    let snippet = db
+        .storage
+        .create_item(item(
+            ItemType::Note,
+            "Nginx reverse proxy",
+            "proxy_pass to the upstream app, then reload the server",
+        ))
+        .await?;
+
+    // Every entry repeats the term, on a shared index their bm25 beat the snippet
+    let entries = (0..200)
+        .map(|i| {
+            let content = format!(
+                "{}{}",
+                "proxy upstream ".repeat(i % 20 + 5),
+                "filler ".repeat(i / 20)
+            );
+            entry(&format!("http/proxy/{i}"), &content)
+        })
+        .collect();
+    DocumentationManager::new(db.storage.pool.clone())
+        .install_documentation_with_entries("nodejs", None, entries)
+        .await?;
+
+    let first_page = ranked_ids(
+        &db,
+        SearchQuery {
+            limit: Some(5),
+            ..text_query("proxy")
+        },
+    )
+    .await?;
+    assert!(
+        first_page.contains(&snippet),
+        "snippet missing from {first_page:?}"
+    );
+
+    let result = db
+        .search_engine()
+        .search(SearchQuery {
+            query: "proxy".to_string(),
+            limit: Some(50),
+            ..Default::default()
+        })
+        .await?;
+    assert_eq!(result.total, 201);
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn title_matches_outrank_content_matches() -> Result<()> {
+    let db = TestDb::new("split_fts_weights").await?;
+    let in_content = db
+        .storage
+        .create_item(item(
+            ItemType::Note,
+            "Shell notes",
+            "rsync flags: rsync -avz, rsync --delete, rsync over ssh",
+        ))
+        .await?;
+    let in_title = db
+        .storage
+        .create_item(item(
+            ItemType::Note,
+            "Rsync cheatsheet",
+            "copy directories between hosts",
+        ))
+        .await?;
+
+    assert_eq!(
+        ranked_ids(&db, text_query("rsync")).await?,
+        vec![in_title, in_content]
+    );
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn reopening_fills_missing_index_rows() -> Result<()> {
+    let db = TestDb::new("split_fts_migration").await?;
+    let tag = db.storage.create_tag("ops".to_string()).await?;
+    let checklist = db
+        .storage
+        .create_item(CreateItemDto {
+            tag_ids: Some(vec![tag]),
+            ..item(
+                ItemType::Note,
+                "Deploy checklist",
+                "tag the release and roll out",
+            )
+        })
+        .await?;
+    DocumentationManager::new(db.storage.pool.clone())
+        .install_documentation_with_entries(
+            "rust",
+            None,
+            vec![entry("std/release", "release notes")],
+        )
+        .await?;
+
+    // As if the indexes were created on a database that already had rows
+    db.storage
+        .pool
+        .execute(
+            "DELETE FROM items_fts; DELETE FROM docs_fts; DELETE FROM items_trigram;
+             DELETE FROM schema_migrations WHERE version >= 12;",
+        )
+        .await?;
+    assert!(found_ids(&db, "release").await?.is_empty());
+
+    let db_path = db.db_path.clone();
+    db.storage.pool.close().await;
+    let storage = Storage::new(db_path.clone()).await?;
+    let reopened = TestDb { storage, db_path };
+
+    let found = found_ids(&reopened, "release").await?;
+    assert_eq!(found.len(), 2);
+    assert!(found.contains(&checklist));
+    assert_eq!(found_ids(&reopened, "ops").await?, vec![checklist]);
+
+    let db_path = reopened.db_path.clone();
+    drop(reopened);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::Storage;
+use dev_vault_lib::models::{CreateItemDto, ItemType, UpdateItemDto};
+use test_support::{found_ids, TestDb};
+
+#[tokio::test]
+async fn tag_names_are_searchable() -> Result<()> {
+    let db = TestDb::new("tag_search").await?;
+
+    let tag_id = db.storage.create_tag("kubernetes".to_string()).await?;
+    let item_id = db
+        .storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Snippet,
+            title: "Restart deployment".to_string(),
+            description: None,
+            content: "kubectl rollout restart deployment/api".to_string(),
+            metadata: None,
+            tag_ids: Some(vec![tag_id]),
+        })
+        .await?;
+
+    assert_eq!(found_ids(&db, "kubernetes").await?, vec![item_id]);
+    assert_eq!(found_ids(&db, "tag:kubernetes").await?, vec![item_id]);
+    assert!(found_ids(&db, "tag:rollout").await?.is_empty());
+
+    sqlx::query("UPDATE tags SET name = 'k8s' WHERE id = ?1")
+        .bind(tag_id)
+        .execute(&db.storage.pool)
+        .await?;
+    assert!(found_ids(&db, "kubernetes").await?.is_empty());
+    assert_eq!(found_ids(&db, "tag:k8s").await?, vec![item_id]);
+
+    // Reopening an up-to-date database leaves the index alone
+    let reopened = Storage::new(db.db_path.clone()).await?;
+    drop(reopened);
+    assert_eq!(found_ids(&db, "k8s").await?, vec![item_id]);
+    assert_eq!(found_ids(&db, "tag:k8s").await?, vec![item_id]);
+
+    let docker_id = db.storage.create_tag("docker".to_string()).await?;
+    db.storage
+        .update_item(UpdateItemDto {
+            id: item_id,
+            item_type: None,
+            title: None,
+            description: None,
+            content: None,
+            metadata: None,
+            tag_ids: Some(vec![docker_id]),
+        })
+        .await?;
+    assert!(found_ids(&db, "tag:k8s").await?.is_empty());
+    assert_eq!(found_ids(&db, "tag:docker").await?, vec![item_id]);
+
+    sqlx::query("DELETE FROM tags WHERE id = ?1")
+        .bind(docker_id)
+        .execute(&db.storage.pool)
+        .await?;
+    assert!(found_ids(&db, "docker").await?.is_empty());
+
+    db.cleanup().await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use dev_vault_lib::domain::{SearchEngine, Storage};
-use dev_vault_lib::models::{CreateItemDto, ItemType};
+use dev_vault_lib::models::{CreateItemDto, ItemType, ParsedDocEntry, SearchQuery};
 use sqlx::Row;
 use std::path::PathBuf;
 use uuid::Uuid;
@@ -47,6 +47,52 @@ pub fn temp_db_path(test_name: &str) -> PathBuf {
     std::env::temp_dir().join(file_name)
 }
 
+pub fn item(item_type: ItemType, title: &str, content: &str) -> CreateItemDto {
+    CreateItemDto {
+        item_type,
+        title: title.to_string(),
+        description: None,
+        content: content.to_string(),
+        metadata: None,
+        tag_ids: None,
+    }
+}
+
+pub fn entry(path: &str, content: &str) -> ParsedDocEntry {
+    ParsedDocEntry {
+        path: path.to_string(),
+        title: path.to_string(),
+        content: content.to_string(),
+        entry_type: Some("module".to_string()),
+        parent_path: None,
+    }
+}
+
+pub fn text_query(text: &str) -> SearchQuery {
+    SearchQuery {
+        query: text.to_string(),
+        limit: Some(50),
+        ..Default::default()
+    }
+}
+
+/// Ids of the full-text matches, best match first
+pub async fn ranked_ids(db: &TestDb, query: SearchQuery) -> Result<Vec<i64>> {
+    let result = db
+        .search_engine()
+        .with_weights(1.0, 0.0)
+        .search(query)
+        .await?;
+    Ok(result.items.into_iter().map(|item| item.item.id).collect())
+}
+
+/// Ids of the full-text matches for a query string, sorted
+pub async fn found_ids(db: &TestDb, text: &str) -> Result<Vec<i64>> {
+    let mut ids = ranked_ids(db, text_query(text)).await?;
+    ids.sort();
+    Ok(ids)
+}
+
 pub async fn seed_mock_items(
     storage: &Storage,
     count: usize,
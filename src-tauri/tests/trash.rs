@@ -0,0 +1,205 @@
+mod mcp_client;
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::domain::DocumentationManager;
+use dev_vault_lib::models::{
+    CreateItemDto, ItemType, ParsedDocEntry, SearchQuery, TrashKind, UpdateItemDto,
+};
+use mcp_client::McpClient;
+use serde_json::json;
+use test_support::{entry, found_ids, ranked_ids, text_query, TestDb};
+
+fn snippet(title: &str) -> CreateItemDto {
+    CreateItemDto {
+        item_type: ItemType::Snippet,
+        title: title.to_string(),
+        description: None,
+        content: "docker compose up --build".to_string(),
+        metadata: None,
+        tag_ids: None,
+    }
+}
+
+#[tokio::test]
+async fn deleted_items_wait_in_the_trash() -> Result<()> {
+    let db = TestDb::new("trash_items").await?;
+    let storage = &db.storage;
+    let tag = storage.create_tag("docker".to_string()).await?;
+    let kept = storage.create_item(snippet("Compose build")).await?;
+    let deleted = storage
+        .create_item(CreateItemDto {
+            tag_ids: Some(vec![tag]),
+            ..snippet("Compose rebuild")
+        })
+        .await?;
+
+    assert!(storage.delete_item(deleted).await?);
+    assert!(!storage.delete_item(deleted).await?);
+
+    assert!(storage.get_item(deleted).await?.is_none());
+    let listed: Vec<i64> = storage
+        .list_items(None, None, None, None)
+        .await?
+        .into_iter()
+        .map(|item| item.item.id)
+        .collect();
+    assert_eq!(listed, vec![kept]);
+    assert_eq!(storage.list_item_type_counts().await?[0].count, 1);
+    assert_eq!(storage.list_tags().await?[0].usage_count, 0);
+    assert_eq!(found_ids(&db, "compose").await?, vec![kept]);
+    assert_eq!(found_ids(&db, "type:snippet").await?, vec![kept]);
+    // A trashed item cannot be edited
+    assert!(
+        !storage
+            .update_item(UpdateItemDto {
+                id: deleted,
+                item_type: None,
+                title: Some("Edited".to_string()),
+                description: None,
+                content: None,
+                metadata: None,
+                tag_ids: None,
+            })
+            .await?
+    );
+
+    let trash = storage.list_deleted_items().await?;
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].kind, TrashKind::Item);
+    assert_eq!(trash[0].id, deleted);
+    assert_eq!(trash[0].title, "Compose rebuild");
+
+    assert!(storage.restore_item(deleted).await?);
+    assert_eq!(found_ids(&db, "compose").await?, vec![kept, deleted]);
+    assert_eq!(storage.get_item(deleted).await?.unwrap().tags.len(), 1);
+
+    // Retention only reaches entries deleted before the cutoff
+    storage.delete_item(deleted).await?;
+    let deleted_at = storage.list_deleted_items().await?[0].deleted_at;
+    assert_eq!(storage.purge_deleted_items(Some(deleted_at)).await?, 0);
+    assert_eq!(storage.purge_deleted_items(Some(deleted_at + 1)).await?, 1);
+    assert!(storage.list_deleted_items().await?.is_empty());
+    assert!(!storage.restore_item(deleted).await?);
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn deleted_documentation_versions_can_be_restored() -> Result<()> {
+    let db = TestDb::new("trash_docs").await?;
+    let manager = DocumentationManager::new(db.storage.pool.clone());
+    let entries = |content: &str| {
+        vec![ParsedDocEntry {
+            path: "library/asyncio".to_string(),
+            title: "asyncio".to_string(),
+            content: content.to_string(),
+            entry_type: Some("module".to_string()),
+            parent_path: None,
+        }]
+    };
+    let main = manager
+        .install_documentation_with_entries("python", None, entries("event loop on main"))
+        .await?;
+    let v312 = manager
+        .install_documentation_with_entries("python", Some("3.12"), entries("event loop 3.12"))
+        .await?;
+
+    manager.delete_documentation(v312.id).await?;
+    let versions = manager.list_doc_versions("python").await?;
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].id, main.id);
+    assert!(versions[0].is_active);
+    assert!(manager
+        .find_documentation("python", Some("3.12"))
+        .await
+        .is_err());
+
+    // Not even a search pinned to the trashed version reaches it
+    let pinned = SearchQuery {
+        doc_ids: Some(vec![v312.id]),
+        ..text_query("event loop")
+    };
+    assert!(ranked_ids(&db, pinned).await?.is_empty());
+    assert_eq!(found_ids(&db, "event loop").await?.len(), 1);
+
+    let trash = manager.list_deleted_documentations().await?;
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].kind, TrashKind::Documentation);
+    assert_eq!(trash[0].id, v312.id);
+
+    // Another version is active, the restored one comes back inactive
+    assert!(manager.restore_documentation(v312.id).await?);
+    let restored = manager.find_documentation("python", Some("3.12")).await?;
+    assert!(!restored.is_active);
+
+    // With no version left active, the restored one takes over
+    manager.delete_documentation(main.id).await?;
+    assert!(manager.find_documentation("python", None).await?.is_active);
+    manager.delete_documentation(v312.id).await?;
+    assert!(manager.list_installed_documentations().await?.is_empty());
+    assert!(manager.restore_documentation(main.id).await?);
+    assert!(manager.find_documentation("python", None).await?.is_active);
+
+    // Installing a trashed version again replaces the trashed copy
+    let reinstalled = manager
+        .install_documentation_with_entries("python", Some("3.12"), entries("event loop 3.12"))
+        .await?;
+    assert_ne!(reinstalled.id, v312.id);
+    assert!(manager.list_deleted_documentations().await?.is_empty());
+
+    manager.delete_documentation(reinstalled.id).await?;
+    assert_eq!(manager.purge_deleted_documentations(None).await?, 1);
+    let entries: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM doc_entries")
+        .fetch_one(&db.storage.pool)
+        .await?;
+    assert_eq!(entries, 1);
+
+    sqlx::query("DELETE FROM documentations")
+        .execute(&db.storage.pool)
+        .await?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn trashed_documentation_is_hidden_from_mcp_by_id() -> Result<()> {
+    let db = TestDb::new("trash_docs_mcp").await?;
+    let manager = DocumentationManager::new(db.storage.pool.clone());
+    let doc = manager
+        .install_documentation_with_entries("python", None, vec![entry("library/os", "os")])
+        .await?;
+    manager.delete_documentation(doc.id).await?;
+
+    assert!(manager.resolve_doc_version(doc.id, None).await.is_err());
+    assert!(manager.get_doc_tree_level(doc.id, None).await?.is_empty());
+    assert!(manager
+        .get_doc_entry_by_path(doc.id, "library/os")
+        .await
+        .is_err());
+
+    let mut client = McpClient::spawn(&db.db_path, &[]).await?;
+    let tree = client
+        .call_tool("devvault.docs.tree", json!({ "docId": doc.id }))
+        .await?;
+    assert!(tree.is_error);
+    assert!(tree.text.contains("not found"), "{}", tree.text);
+    let entry = client
+        .call_tool(
+            "devvault.docs.entry",
+            json!({ "docId": doc.id, "path": "library/os" }),
+        )
+        .await?;
+    assert!(entry.is_error);
+    client.shutdown().await?;
+
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
@@ -0,0 +1,237 @@
+mod test_support;
+
+use anyhow::Result;
+use dev_vault_lib::models::{
+    CreateItemDto, ImportConflict, ItemType, ItemWithTags, UpdateItemDto, VaultFormat,
+    VaultImportSummary,
+};
+use test_support::TestDb;
+use uuid::Uuid;
+
+async fn seed(db: &TestDb) -> Result<()> {
+    let storage = &db.storage;
+    let docker = storage.create_tag("docker".to_string()).await?;
+    let ops = storage.create_tag("ops".to_string()).await?;
+    storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Snippet,
+            title: "Compose: rebuild".to_string(),
+            description: Some("Rebuild images before starting".to_string()),
+            content: "docker compose up --build\n".to_string(),
+            metadata: Some(serde_json::json!({ "language": "bash" })),
+            tag_ids: Some(vec![docker, ops]),
+        })
+        .await?;
+    storage
+        .create_item(CreateItemDto {
+            item_type: ItemType::Note,
+            title: "---".to_string(),
+            description: None,
+            content: "---\nnot front-matter\n---\n\n- [ ] todo".to_string(),
+            metadata: None,
+            tag_ids: None,
+        })
+        .await?;
+    Ok(())
+}
+
+/// Items sorted by title, with tag names and colors in place of ids
+async fn snapshot(db: &TestDb) -> Result<Vec<serde_json::Value>> {
+    let mut items = db.storage.list_items(Some(100), None, None, None).await?;
+    items.sort_by(|a, b| a.item.title.cmp(&b.item.title));
+    Ok(items
+        .into_iter()
+        .map(|ItemWithTags { item, tags, .. }| {
+            let mut tags: Vec<(String, i64)> =
+                tags.into_iter().map(|t| (t.name, t.color_code)).collect();
+            tags.sort();
+            serde_json::json!({
+                "type": item.item_type,
+                "title": item.title,
+                "description": item.description,
+                "content": item.content,
+                "metadata": item.metadata,
+                "createdAt": item.created_at,
+                "updatedAt": item.updated_at,
+                "tags": tags,
+            })
+        })
+        .collect())
+}
+
+fn without_tag_colors(mut items: Vec<serde_json::Value>) -> Vec<serde_json::Value> {
+    for item in items.iter_mut() {
+        for tag in item["tags"].as_array_mut().unwrap() {
+            tag[1] = serde_json::Value::Null;
+        }
+    }
+    items
+}
+
+#[tokio::test]
+async fn exports_round_trip_through_both_formats() -> Result<()> {
+    let source = TestDb::new("vault_export").await?;
+    seed(&source).await?;
+    sqlx::query("UPDATE items SET created_at = 1700000000, updated_at = 1700000500")
+        .execute(&source.storage.pool)
+        .await?;
+    let expected = snapshot(&source).await?;
+    let export_dir = std::env::temp_dir().join(format!("devvault_export_{}", Uuid::new_v4()));
+
+    for (format, path) in [
+        (VaultFormat::Json, export_dir.join("vault.json")),
+        (VaultFormat::Markdown, export_dir.join("markdown")),
+    ] {
+        assert_eq!(source.storage.export_vault(&path, format).await?, 2);
+
+        let target = TestDb::new("vault_import").await?;
+        let summary = target
+            .storage
+            .import_vault(&path, format, ImportConflict::Skip)
+            .await?;
+        assert_eq!(summary.created, 2);
+
+        let imported = snapshot(&target).await?;
+        if format == VaultFormat::Markdown {
+            // Markdown has no place for tag colors
+            assert_eq!(
+                without_tag_colors(imported),
+                without_tag_colors(expected.clone())
+            );
+        } else {
+            assert_eq!(imported, expected);
+        }
+
+        let db_path = target.db_path.clone();
+        drop(target);
+        test_support::remove_db_file(db_path)?;
+    }
+
+    let front_matter =
+        std::fs::read_to_string(export_dir.join("markdown").join("Compose- rebuild.md"))?;
+    assert!(front_matter.starts_with("---\ntype: snippet\ntitle: \"Compose: rebuild\"\n"));
+    assert!(front_matter.contains("tags: [\"docker\",\"ops\"]\n"));
+    assert!(front_matter.contains("created: 2023-11-14T22:13:20Z\n"));
+
+    std::fs::remove_dir_all(export_dir)?;
+    let db_path = source.db_path.clone();
+    drop(source);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn import_conflicts_follow_the_strategy() -> Result<()> {
+    let db = TestDb::new("vault_conflicts").await?;
+    seed(&db).await?;
+    let dir = std::env::temp_dir().join(format!("devvault_conflicts_{}", Uuid::new_v4()));
+    let path = dir.join("vault.json");
+    db.storage.export_vault(&path, VaultFormat::Json).await?;
+
+    let snippet_id = db
+        .storage
+        .list_items(None, None, Some(ItemType::Snippet), None)
+        .await?[0]
+        .item
+        .id;
+    db.storage
+        .update_item(UpdateItemDto {
+            id: snippet_id,
+            item_type: None,
+            title: None,
+            description: None,
+            content: Some("docker compose up".to_string()),
+            metadata: None,
+            tag_ids: Some(Vec::new()),
+        })
+        .await?;
+
+    let import = |conflict| db.storage.import_vault(&path, VaultFormat::Json, conflict);
+    assert_eq!(
+        import(ImportConflict::Skip).await?,
+        VaultImportSummary {
+            created: 0,
+            updated: 0,
+            skipped: 2,
+        }
+    );
+    assert_eq!(
+        db.storage.get_item(snippet_id).await?.unwrap().item.content,
+        "docker compose up"
+    );
+
+    assert_eq!(import(ImportConflict::Overwrite).await?.updated, 2);
+    let overwritten = db.storage.get_item(snippet_id).await?.unwrap();
+    assert_eq!(overwritten.item.content, "docker compose up --build\n");
+    assert_eq!(overwritten.tags.len(), 2);
+    // The overwritten text stays in the item history
+    assert_eq!(
+        db.storage.list_item_revisions(snippet_id).await?[0].content,
+        "docker compose up"
+    );
+
+    assert_eq!(import(ImportConflict::Duplicate).await?.created, 2);
+    assert_eq!(
+        db.storage.list_items(None, None, None, None).await?.len(),
+        4
+    );
+
+    std::fs::remove_dir_all(dir)?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn hand_written_markdown_is_imported() -> Result<()> {
+    let db = TestDb::new("vault_markdown").await?;
+    let dir = std::env::temp_dir().join(format!("devvault_markdown_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(
+        dir.join("Kubernetes tips.md"),
+        "---\ntype: snippet\ntags:\n  - k8s\n  - 'ops'\ncreated: 2026-01-05\n---\nkubectl get pods -A\n",
+    )?;
+    std::fs::write(dir.join("Plain.md"), "Just text")?;
+    std::fs::write(dir.join("ignored.txt"), "not markdown")?;
+
+    let summary = db
+        .storage
+        .import_vault(&dir, VaultFormat::Markdown, ImportConflict::Skip)
+        .await?;
+    assert_eq!(summary.created, 2);
+
+    let items = db.storage.list_items(None, None, None, None).await?;
+    let tips = items
+        .iter()
+        .find(|item| item.item.title == "Kubernetes tips")
+        .unwrap();
+    assert_eq!(tips.item.item_type, ItemType::Snippet);
+    assert_eq!(tips.item.content, "kubectl get pods -A\n");
+    assert_eq!(tips.item.created_at, 1767571200);
+    assert_eq!(tips.item.updated_at, 1767571200);
+    let mut tags: Vec<&str> = tips.tags.iter().map(|tag| tag.name.as_str()).collect();
+    tags.sort();
+    assert_eq!(tags, vec!["k8s", "ops"]);
+
+    let plain = items
+        .iter()
+        .find(|item| item.item.title == "Plain")
+        .unwrap();
+    assert_eq!(plain.item.item_type, ItemType::Note);
+    assert_eq!(plain.item.content, "Just text");
+
+    std::fs::write(dir.join("Broken.md"), "---\ntype: recipe\n---\n")?;
+    let error = db
+        .storage
+        .import_vault(&dir, VaultFormat::Markdown, ImportConflict::Skip)
+        .await
+        .unwrap_err();
+    assert!(format!("{error:#}").contains("Unknown item type: recipe"));
+
+    std::fs::remove_dir_all(dir)?;
+    let db_path = db.db_path.clone();
+    drop(db);
+    test_support::remove_db_file(db_path)?;
+    Ok(())
+}
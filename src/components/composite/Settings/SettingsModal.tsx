@@ -9,9 +9,13 @@ import {
   AppearanceSection,
   DocumentationSection,
   GeneralSection,
+  LinkedConfigsSection,
+  MarkdownSyncSection,
   McpSection,
   SearchSection,
   ShortcutsSection,
+  TransferSection,
+  TrashSection,
 } from "@/components/composite/Settings/sections";
 import { Button } from "@/components/ui/Button";
 import { Modal } from "@/components/ui/Modal";
@@ -37,6 +41,14 @@ export const SettingsModal = () => {
         return <DocumentationSection />;
       case "mcp":
         return <McpSection />;
+      case "trash":
+        return <TrashSection />;
+      case "transfer":
+        return <TransferSection />;
+      case "sync":
+        return <MarkdownSyncSection />;
+      case "configs":
+        return <LinkedConfigsSection />;
       default:
         return null;
     }
@@ -1,4 +1,15 @@
-import { Book, Keyboard, Palette, Plug, Search, Settings } from "lucide-react";
+import {
+  ArrowUpDown,
+  Book,
+  FileCog,
+  Keyboard,
+  Palette,
+  Plug,
+  RefreshCw,
+  Search,
+  Settings,
+  Trash2,
+} from "lucide-react";
 import type { ReactNode } from "react";
 import { cn } from "@/components/ui";
 
@@ -8,7 +19,11 @@ export type SettingsSection =
   | "search"
   | "shortcuts"
   | "documentation"
-  | "mcp";
+  | "mcp"
+  | "trash"
+  | "transfer"
+  | "sync"
+  | "configs";
 
 interface SidebarItemProps {
   id: SettingsSection;
@@ -34,6 +49,10 @@ const sidebarGroups: SidebarGroup[] = [
       { id: "shortcuts", label: "Сочетания клавиш", icon: <Keyboard size={16} /> },
       { id: "documentation", label: "Документация", icon: <Book size={16} /> },
       { id: "mcp", label: "MCP", icon: <Plug size={16} /> },
+      { id: "trash", label: "Корзина", icon: <Trash2 size={16} /> },
+      { id: "transfer", label: "Импорт и экспорт", icon: <ArrowUpDown size={16} /> },
+      { id: "sync", label: "Синхронизация", icon: <RefreshCw size={16} /> },
+      { id: "configs", label: "Файлы конфигов", icon: <FileCog size={16} /> },
     ],
   },
 ];
@@ -1,10 +1,10 @@
 import { useShallow } from "zustand/react/shallow";
-import { Switch } from "@/components/ui";
+import { Input, Switch } from "@/components/ui";
 import { useSettingsStore } from "@/stores";
 
 export const GeneralSection = () => {
-  const [config, updateUiConfig] = useSettingsStore(
-    useShallow((state) => [state.config, state.updateUiConfig]),
+  const [config, updateUiConfig, updateHistoryConfig] = useSettingsStore(
+    useShallow((state) => [state.config, state.updateUiConfig, state.updateHistoryConfig]),
   );
 
   if (!config) return null;
@@ -19,6 +19,26 @@ export const GeneralSection = () => {
           checked={config.ui.autosave_enabled}
           onCheckedChange={(checked) => updateUiConfig({ autosave_enabled: checked })}
         />
+        <Input
+          label="Версий на айтем в истории (0 — без ограничения)"
+          type="number"
+          min={0}
+          value={config.history.max_revisions_per_item}
+          onChange={(e) =>
+            updateHistoryConfig({
+              max_revisions_per_item: Number.parseInt(e.target.value, 10) || 0,
+            })
+          }
+        />
+        <Input
+          label="Хранить версии, дней (0 — без ограничения)"
+          type="number"
+          min={0}
+          value={config.history.max_age_days}
+          onChange={(e) =>
+            updateHistoryConfig({ max_age_days: Number.parseInt(e.target.value, 10) || 0 })
+          }
+        />
       </div>
     </div>
   );
@@ -0,0 +1,240 @@
+import { listen } from "@tauri-apps/api/event";
+import { useCallback, useEffect, useState } from "react";
+import { toast } from "sonner";
+import {
+  Button,
+  Card,
+  CardContent,
+  CardHeader,
+  CardTitle,
+  Input,
+  Select,
+} from "@/components/ui";
+import { tauriService } from "@/services/tauri";
+import { useItemsStore } from "@/stores";
+import type { ConfigDrift, ConfigLink, ConfigWriteBackPreview, ItemWithTags } from "@/types";
+
+// The list endpoint pages by 50 by default, the picker needs every config
+const CONFIGS_LIMIT = 10_000;
+
+const driftLabel = (drift: ConfigDrift) => {
+  if (drift.missing) return "файл не найден";
+  if (drift.fileChanged && drift.itemChanged) return "изменены и файл, и айтем";
+  if (drift.itemChanged) return "айтем изменен в Dev Vault";
+  return "файл изменен на диске";
+};
+
+export const LinkedConfigsSection = () => {
+  const refreshItems = useItemsStore((state) => state.refreshItems);
+  const [configs, setConfigs] = useState<ItemWithTags[]>([]);
+  const [links, setLinks] = useState<ConfigLink[]>([]);
+  const [drifts, setDrifts] = useState<ConfigDrift[]>([]);
+  const [preview, setPreview] = useState<ConfigWriteBackPreview | null>(null);
+  const [itemId, setItemId] = useState("");
+  const [path, setPath] = useState("");
+  const [isBusy, setIsBusy] = useState(false);
+
+  const loadData = useCallback(async () => {
+    try {
+      const [items, configLinks, report] = await Promise.all([
+        tauriService.listItems(CONFIGS_LIMIT, undefined, "config"),
+        tauriService.listConfigLinks(),
+        tauriService.configDriftReport(),
+      ]);
+      setConfigs(items);
+      setLinks(configLinks);
+      setDrifts(report);
+    } catch (err) {
+      toast.error(`Не удалось загрузить конфиги: ${err}`);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadData();
+    const unlisten = listen<number[]>("linked-configs-changed", () => {
+      loadData();
+    });
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, [loadData]);
+
+  const run = async (action: () => Promise<void>, error: string) => {
+    setIsBusy(true);
+    try {
+      await action();
+      await Promise.all([loadData(), refreshItems()]);
+    } catch (err) {
+      toast.error(`${error}: ${err}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleLink = () =>
+    run(async () => {
+      await tauriService.linkConfigFile(Number(itemId), path.trim());
+      setPath("");
+      toast.success("Конфиг привязан к файлу");
+    }, "Не удалось привязать файл");
+
+  const handlePreview = async (drift: ConfigDrift) => {
+    try {
+      setPreview(await tauriService.previewConfigWriteBack(drift.itemId));
+    } catch (err) {
+      toast.error(`Не удалось построить diff: ${err}`);
+    }
+  };
+
+  const handleWriteBack = (id: number) =>
+    run(async () => {
+      await tauriService.writeConfigBack(id);
+      setPreview(null);
+      toast.success("Конфиг записан на диск");
+    }, "Не удалось записать файл");
+
+  // Linking again takes the file content over, the vault version stays in the history
+  const handleTakeFile = (drift: ConfigDrift) =>
+    run(async () => {
+      await tauriService.linkConfigFile(drift.itemId, drift.path);
+      setPreview((prev) => (prev?.itemId === drift.itemId ? null : prev));
+    }, "Не удалось прочитать файл");
+
+  const handleUnlink = (id: number) =>
+    run(async () => {
+      await tauriService.unlinkConfigFile(id);
+    }, "Не удалось отвязать файл");
+
+  const sourcePaths = new Map(links.map((link) => [link.itemId, link.path]));
+  const linked = configs.filter((item) => sourcePaths.has(item.id));
+  const unlinked = configs.filter((item) => !sourcePaths.has(item.id));
+
+  return (
+    <div className="flex flex-col gap-6">
+      <div>
+        <h3 className="text-lg font-medium mb-2">Файлы конфигов</h3>
+        <p className="text-sm text-muted-foreground">
+          Конфиг можно привязать к файлу на диске, например ~/.config/nvim/init.lua. Правки
+          файла подтягиваются в айтем автоматически, прежняя версия остается в истории; если
+          айтем тоже изменен, показывается расхождение. Правки в Dev Vault записываются обратно
+          на диск только вручную, после просмотра diff.
+        </p>
+      </div>
+
+      <Card className="rounded-2xl border-none bg-background/55 shadow-none">
+        <CardHeader>
+          <CardTitle className="text-base">Привязать файл</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <Select
+            label="Конфиг"
+            value={itemId}
+            onChange={(e) => setItemId(e.target.value)}
+            options={[
+              { value: "", label: "Выберите конфиг" },
+              ...unlinked.map((item) => ({ value: String(item.id), label: item.title })),
+            ]}
+          />
+          <Input
+            label="Путь к файлу"
+            placeholder="~/.config/nvim/init.lua"
+            value={path}
+            onChange={(e) => setPath(e.target.value)}
+          />
+          <div className="flex justify-end">
+            <Button onClick={handleLink} disabled={isBusy || !itemId || !path.trim()}>
+              Привязать
+            </Button>
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card className="rounded-2xl border-none bg-background/55 shadow-none">
+        <CardHeader>
+          <CardTitle className="text-base">Расхождения</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-3">
+          {drifts.length === 0 ? (
+            <div className="text-sm text-muted-foreground">
+              Все привязанные конфиги совпадают со своими файлами
+            </div>
+          ) : null}
+          {drifts.map((drift) => (
+            <div key={drift.itemId} className="flex flex-col gap-2 rounded-xl bg-muted/45 px-3 py-2">
+              <div className="flex items-center justify-between gap-4">
+                <div className="flex flex-col gap-1">
+                  <span className="font-medium text-sm">{drift.title}</span>
+                  <div className="text-xs text-muted-foreground">
+                    {drift.path} · {driftLabel(drift)}
+                  </div>
+                </div>
+                <div className="flex items-center gap-2">
+                  <Button
+                    size="sm"
+                    variant="ghost"
+                    onClick={() => handleTakeFile(drift)}
+                    disabled={isBusy || drift.missing}
+                  >
+                    Взять с диска
+                  </Button>
+                  <Button size="sm" onClick={() => handlePreview(drift)} disabled={isBusy}>
+                    Записать на диск
+                  </Button>
+                </div>
+              </div>
+              {preview?.itemId === drift.itemId ? (
+                <div className="flex flex-col gap-2">
+                  <pre className="max-h-72 overflow-auto rounded-lg bg-background/70 p-3 font-mono text-xs">
+                    {preview.exists ? preview.diff : `Файл будет создан\n${preview.diff}`}
+                  </pre>
+                  <div className="flex justify-end gap-2">
+                    <Button size="sm" variant="ghost" onClick={() => setPreview(null)}>
+                      Отмена
+                    </Button>
+                    <Button
+                      size="sm"
+                      onClick={() => handleWriteBack(drift.itemId)}
+                      disabled={isBusy}
+                    >
+                      Перезаписать {preview.path}
+                    </Button>
+                  </div>
+                </div>
+              ) : null}
+            </div>
+          ))}
+        </CardContent>
+      </Card>
+
+      <Card className="rounded-2xl border-none bg-background/55 shadow-none">
+        <CardHeader>
+          <CardTitle className="text-base">Привязанные конфиги</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-3">
+          {linked.length === 0 ? (
+            <div className="text-sm text-muted-foreground">Нет привязанных конфигов</div>
+          ) : null}
+          {linked.map((item) => (
+            <div
+              key={item.id}
+              className="flex items-center justify-between gap-4 rounded-xl bg-muted/45 px-3 py-2"
+            >
+              <div className="flex flex-col gap-1">
+                <span className="font-medium text-sm">{item.title}</span>
+                <div className="text-xs text-muted-foreground">{sourcePaths.get(item.id)}</div>
+              </div>
+              <Button
+                size="sm"
+                variant="ghost"
+                onClick={() => handleUnlink(item.id)}
+                disabled={isBusy}
+              >
+                Отвязать
+              </Button>
+            </div>
+          ))}
+        </CardContent>
+      </Card>
+    </div>
+  );
+};
@@ -0,0 +1,159 @@
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { useShallow } from "zustand/react/shallow";
+import {
+  Button,
+  Card,
+  CardContent,
+  CardHeader,
+  CardTitle,
+  Input,
+  Switch,
+} from "@/components/ui";
+import { tauriService } from "@/services/tauri";
+import { useItemsStore, useSettingsStore } from "@/stores";
+import type { MarkdownSyncConflict, MarkdownSyncReport, SyncSide } from "@/types";
+
+export const MarkdownSyncSection = () => {
+  const [config, updateMarkdownSyncConfig] = useSettingsStore(
+    useShallow((state) => [state.config, state.updateMarkdownSyncConfig]),
+  );
+  const refreshItems = useItemsStore((state) => state.refreshItems);
+  const [directory, setDirectory] = useState(config?.markdown_sync.directory ?? "");
+  const [conflicts, setConflicts] = useState<MarkdownSyncConflict[]>([]);
+  const [isSyncing, setIsSyncing] = useState(false);
+
+  useEffect(() => {
+    setDirectory(config?.markdown_sync.directory ?? "");
+  }, [config?.markdown_sync.directory]);
+
+  useEffect(() => {
+    const unlisten = listen<MarkdownSyncReport>("markdown-sync", (event) => {
+      setConflicts(event.payload.conflicts);
+    });
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
+  // Saved once editing is done, a half-typed path must not become the synced folder
+  const commitDirectory = () => {
+    if (directory.trim() !== config?.markdown_sync.directory) {
+      updateMarkdownSyncConfig({ directory: directory.trim() });
+    }
+  };
+
+  const handleSync = async () => {
+    setIsSyncing(true);
+    try {
+      const report = await tauriService.syncMarkdownNow();
+      setConflicts(report.conflicts);
+      toast.success(
+        `Файлов записано: ${report.exported + report.updatedFiles}, заметок обновлено: ${
+          report.imported + report.updatedItems
+        }, конфликтов: ${report.conflicts.length}`,
+      );
+      await refreshItems();
+    } catch (err) {
+      toast.error(`Не удалось синхронизировать: ${err}`);
+    } finally {
+      setIsSyncing(false);
+    }
+  };
+
+  const handleResolve = async (conflict: MarkdownSyncConflict, keep: SyncSide) => {
+    try {
+      await tauriService.resolveMarkdownConflict(conflict.itemId, keep);
+      setConflicts((prev) => prev.filter((c) => c.itemId !== conflict.itemId));
+      if (keep === "file") {
+        await refreshItems();
+      }
+    } catch (err) {
+      toast.error(`Не удалось разрешить конфликт: ${err}`);
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-6">
+      <div>
+        <h3 className="text-lg font-medium mb-2">Синхронизация с Markdown</h3>
+        <p className="text-sm text-muted-foreground">
+          Заметки зеркалируются в .md-файлы с front-matter в выбранной папке, например в
+          хранилище Obsidian. Правки файлов попадают обратно в заметки, теги берутся из
+          front-matter и #тегов в тексте. Скрытые папки вроде .obsidian не трогаются, файлы
+          удаленных заметок переносятся в .devvault-trash. Папка должна уже существовать.
+        </p>
+      </div>
+
+      {config ? (
+        <div className="flex flex-col gap-4">
+          <Switch
+            label="Синхронизировать заметки с папкой"
+            className="w-fit px-0"
+            checked={config.markdown_sync.enabled}
+            onCheckedChange={(checked) => updateMarkdownSyncConfig({ enabled: checked })}
+          />
+          <Input
+            label="Папка"
+            placeholder="/Users/me/Obsidian/Dev"
+            value={directory}
+            onChange={(e) => setDirectory(e.target.value)}
+            onBlur={commitDirectory}
+          />
+          <Input
+            label="Проверять изменения каждые, секунд"
+            type="number"
+            min={1}
+            value={config.markdown_sync.interval_secs}
+            onChange={(e) =>
+              updateMarkdownSyncConfig({
+                interval_secs: Number.parseInt(e.target.value, 10) || 1,
+              })
+            }
+          />
+          <div className="flex justify-end">
+            <Button onClick={handleSync} disabled={isSyncing || !config.markdown_sync.directory}>
+              Синхронизировать сейчас
+            </Button>
+          </div>
+        </div>
+      ) : null}
+
+      <Card className="rounded-2xl border-none bg-background/55 shadow-none">
+        <CardHeader>
+          <CardTitle className="text-base">Конфликты</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-3">
+          {conflicts.length === 0 ? (
+            <div className="text-sm text-muted-foreground">
+              Заметки, измененные и в Dev Vault, и в файле, появятся здесь
+            </div>
+          ) : null}
+          {conflicts.map((conflict) => (
+            <div
+              key={conflict.itemId}
+              className="flex items-center justify-between gap-4 rounded-xl bg-muted/45 px-3 py-2"
+            >
+              <div className="flex flex-col gap-1">
+                <span className="font-medium text-sm">{conflict.title}</span>
+                <div className="text-xs text-muted-foreground">
+                  {conflict.path} · заметка: {new Date(conflict.itemUpdatedAt * 1000).toLocaleString()}{" "}
+                  · файл: {new Date(conflict.fileModifiedAt * 1000).toLocaleString()}
+                </div>
+              </div>
+              <div className="flex items-center gap-2">
+                <Button size="sm" variant="ghost" onClick={() => handleResolve(conflict, "file")}>
+                  Оставить файл
+                </Button>
+                <Button size="sm" onClick={() => handleResolve(conflict, "vault")}>
+                  Оставить заметку
+                </Button>
+              </div>
+            </div>
+          ))}
+        </CardContent>
+      </Card>
+    </div>
+  );
+};
@@ -5,7 +5,8 @@ import type { AiToolStatus, McpServerConfig } from "@/types";
 
 const buildSnippet = (config: McpServerConfig | null) => {
   if (!config) return "";
-  return `codex mcp add ${config.name} -- ${[config.command, ...config.args].join(" ")}`;
+  const entry = { command: config.command, args: config.args };
+  return JSON.stringify({ mcpServers: { [config.name]: entry } }, null, 2);
 };
 
 export const McpSection = () => {
@@ -52,13 +53,27 @@ export const McpSection = () => {
     }
   };
 
+  const handleDisconnect = async (toolId: string) => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      const updated = await invoke<AiToolStatus>("disconnect_mcp_server", { toolId });
+      setTools((prev) => prev.map((tool) => (tool.id === updated.id ? updated : tool)));
+    } catch (err) {
+      setError("Не удалось отключить MCP");
+      console.error(err);
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
   return (
     <div className="flex flex-col gap-6">
       <div>
         <h3 className="text-lg font-medium mb-2">MCP-сервер</h3>
         <p className="text-sm text-muted-foreground">
-          Dev Vault предоставляет MCP-сервер в режиме только чтения. Подключение сейчас доступно
-          только для Codex через CLI.
+          Dev Vault предоставляет MCP-сервер для AI-агентов. При подключении запись добавляется в
+          конфиг агента, прежняя версия файла сохраняется рядом с расширением .devvault.bak.
         </p>
       </div>
 
@@ -84,7 +99,7 @@ export const McpSection = () => {
                 <div className="font-mono text-xs break-all">{serverConfig.args.join(" ")}</div>
               </div>
               <div className="text-sm">
-                <div className="text-muted-foreground mb-2">Команда подключения</div>
+                <div className="text-muted-foreground mb-2">Ручная настройка</div>
                 <pre className="overflow-x-auto rounded-xl bg-muted/50 p-3 text-xs">{snippet}</pre>
               </div>
             </div>
@@ -117,7 +132,8 @@ export const McpSection = () => {
                     ) : (
                       <Badge variant="outline">Не найден</Badge>
                     )}
-                    {tool.mcpInstalled ? <Badge>Подключен</Badge> : null}
+                    {tool.mcpInstalled && !tool.mcpOutdated ? <Badge>Подключен</Badge> : null}
+                    {tool.mcpOutdated ? <Badge variant="outline">Устарел</Badge> : null}
                   </div>
                   {tool.configPath ? (
                     <div className="text-xs text-muted-foreground break-all">{tool.configPath}</div>
@@ -127,15 +143,25 @@ export const McpSection = () => {
                   ) : null}
                   {!tool.detected ? (
                     <div className="text-xs text-muted-foreground">
-                      Установите Codex CLI и убедитесь, что команда доступна в PATH.
+                      {tool.name} не найден на этом компьютере. Установите его или подключите вручную.
                     </div>
                   ) : null}
                 </div>
-                <div>
+                <div className="flex items-center gap-2">
+                  {tool.mcpInstalled ? (
+                    <Button
+                      size="sm"
+                      variant="ghost"
+                      onClick={() => handleDisconnect(tool.id)}
+                      disabled={isLoading}
+                    >
+                      Отключить
+                    </Button>
+                  ) : null}
                   {tool.supportsAutoConnect ? (
                     <Button
                       size="sm"
-                      variant={tool.mcpInstalled ? "secondary" : "primary"}
+                      variant={tool.mcpInstalled && !tool.mcpOutdated ? "secondary" : "primary"}
                       onClick={() => handleConnect(tool.id)}
                       disabled={isLoading || !tool.detected}
                     >
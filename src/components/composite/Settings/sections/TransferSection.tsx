@@ -0,0 +1,194 @@
+import { useState } from "react";
+import { toast } from "sonner";
+import { Button, Card, CardContent, CardHeader, CardTitle, Input, Select } from "@/components/ui";
+import { tauriService } from "@/services/tauri";
+import { useItemsStore } from "@/stores";
+import type { ImportConflict, SnippetFormat, VaultFormat } from "@/types";
+
+export const TransferSection = () => {
+  const refreshItems = useItemsStore((state) => state.refreshItems);
+  const tags = useItemsStore((state) => state.tags);
+  const [path, setPath] = useState("");
+  const [format, setFormat] = useState<VaultFormat>("json");
+  const [conflict, setConflict] = useState<ImportConflict>("skip");
+  const [isBusy, setIsBusy] = useState(false);
+  const [snippetPath, setSnippetPath] = useState("");
+  const [snippetFormat, setSnippetFormat] = useState<SnippetFormat>("vscode");
+  const [exportTagId, setExportTagId] = useState("");
+  const [exportQuery, setExportQuery] = useState("");
+
+  const handleExport = async () => {
+    setIsBusy(true);
+    try {
+      const count = await tauriService.exportVault(path.trim(), format);
+      toast.success(`Экспортировано айтемов: ${count}`);
+    } catch (err) {
+      toast.error(`Не удалось экспортировать: ${err}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleImport = async () => {
+    setIsBusy(true);
+    try {
+      const summary = await tauriService.importVault(path.trim(), format, conflict);
+      toast.success(
+        `Создано: ${summary.created}, обновлено: ${summary.updated}, пропущено: ${summary.skipped}`,
+      );
+      await refreshItems();
+    } catch (err) {
+      toast.error(`Не удалось импортировать: ${err}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleSnippetImport = async () => {
+    setIsBusy(true);
+    try {
+      const summary = await tauriService.importSnippets(
+        snippetPath.trim(),
+        snippetFormat,
+        conflict,
+      );
+      toast.success(
+        `Создано: ${summary.created}, обновлено: ${summary.updated}, пропущено: ${summary.skipped}`,
+      );
+      await refreshItems();
+    } catch (err) {
+      toast.error(`Не удалось импортировать сниппеты: ${err}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleSnippetExport = async () => {
+    setIsBusy(true);
+    try {
+      const query = exportQuery.trim();
+      const count = await tauriService.exportVscodeSnippets(
+        snippetPath.trim(),
+        exportTagId ? Number(exportTagId) : undefined,
+        query
+          ? { query, tagIds: exportTagId ? [Number(exportTagId)] : undefined, limit: 1000 }
+          : undefined,
+      );
+      toast.success(`Экспортировано сниппетов: ${count}`);
+    } catch (err) {
+      toast.error(`Не удалось экспортировать сниппеты: ${err}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-6">
+      <div>
+        <h3 className="text-lg font-medium mb-2">Импорт и экспорт</h3>
+        <p className="text-sm text-muted-foreground">
+          JSON-бандл сохраняет айтемы, теги и их цвета в одном файле. Markdown-папка содержит по
+          файлу на айтем, тип, теги, описание и даты лежат во front-matter.
+        </p>
+      </div>
+
+      <Card className="rounded-2xl border-none bg-background/55 shadow-none">
+        <CardHeader>
+          <CardTitle className="text-base">Хранилище</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <Select
+            label="Формат"
+            value={format}
+            onChange={(e) => setFormat(e.target.value as VaultFormat)}
+            options={[
+              { value: "json", label: "JSON-бандл (файл)" },
+              { value: "markdown", label: "Markdown (папка)" },
+            ]}
+          />
+          <Input
+            label={format === "json" ? "Путь к файлу" : "Путь к папке"}
+            placeholder={
+              format === "json" ? "/Users/me/Backups/dev-vault.json" : "/Users/me/Notes/dev-vault"
+            }
+            value={path}
+            onChange={(e) => setPath(e.target.value)}
+          />
+          <Select
+            label="Если айтем с таким типом и названием уже есть"
+            value={conflict}
+            onChange={(e) => setConflict(e.target.value as ImportConflict)}
+            options={[
+              { value: "skip", label: "Пропустить" },
+              { value: "overwrite", label: "Перезаписать" },
+              { value: "duplicate", label: "Создать копию" },
+            ]}
+          />
+          <div className="flex justify-end gap-2">
+            <Button variant="ghost" onClick={handleImport} disabled={isBusy || !path.trim()}>
+              Импортировать
+            </Button>
+            <Button onClick={handleExport} disabled={isBusy || !path.trim()}>
+              Экспортировать
+            </Button>
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card className="rounded-2xl border-none bg-background/55 shadow-none">
+        <CardHeader>
+          <CardTitle className="text-base">Сниппеты редакторов</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <p className="text-sm text-muted-foreground">
+            Импорт превращает сниппеты VS Code и live templates JetBrains в айтемы-сниппеты:
+            префикс и тело с плейсхолдерами сохраняются в метаданных, scope становится языком и
+            тегами. Экспорт всегда пишет файл сниппетов VS Code.
+          </p>
+          <Select
+            label="Формат импорта"
+            value={snippetFormat}
+            onChange={(e) => setSnippetFormat(e.target.value as SnippetFormat)}
+            options={[
+              { value: "vscode", label: "VS Code (.code-snippets, .json)" },
+              { value: "jetbrains", label: "JetBrains live templates (.xml)" },
+            ]}
+          />
+          <Input
+            label="Путь к файлу"
+            placeholder="/Users/me/.config/Code/User/snippets/dev-vault.code-snippets"
+            value={snippetPath}
+            onChange={(e) => setSnippetPath(e.target.value)}
+          />
+          <Select
+            label="Экспортировать айтемы с тегом"
+            value={exportTagId}
+            onChange={(e) => setExportTagId(e.target.value)}
+            options={[
+              { value: "", label: "Все айтемы" },
+              ...tags.map((tag) => ({ value: String(tag.id), label: tag.name })),
+            ]}
+          />
+          <Input
+            label="Или результаты поиска"
+            placeholder="lang:typescript react hook"
+            value={exportQuery}
+            onChange={(e) => setExportQuery(e.target.value)}
+          />
+          <div className="flex justify-end gap-2">
+            <Button
+              variant="ghost"
+              onClick={handleSnippetImport}
+              disabled={isBusy || !snippetPath.trim()}
+            >
+              Импортировать
+            </Button>
+            <Button onClick={handleSnippetExport} disabled={isBusy || !snippetPath.trim()}>
+              Экспортировать в VS Code
+            </Button>
+          </div>
+        </CardContent>
+      </Card>
+    </div>
+  );
+};
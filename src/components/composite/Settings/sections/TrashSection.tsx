@@ -0,0 +1,137 @@
+import { useCallback, useEffect, useState } from "react";
+import { toast } from "sonner";
+import { useShallow } from "zustand/react/shallow";
+import { Badge, Button, Card, CardContent, CardHeader, CardTitle, Input } from "@/components/ui";
+import { tauriService } from "@/services/tauri";
+import { useDocsStore, useItemsStore, useSettingsStore } from "@/stores";
+import type { TrashEntry } from "@/types";
+
+export const TrashSection = () => {
+  const [config, updateTrashConfig] = useSettingsStore(
+    useShallow((state) => [state.config, state.updateTrashConfig]),
+  );
+  const refreshItems = useItemsStore((state) => state.refreshItems);
+  const loadInstalledDocs = useDocsStore((state) => state.loadInstalledDocs);
+  const [entries, setEntries] = useState<TrashEntry[]>([]);
+  const [isLoading, setIsLoading] = useState(false);
+
+  const loadTrash = useCallback(async () => {
+    setIsLoading(true);
+    try {
+      setEntries(await tauriService.listTrash());
+    } catch (err) {
+      toast.error("Не удалось загрузить корзину");
+      console.error(err);
+    } finally {
+      setIsLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadTrash();
+  }, [loadTrash]);
+
+  const handleRestore = async (entry: TrashEntry) => {
+    try {
+      if (entry.kind === "item") {
+        await tauriService.restoreItem(entry.id);
+        await refreshItems();
+      } else {
+        await tauriService.restoreDocumentation(entry.id);
+        await loadInstalledDocs();
+      }
+      setEntries((prev) => prev.filter((e) => e.kind !== entry.kind || e.id !== entry.id));
+    } catch (err) {
+      toast.error("Не удалось восстановить");
+      console.error(err);
+    }
+  };
+
+  const handlePurge = async (entry: TrashEntry) => {
+    try {
+      await tauriService.purgeFromTrash(entry.kind, entry.id);
+      setEntries((prev) => prev.filter((e) => e.kind !== entry.kind || e.id !== entry.id));
+    } catch (err) {
+      toast.error("Не удалось удалить");
+      console.error(err);
+    }
+  };
+
+  const handleEmpty = async () => {
+    try {
+      await tauriService.emptyTrash();
+      setEntries([]);
+    } catch (err) {
+      toast.error("Не удалось очистить корзину");
+      console.error(err);
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-6">
+      <div>
+        <h3 className="text-lg font-medium mb-2">Корзина</h3>
+        <p className="text-sm text-muted-foreground">
+          Удаленные айтемы и документация хранятся здесь, пока не истечет срок хранения.
+        </p>
+      </div>
+
+      {config ? (
+        <Input
+          label="Хранить в корзине, дней (0 — без ограничения)"
+          type="number"
+          min={0}
+          value={config.trash.retention_days}
+          onChange={(e) =>
+            updateTrashConfig({ retention_days: Number.parseInt(e.target.value, 10) || 0 })
+          }
+        />
+      ) : null}
+
+      <Card className="rounded-2xl border-none bg-background/55 shadow-none">
+        <CardHeader className="flex flex-row items-center justify-between">
+          <CardTitle className="text-base">Удаленные</CardTitle>
+          <Button
+            size="sm"
+            variant="ghost"
+            onClick={handleEmpty}
+            disabled={isLoading || entries.length === 0}
+          >
+            Очистить корзину
+          </Button>
+        </CardHeader>
+        <CardContent className="space-y-3">
+          {entries.length === 0 && !isLoading ? (
+            <div className="text-sm text-muted-foreground">Корзина пуста</div>
+          ) : null}
+          {entries.map((entry) => (
+            <div
+              key={`${entry.kind}-${entry.id}`}
+              className="flex items-center justify-between gap-4 rounded-xl bg-muted/45 px-3 py-2"
+            >
+              <div className="flex flex-col gap-1">
+                <div className="flex items-center gap-2">
+                  <span className="font-medium text-sm">{entry.title}</span>
+                  <Badge variant="outline">
+                    {entry.kind === "documentation" ? "documentation" : entry.itemType}
+                  </Badge>
+                </div>
+                <div className="text-xs text-muted-foreground">
+                  Удалено: {new Date(entry.deletedAt * 1000).toLocaleString()}
+                </div>
+              </div>
+              <div className="flex items-center gap-2">
+                <Button size="sm" variant="ghost" onClick={() => handlePurge(entry)}>
+                  Удалить навсегда
+                </Button>
+                <Button size="sm" onClick={() => handleRestore(entry)}>
+                  Восстановить
+                </Button>
+              </div>
+            </div>
+          ))}
+        </CardContent>
+      </Card>
+    </div>
+  );
+};
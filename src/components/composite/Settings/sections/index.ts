@@ -1,6 +1,10 @@
 export * from "./AppearanceSection";
 export * from "./DocumentationSection";
 export * from "./GeneralSection";
+export * from "./LinkedConfigsSection";
+export * from "./MarkdownSyncSection";
 export * from "./McpSection";
 export * from "./SearchSection";
 export * from "./ShortcutsSection";
+export * from "./TransferSection";
+export * from "./TrashSection";
@@ -134,11 +134,23 @@ export const MainLayout = () => {
       openSettings();
     });
 
+    // The background Markdown sync changed notes or their files
+    const unlistenMarkdownSync = listen("markdown-sync", () => {
+      useItemsStore.getState().refreshItems();
+    });
+
+    // A linked config file was edited on disk
+    const unlistenLinkedConfigs = listen("linked-configs-changed", () => {
+      useItemsStore.getState().refreshItems();
+    });
+
     return () => {
       unlistenSearch.then((f) => f());
       unlistenNewTab.then((f) => f());
       unlistenCreateItem.then((f) => f());
       unlistenSettings.then((f) => f());
+      unlistenMarkdownSync.then((f) => f());
+      unlistenLinkedConfigs.then((f) => f());
     };
   }, [openNewTab, handleCreateClick, openSettings]);
 
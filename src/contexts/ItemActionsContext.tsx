@@ -53,7 +53,7 @@ export const ItemActionsProvider = ({ children }: ItemActionsProviderProps) => {
         <div className="space-y-4">
           <p className="text-sm text-muted-foreground">
             Элемент <span className="font-medium text-foreground">{pendingDelete?.title}</span>{" "}
-            будет удален без возможности восстановления.
+            будет перемещен в корзину. Восстановить его можно в Настройки → Корзина.
           </p>
           <div className="flex justify-end gap-2">
             <Button variant="ghost" onClick={handleClose}>
@@ -1,17 +1,32 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   AvailableDocumentation,
+  ConfigDrift,
+  ConfigLink,
+  ConfigWriteBackPreview,
   CreateItemDto,
+  CustomDocSourcesReport,
   DocEntry,
+  DocInstallCheckpoint,
   DocTreeNode,
   Documentation,
   DocumentationGraph,
+  DocumentationUpdate,
+  ItemRevision,
   ItemType,
   ItemWithTags,
+  MarkdownSyncReport,
   SearchQuery,
   SearchResult,
+  ImportConflict,
+  SnippetFormat,
+  SyncSide,
   Tag,
+  TrashEntry,
+  TrashKind,
   UpdateItemDto,
+  VaultFormat,
+  VaultImportSummary,
 } from "@/types";
 
 export const tauriService = {
@@ -31,6 +46,101 @@ export const tauriService = {
     return invoke<boolean>("delete_item", { id });
   },
 
+  async restoreItem(id: number): Promise<boolean> {
+    return invoke<boolean>("restore_item", { id });
+  },
+
+  async listTrash(): Promise<TrashEntry[]> {
+    return invoke<TrashEntry[]>("list_trash");
+  },
+
+  async purgeFromTrash(kind: TrashKind, id: number): Promise<boolean> {
+    return invoke<boolean>("purge_from_trash", { kind, id });
+  },
+
+  async emptyTrash(): Promise<void> {
+    return invoke<void>("empty_trash");
+  },
+
+  /** JSON goes to a file, Markdown to a folder; resolves to the number of exported items */
+  async exportVault(path: string, format: VaultFormat): Promise<number> {
+    return invoke<number>("export_vault", { path, format });
+  },
+
+  async importVault(
+    path: string,
+    format: VaultFormat,
+    conflict: ImportConflict,
+  ): Promise<VaultImportSummary> {
+    return invoke<VaultImportSummary>("import_vault", { path, format, conflict });
+  },
+
+  async importSnippets(
+    path: string,
+    format: SnippetFormat,
+    conflict: ImportConflict,
+  ): Promise<VaultImportSummary> {
+    return invoke<VaultImportSummary>("import_snippets", { path, format, conflict });
+  },
+
+  async exportVscodeSnippets(
+    path: string,
+    tagId?: number,
+    query?: SearchQuery,
+  ): Promise<number> {
+    return invoke<number>("export_vscode_snippets", { path, tagId, query });
+  },
+
+  async syncMarkdownNow(): Promise<MarkdownSyncReport> {
+    return invoke<MarkdownSyncReport>("sync_markdown_now");
+  },
+
+  async resolveMarkdownConflict(itemId: number, keep: SyncSide): Promise<void> {
+    return invoke<void>("resolve_markdown_conflict", { itemId, keep });
+  },
+
+  /** Takes over the file content, later edits of the file update the item */
+  async linkConfigFile(itemId: number, path: string): Promise<void> {
+    return invoke<void>("link_config_file", { itemId, path });
+  },
+
+  async unlinkConfigFile(itemId: number): Promise<void> {
+    return invoke<void>("unlink_config_file", { itemId });
+  },
+
+  async listConfigLinks(): Promise<ConfigLink[]> {
+    return invoke<ConfigLink[]>("list_config_links");
+  },
+
+  async configDriftReport(): Promise<ConfigDrift[]> {
+    return invoke<ConfigDrift[]>("config_drift_report");
+  },
+
+  async previewConfigWriteBack(itemId: number): Promise<ConfigWriteBackPreview> {
+    return invoke<ConfigWriteBackPreview>("preview_config_write_back", { itemId });
+  },
+
+  async writeConfigBack(itemId: number): Promise<void> {
+    return invoke<void>("write_config_back", { itemId });
+  },
+
+  async listItemRevisions(itemId: number): Promise<ItemRevision[]> {
+    return invoke<ItemRevision[]>("list_item_revisions", { itemId });
+  },
+
+  /** `null` on either side is the current version of the item */
+  async diffItemRevisions(
+    itemId: number,
+    fromRevisionId: number | null,
+    toRevisionId: number | null,
+  ): Promise<string> {
+    return invoke<string>("diff_item_revisions", { itemId, fromRevisionId, toRevisionId });
+  },
+
+  async restoreItemRevision(itemId: number, revisionId: number): Promise<boolean> {
+    return invoke<boolean>("restore_item_revision", { itemId, revisionId });
+  },
+
   async listItems(
     limit?: number,
     offset?: number,
@@ -73,32 +183,83 @@ export const tauriService = {
     return invoke<AvailableDocumentation[]>("list_available_docs");
   },
 
+  async listCustomDocSources(): Promise<CustomDocSourcesReport> {
+    return invoke<CustomDocSourcesReport>("list_custom_doc_sources");
+  },
+
+  async addCustomDocSource(
+    content: string,
+    format: "toml" | "json",
+  ): Promise<CustomDocSourcesReport> {
+    return invoke<CustomDocSourcesReport>("add_custom_doc_source", { content, format });
+  },
+
+  async removeCustomDocSource(name: string): Promise<CustomDocSourcesReport> {
+    return invoke<CustomDocSourcesReport>("remove_custom_doc_source", { name });
+  },
+
   async listInstalledDocs(): Promise<Documentation[]> {
     return invoke<Documentation[]>("list_installed_docs");
   },
 
-  async installDocumentation(name: string): Promise<Documentation> {
-    return invoke<Documentation>("install_documentation", { name });
+  async installDocumentation(name: string, version?: string): Promise<Documentation> {
+    return invoke<Documentation>("install_documentation", { name, version });
+  },
+
+  async cancelDocInstall(name: string): Promise<boolean> {
+    return invoke<boolean>("cancel_doc_install", { name });
+  },
+
+  async listDocInstallCheckpoints(): Promise<DocInstallCheckpoint[]> {
+    return invoke<DocInstallCheckpoint[]>("list_doc_install_checkpoints");
+  },
+
+  async discardDocInstallCheckpoint(name: string, version: string): Promise<boolean> {
+    return invoke<boolean>("discard_doc_install_checkpoint", { name, version });
+  },
+
+  async listDocVersions(name: string): Promise<Documentation[]> {
+    return invoke<Documentation[]>("list_doc_versions", { name });
   },
 
-  async updateDocumentation(docId: number): Promise<Documentation> {
-    return invoke<Documentation>("update_documentation", { docId });
+  async setActiveDocVersion(docId: number): Promise<Documentation> {
+    return invoke<Documentation>("set_active_doc_version", { docId });
+  },
+
+  async updateDocumentation(docId: number): Promise<DocumentationUpdate> {
+    return invoke<DocumentationUpdate>("update_documentation", { docId });
+  },
+
+  async rebuildDocumentationFromCache(docId: number): Promise<DocumentationUpdate> {
+    return invoke<DocumentationUpdate>("rebuild_documentation_from_cache", { docId });
+  },
+
+  async clearDocHttpCache(name: string): Promise<number> {
+    return invoke<number>("clear_doc_http_cache", { name });
   },
 
   async deleteDocumentation(docId: number): Promise<void> {
     return invoke<void>("delete_documentation", { docId });
   },
 
+  async restoreDocumentation(docId: number): Promise<boolean> {
+    return invoke<boolean>("restore_documentation", { docId });
+  },
+
   async getDocEntries(docId: number, parentPath?: string): Promise<DocEntry[]> {
     return invoke<DocEntry[]>("get_doc_entries", { docId, parentPath });
   },
 
-  async getDocEntryByPath(docId: number, path: string): Promise<DocEntry> {
-    return invoke<DocEntry>("get_doc_entry_by_path", { docId, path });
+  async getDocEntryByPath(docId: number, path: string, version?: string): Promise<DocEntry> {
+    return invoke<DocEntry>("get_doc_entry_by_path", { docId, path, version });
   },
 
-  async getDocTree(docId: number, parentPath?: string): Promise<DocTreeNode[]> {
-    return invoke<DocTreeNode[]>("get_doc_tree", { docId, parentPath });
+  async getDocTree(
+    docId: number,
+    parentPath?: string,
+    version?: string,
+  ): Promise<DocTreeNode[]> {
+    return invoke<DocTreeNode[]>("get_doc_tree", { docId, parentPath, version });
   },
 
   async getDocGraph(docId: number): Promise<DocumentationGraph> {
@@ -7,6 +7,7 @@ import type {
   DocTreeNode,
   Documentation,
   DocumentationGraph,
+  DocumentationUpdate,
   ScrapeProgress,
 } from "@/types";
 
@@ -25,8 +26,11 @@ interface DocsState {
 
   loadAvailableDocs: () => Promise<void>;
   loadInstalledDocs: () => Promise<void>;
-  installDoc: (name: string) => Promise<void>;
+  installDoc: (name: string, version?: string) => Promise<void>;
+  cancelInstall: (name: string) => Promise<void>;
+  setActiveVersion: (docId: number) => Promise<void>;
   updateDoc: (docId: number) => Promise<void>;
+  rebuildDocFromCache: (docId: number) => Promise<void>;
   deleteDoc: (docId: number) => Promise<void>;
   selectDoc: (doc: Documentation | null) => void;
   loadDocTree: (docId: number) => Promise<void>;
@@ -36,6 +40,15 @@ interface DocsState {
   clearSelectedEntry: () => void;
 }
 
+/** Error returned by `install_documentation` when the install was cancelled */
+const INSTALL_CANCELLED = "Documentation scrape was cancelled";
+
+/** Marks `active` as the only active version of its documentation */
+const withActiveVersion = (docs: Documentation[], active: Documentation): Documentation[] =>
+  docs.map((doc) =>
+    doc.name === active.name ? { ...doc, isActive: doc.id === active.id } : doc,
+  );
+
 export const useDocsStore = create<DocsState>((set, get) => {
   if (typeof window !== "undefined") {
     listen<ScrapeProgress>("doc-install-progress", (event) => {
@@ -49,7 +62,7 @@ export const useDocsStore = create<DocsState>((set, get) => {
       console.log("[DocsStore] ✓ Install complete:", event.payload);
       set({ installProgress: null, isInstalling: false });
       const { installedDocs } = get();
-      set({ installedDocs: [...installedDocs, event.payload] });
+      set({ installedDocs: [...withActiveVersion(installedDocs, event.payload), event.payload] });
     }).catch((err) => {
       console.error("[DocsStore] Failed to listen to doc-install-complete:", err);
     });
@@ -61,6 +74,13 @@ export const useDocsStore = create<DocsState>((set, get) => {
       console.error("[DocsStore] Failed to listen to doc-install-error:", err);
     });
 
+    listen<string>("doc-install-cancelled", (event) => {
+      console.log("[DocsStore] ⏹ Install cancelled:", event.payload);
+      set({ installProgress: null, isInstalling: false });
+    }).catch((err) => {
+      console.error("[DocsStore] Failed to listen to doc-install-cancelled:", err);
+    });
+
     listen<ScrapeProgress>("doc-update-progress", (event) => {
       console.log("[DocsStore] 📊 Update progress:", event.payload);
       set({ updateProgress: event.payload });
@@ -68,13 +88,12 @@ export const useDocsStore = create<DocsState>((set, get) => {
       console.error("[DocsStore] Failed to listen to doc-update-progress:", err);
     });
 
-    listen<Documentation>("doc-update-complete", (event) => {
-      console.log("[DocsStore] ✓ Update complete:", event.payload);
+    listen<DocumentationUpdate>("doc-update-complete", (event) => {
+      const { summary, ...updatedDoc } = event.payload;
+      console.log("[DocsStore] ✓ Update complete:", updatedDoc, summary);
       set({ updateProgress: null });
       const { installedDocs } = get();
-      const newDocs = installedDocs.map((doc) =>
-        doc.id === event.payload.id ? event.payload : doc,
-      );
+      const newDocs = installedDocs.map((doc) => (doc.id === updatedDoc.id ? updatedDoc : doc));
       set({ installedDocs: newDocs });
     }).catch((err) => {
       console.error("[DocsStore] Failed to listen to doc-update-complete:", err);
@@ -131,20 +150,25 @@ export const useDocsStore = create<DocsState>((set, get) => {
       }
     },
 
-    installDoc: async (name: string) => {
-      console.log(`[DocsStore] 📥 Starting installation of '${name}'`);
+    installDoc: async (name: string, version?: string) => {
+      console.log(`[DocsStore] 📥 Starting installation of '${name}' (${version ?? "default"})`);
       set({ isInstalling: true, error: null, installProgress: null });
       try {
         console.log(`[DocsStore] → Calling Tauri backend...`);
-        const doc = await tauriService.installDocumentation(name);
+        const doc = await tauriService.installDocumentation(name, version);
         console.log(`[DocsStore] ✓ Installation successful:`, doc);
 
         const { installedDocs } = get();
         if (!installedDocs.some((d) => d.id === doc.id)) {
-          set({ installedDocs: [...installedDocs, doc] });
+          set({ installedDocs: [...withActiveVersion(installedDocs, doc), doc] });
         }
         console.log(`[DocsStore] ✓ State updated, now ${installedDocs.length + 1} docs installed`);
       } catch (error) {
+        if (String(error) === INSTALL_CANCELLED) {
+          console.log(`[DocsStore] Installation of '${name}' cancelled, progress is kept`);
+          set({ installProgress: null });
+          return;
+        }
         console.error(`[DocsStore] ✗ Installation failed:`, error);
         set({ error: (error as Error).message, installProgress: null });
         throw error;
@@ -154,13 +178,35 @@ export const useDocsStore = create<DocsState>((set, get) => {
       }
     },
 
+    cancelInstall: async (name: string) => {
+      try {
+        await tauriService.cancelDocInstall(name);
+      } catch (error) {
+        console.error(`[DocsStore] ✗ Failed to cancel installation:`, error);
+      }
+    },
+
+    setActiveVersion: async (docId: number) => {
+      try {
+        const doc = await tauriService.setActiveDocVersion(docId);
+        const { installedDocs, selectedDoc } = get();
+        set({
+          installedDocs: withActiveVersion(installedDocs, doc),
+          selectedDoc: selectedDoc?.name === doc.name ? doc : selectedDoc,
+        });
+      } catch (error) {
+        console.error(`[DocsStore] ✗ Failed to switch version:`, error);
+        set({ error: (error as Error).message });
+      }
+    },
+
     updateDoc: async (docId: number) => {
       console.log(`[DocsStore] 🔄 Starting update for doc_id: ${docId}`);
       set({ isLoading: true, error: null, updateProgress: null });
       try {
         console.log(`[DocsStore] → Calling Tauri backend to update...`);
-        const updatedDoc = await tauriService.updateDocumentation(docId);
-        console.log(`[DocsStore] ✓ Update successful:`, updatedDoc);
+        const { summary, ...updatedDoc } = await tauriService.updateDocumentation(docId);
+        console.log(`[DocsStore] ✓ Update successful:`, updatedDoc, summary);
 
         const { installedDocs } = get();
         const newDocs = installedDocs.map((doc) => (doc.id === docId ? updatedDoc : doc));
@@ -176,6 +222,24 @@ export const useDocsStore = create<DocsState>((set, get) => {
       }
     },
 
+    rebuildDocFromCache: async (docId: number) => {
+      console.log(`[DocsStore] ♻ Rebuilding doc_id ${docId} from cache`);
+      set({ isLoading: true, error: null, updateProgress: null });
+      try {
+        const { summary, ...rebuiltDoc } = await tauriService.rebuildDocumentationFromCache(docId);
+        console.log(`[DocsStore] ✓ Rebuild successful:`, rebuiltDoc, summary);
+
+        const { installedDocs } = get();
+        set({ installedDocs: installedDocs.map((doc) => (doc.id === docId ? rebuiltDoc : doc)) });
+      } catch (error) {
+        console.error(`[DocsStore] ✗ Rebuild failed:`, error);
+        set({ error: (error as Error).message, updateProgress: null });
+        throw error;
+      } finally {
+        set({ isLoading: false });
+      }
+    },
+
     deleteDoc: async (docId: number) => {
       console.log(`[DocsStore] 🗑️  Starting deletion for doc_id: ${docId}`);
       set({ isLoading: true, error: null });
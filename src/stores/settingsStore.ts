@@ -13,6 +13,9 @@ interface SettingsState {
   updateConfig: (updater: (config: AppConfig) => AppConfig) => Promise<void>;
   updateSearchConfig: (config: Partial<AppConfig["search"]>) => Promise<void>;
   updateUiConfig: (config: Partial<AppConfig["ui"]>) => Promise<void>;
+  updateHistoryConfig: (config: Partial<AppConfig["history"]>) => Promise<void>;
+  updateTrashConfig: (config: Partial<AppConfig["trash"]>) => Promise<void>;
+  updateMarkdownSyncConfig: (config: Partial<AppConfig["markdown_sync"]>) => Promise<void>;
 }
 
 export const useSettingsStore = create<SettingsState>((set, get) => ({
@@ -61,6 +64,27 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
       ui: { ...prev.ui, ...uiConfig },
     }));
   },
+
+  updateHistoryConfig: async (historyConfig) => {
+    await get().updateConfig((prev) => ({
+      ...prev,
+      history: { ...prev.history, ...historyConfig },
+    }));
+  },
+
+  updateTrashConfig: async (trashConfig) => {
+    await get().updateConfig((prev) => ({
+      ...prev,
+      trash: { ...prev.trash, ...trashConfig },
+    }));
+  },
+
+  updateMarkdownSyncConfig: async (syncConfig) => {
+    await get().updateConfig((prev) => ({
+      ...prev,
+      markdown_sync: { ...prev.markdown_sync, ...syncConfig },
+    }));
+  },
 }));
 
 useSettingsStore.getState().loadConfig();
@@ -16,6 +16,80 @@ export interface Item {
   metadata: Record<string, unknown> | null;
 }
 
+export interface ItemRevision {
+  id: number;
+  itemId: number;
+  title: string;
+  description: string | null;
+  content: string;
+  metadata: Record<string, unknown> | null;
+  savedAt: number;
+  createdAt: number;
+}
+
+export type TrashKind = "item" | "documentation";
+
+export interface TrashEntry {
+  kind: TrashKind;
+  id: number;
+  title: string;
+  itemType: ItemType | null;
+  deletedAt: number;
+}
+
+export type VaultFormat = "json" | "markdown";
+
+export type ImportConflict = "skip" | "overwrite" | "duplicate";
+
+export type SnippetFormat = "vscode" | "jetbrains";
+
+export type SyncSide = "vault" | "file";
+
+export interface MarkdownSyncConflict {
+  itemId: number;
+  title: string;
+  path: string;
+  itemUpdatedAt: number;
+  fileModifiedAt: number;
+}
+
+export interface MarkdownSyncReport {
+  exported: number;
+  imported: number;
+  updatedFiles: number;
+  updatedItems: number;
+  deletedFiles: number;
+  deletedItems: number;
+  conflicts: MarkdownSyncConflict[];
+}
+
+export interface ConfigLink {
+  itemId: number;
+  path: string;
+}
+
+export interface ConfigDrift {
+  itemId: number;
+  title: string;
+  path: string;
+  missing: boolean;
+  fileChanged: boolean;
+  itemChanged: boolean;
+}
+
+export interface ConfigWriteBackPreview {
+  itemId: number;
+  path: string;
+  exists: boolean;
+  diff: string;
+}
+
+export interface VaultImportSummary {
+  created: number;
+  updated: number;
+  skipped: number;
+}
+
 export interface Tag {
   id: number;
   name: string;
@@ -32,6 +106,30 @@ export interface Documentation {
   installedAt: number;
   updatedAt: number;
   metadata?: Record<string, unknown>;
+  isActive: boolean;
+}
+
+export interface CustomDocSource {
+  name: string;
+  displayName: string;
+  kind: "scraper" | "github";
+  file: string;
+}
+
+export interface CustomDocSourcesReport {
+  sources: CustomDocSource[];
+  errors: { file: string; message: string }[];
+}
+
+export interface DocUpdateSummary {
+  added: number;
+  changed: number;
+  removed: number;
+  unchanged: number;
+}
+
+export interface DocumentationUpdate extends Documentation {
+  summary: DocUpdateSummary;
 }
 
 export interface DocEntry {
@@ -49,6 +147,7 @@ export interface AvailableDocumentation {
   name: string;
   displayName: string;
   version: string;
+  availableVersions: string[];
   description: string;
   sourceUrl: string;
 }
@@ -112,11 +211,17 @@ export interface SearchQuery {
   tagIds?: number[];
   limit?: number;
   offset?: number;
+  cursor?: string;
+  docIds?: number[];
+  pathPrefix?: string;
 }
 
 export interface SearchResult {
   items: ItemWithTags[];
   total: number;
+  hasMore: boolean;
+  nextCursor?: string | null;
+  correctedQuery?: string | null;
 }
 
 export interface SearchConfig {
@@ -134,9 +239,27 @@ export interface UiConfig {
   markdown_live_preview: boolean;
 }
 
+export interface HistoryConfig {
+  max_revisions_per_item: number;
+  max_age_days: number;
+}
+
+export interface TrashConfig {
+  retention_days: number;
+}
+
+export interface MarkdownSyncConfig {
+  enabled: boolean;
+  directory: string;
+  interval_secs: number;
+}
+
 export interface AppConfig {
   search: SearchConfig;
   ui: UiConfig;
+  history: HistoryConfig;
+  trash: TrashConfig;
+  markdown_sync: MarkdownSyncConfig;
 }
 
 export interface McpServerConfig {
@@ -152,11 +275,29 @@ export interface AiToolStatus {
   detected: boolean;
   configPath?: string;
   mcpInstalled: boolean;
+  mcpOutdated: boolean;
   supportsAutoConnect: boolean;
   statusMessage?: string;
 }
 
-export type ScrapeStatus = "starting" | "scraping" | "processing" | "completed" | "failed";
+export type ScrapeStatus =
+  | "starting"
+  | "scraping"
+  | "processing"
+  | "completed"
+  | "failed"
+  | "cancelled";
+
+export interface DocInstallCheckpoint {
+  name: string;
+  version: string;
+  status: "running" | "cancelled" | "failed";
+  visitedCount: number;
+  queuedCount: number;
+  entriesCount: number;
+  startedAt: number;
+  updatedAt: number;
+}
 
 export interface ScrapeProgress {
   currentPage: number;